@@ -311,6 +311,18 @@ fn can_model_linearizable_register() {
         Deliver { src: Id::from(1), dst: Id::from(0), msg: Internal(AckRecord(6)) },
     ]);
     assert_eq!(checker.unique_state_count(), 544);
+
+    // DPOR does not cache states, so a single client keeps the search small.
+    let checker = AbdModelCfg {
+        client_count: 1,
+        server_count: 2,
+        network: Network::new_unordered_nonduplicating([]),
+    }
+    .into_model()
+    .checker()
+    .spawn_dpor()
+    .join();
+    checker.assert_properties();
 }
 
 fn main() -> Result<(), pico_args::Error> {
@@ -347,6 +347,18 @@ fn can_model_paxos() {
         Deliver { src: 4.into(), dst: 2.into(), msg: Get(8) }
     ]);
     assert_eq!(checker.unique_state_count(), 16_668);
+
+    // DPOR
+    let checker = PaxosModelCfg {
+        client_count: 2,
+        server_count: 3,
+        network: Network::new_unordered_nonduplicating([]),
+    }
+    .into_model()
+    .checker()
+    .spawn_dpor()
+    .join();
+    checker.assert_properties();
 }
 
 fn main() -> Result<(), pico_args::Error> {
@@ -109,6 +109,18 @@ fn can_model_single_copy_register() {
     ]);
     assert_eq!(checker.unique_state_count(), 93);
 
+    // DPOR reaches the same discoveries via fewer interleavings.
+    let checker = SingleCopyModelCfg {
+        client_count: 2,
+        server_count: 1,
+        network: Network::new_unordered_nonduplicating([]),
+    }
+    .into_model()
+    .checker()
+    .spawn_dpor()
+    .join();
+    checker.assert_properties();
+
     // Otherwise (if more than one server) then not linearizabile. BFS this time.
     let checker = SingleCopyModelCfg {
         client_count: 2,
@@ -124,3 +124,38 @@ pub mod ping_pong {
         }
     }
 }
+
+/// An actor that broadcasts a message to every other actor, which count the messages they
+/// receive. Deliveries to distinct actors are independent.
+pub mod broadcast {
+    use crate::actor::*;
+    use crate::*;
+
+    pub struct Broadcaster;
+
+    impl Actor for Broadcaster {
+        type Msg = ();
+        type State = u8;
+        type Timer = ();
+
+        fn on_start(&self, id: Id, o: &mut Out<Self>) -> Self::State {
+            if id == Id::from(0) {
+                o.broadcast(&[1.into(), 2.into(), 3.into()], &());
+            }
+            0
+        }
+
+        fn on_msg(&self, _: Id, state: &mut Cow<Self::State>, _: Id, _: (), _: &mut Out<Self>) {
+            *state.to_mut() += 1;
+        }
+    }
+
+    pub fn broadcast_model() -> ActorModel<Broadcaster> {
+        ActorModel::new((), ())
+            .actors((0..4).map(|_| Broadcaster))
+            .init_network(Network::new_unordered_nonduplicating([]))
+            .property(Expectation::Always, "received once", |_, s| {
+                s.actor_states.iter().all(|s| **s <= 1)
+            })
+    }
+}
@@ -3,7 +3,7 @@
 use crate::actor::{
     is_no_op, is_no_op_with_timer, Actor, ActorModelState, Command, Envelope, Id, Network, Out,
 };
-use crate::{Expectation, Model, Path, Property};
+use crate::{fingerprint, Expectation, Fairness, Ltl, Model, Path, Property};
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fmt::{Debug, Display, Formatter};
@@ -32,10 +32,15 @@ where
     pub lossy_network: LossyNetwork,
     /// Maximum number of actors that can be contemporarily crashed
     pub max_crashes: usize,
+    /// Fairness constraints for kinds of [`ActorModelAction`]. See [`Model::fairness`].
+    pub fairness: Vec<(ActorModelActionKind, Fairness)>,
     pub properties: Vec<Property<ActorModel<A, C, H>>>,
-    pub record_msg_in: fn(cfg: &C, history: &H, envelope: Envelope<&A::Msg>) -> Option<H>,
-    pub record_msg_out: fn(cfg: &C, history: &H, envelope: Envelope<&A::Msg>) -> Option<H>,
-    pub within_boundary: fn(cfg: &C, state: &ActorModelState<A, H>) -> bool,
+    #[allow(clippy::type_complexity)]
+    pub record_msg_in: Arc<dyn Fn(&C, &H, Envelope<&A::Msg>) -> Option<H> + Send + Sync>,
+    #[allow(clippy::type_complexity)]
+    pub record_msg_out: Arc<dyn Fn(&C, &H, Envelope<&A::Msg>) -> Option<H> + Send + Sync>,
+    #[allow(clippy::type_complexity)]
+    pub within_boundary: Arc<dyn Fn(&C, &ActorModelState<A, H>) -> bool + Send + Sync>,
 }
 
 /// Indicates possible steps that an actor system can take as it evolves.
@@ -54,6 +59,18 @@ pub enum ActorModelAction<Msg, Timer> {
     Crash(Id),
 }
 
+/// Indicates a kind of [`ActorModelAction`] to which a [`Fairness`] constraint can apply, such that
+/// each message or timer of that kind is treated fairly.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ActorModelActionKind {
+    /// Applies to [`ActorModelAction::Deliver`], e.g. weak fairness indicates that every
+    /// continuously deliverable message is eventually delivered.
+    Deliver,
+    /// Applies to [`ActorModelAction::Timeout`], e.g. weak fairness indicates that every
+    /// continuously set timer eventually fires.
+    Timeout,
+}
+
 /// Indicates whether the network loses messages. Note that as long as invariants do not check
 /// the network state, losing a message is indistinguishable from an unlimited delay, so in
 /// many cases you can improve model checking performance by not modeling message loss.
@@ -93,10 +110,11 @@ where
             init_network: Network::new_unordered_duplicating([]),
             lossy_network: LossyNetwork::No,
             max_crashes: 0,
+            fairness: Vec::new(),
             properties: Default::default(),
-            record_msg_in: |_, _, _| None,
-            record_msg_out: |_, _, _| None,
-            within_boundary: |_, _| true,
+            record_msg_in: Arc::new(|_, _, _| None),
+            record_msg_out: Arc::new(|_, _, _| None),
+            within_boundary: Arc::new(|_, _| true),
         }
     }
 
@@ -132,29 +150,51 @@ where
         self
     }
 
+    /// Requires that infinite behaviors treat actions of the specified kind fairly, replacing any
+    /// constraint previously specified for that kind. Only [`CheckerBuilder::spawn_liveness`]
+    /// considers fairness.
+    ///
+    /// [`CheckerBuilder::spawn_liveness`]: crate::CheckerBuilder::spawn_liveness
+    pub fn fairness(mut self, kind: ActorModelActionKind, fairness: Fairness) -> Self {
+        self.fairness.retain(|(k, _)| *k != kind);
+        self.fairness.push((kind, fairness));
+        self
+    }
+
     /// Adds a [`Property`] to this model.
-    #[allow(clippy::type_complexity)]
     pub fn property(
         mut self,
         expectation: Expectation,
-        name: &'static str,
-        condition: fn(&ActorModel<A, C, H>, &ActorModelState<A, H>) -> bool,
+        name: impl Into<String>,
+        condition: impl Fn(&ActorModel<A, C, H>, &ActorModelState<A, H>) -> bool + Send + Sync + 'static,
     ) -> Self {
         self.properties.push(Property {
             expectation,
-            name,
-            condition,
+            name: name.into(),
+            condition: Some(Arc::new(condition)),
+            formula: None,
+            step_condition: None,
         });
         self
     }
 
+    /// Adds an [`Ltl`] [`Property`] to this model. See [`Property::ltl`].
+    pub fn ltl_property(
+        mut self,
+        name: impl Into<String>,
+        formula: Ltl<ActorModel<A, C, H>>,
+    ) -> Self {
+        self.properties.push(Property::ltl(name, formula));
+        self
+    }
+
     /// Defines whether/how an incoming message contributes to relevant history. Returning
     /// `Some(new_history)` updates the relevant history, while `None` does not.
     pub fn record_msg_in(
         mut self,
-        record_msg_in: fn(cfg: &C, history: &H, Envelope<&A::Msg>) -> Option<H>,
+        record_msg_in: impl Fn(&C, &H, Envelope<&A::Msg>) -> Option<H> + Send + Sync + 'static,
     ) -> Self {
-        self.record_msg_in = record_msg_in;
+        self.record_msg_in = Arc::new(record_msg_in);
         self
     }
 
@@ -162,21 +202,69 @@ where
     /// `Some(new_history)` updates the relevant history, while `None` does not.
     pub fn record_msg_out(
         mut self,
-        record_msg_out: fn(cfg: &C, history: &H, Envelope<&A::Msg>) -> Option<H>,
+        record_msg_out: impl Fn(&C, &H, Envelope<&A::Msg>) -> Option<H> + Send + Sync + 'static,
     ) -> Self {
-        self.record_msg_out = record_msg_out;
+        self.record_msg_out = Arc::new(record_msg_out);
         self
     }
 
     /// Indicates whether a state is within the state space that should be model checked.
     pub fn within_boundary(
         mut self,
-        within_boundary: fn(cfg: &C, state: &ActorModelState<A, H>) -> bool,
+        within_boundary: impl Fn(&C, &ActorModelState<A, H>) -> bool + Send + Sync + 'static,
     ) -> Self {
-        self.within_boundary = within_boundary;
+        self.within_boundary = Arc::new(within_boundary);
         self
     }
 
+    /// Indicates whether an action would record history if taken in a particular state. Unlike
+    /// [`Model::next_state`], this does not require the action to be enabled in the state.
+    fn records_history(
+        &self,
+        state: &ActorModelState<A, H>,
+        action: &ActorModelAction<A::Msg, A::Timer>,
+    ) -> bool {
+        let (id, out) = match action {
+            ActorModelAction::Deliver { src, dst: id, msg } => {
+                let index = usize::from(*id);
+                if index >= self.actors.len() || state.crashed[index] {
+                    return false;
+                }
+                let env = Envelope {
+                    src: *src,
+                    dst: *id,
+                    msg,
+                };
+                if (self.record_msg_in)(&self.cfg, &state.history, env).is_some() {
+                    return true;
+                }
+                let mut actor_state = Cow::Borrowed(&*state.actor_states[index]);
+                let mut out = Out::new();
+                self.actors[index].on_msg(*id, &mut actor_state, *src, msg.clone(), &mut out);
+                (*id, out)
+            }
+            ActorModelAction::Timeout(id, timer) => {
+                let index = usize::from(*id);
+                let mut actor_state = Cow::Borrowed(&*state.actor_states[index]);
+                let mut out = Out::new();
+                self.actors[index].on_timeout(*id, &mut actor_state, timer, &mut out);
+                (*id, out)
+            }
+            ActorModelAction::Drop(_) | ActorModelAction::Crash(_) => return false,
+        };
+        out.iter().any(|c| match c {
+            Command::Send(dst, msg) => {
+                let env = Envelope {
+                    src: id,
+                    dst: *dst,
+                    msg,
+                };
+                (self.record_msg_out)(&self.cfg, &state.history, env).is_some()
+            }
+            _ => false,
+        })
+    }
+
     /// Updates the actor state, sends messages, and configures the timers.
     fn process_commands(&self, id: Id, commands: Out<A>, state: &mut ActorModelState<A, H>) {
         let index = usize::from(id);
@@ -646,6 +734,44 @@ where
     fn within_boundary(&self, state: &Self::State) -> bool {
         (self.within_boundary)(&self.cfg, state)
     }
+
+    fn fairness(&self, action: &Self::Action) -> Option<(Fairness, u64)> {
+        let kind = match action {
+            ActorModelAction::Deliver { .. } => ActorModelActionKind::Deliver,
+            ActorModelAction::Timeout(..) => ActorModelActionKind::Timeout,
+            ActorModelAction::Drop(_) | ActorModelAction::Crash(_) => return None,
+        };
+        self.fairness
+            .iter()
+            .find(|(k, _)| *k == kind)
+            .map(|(_, fairness)| (*fairness, fingerprint(action).get()))
+    }
+
+    /// Actions are independent if they involve distinct actors and neither records history,
+    /// which is shared by all actors. A dropped message involves its sender in addition to its
+    /// recipient, as the sender could send an identical message, which a duplicating network does
+    /// not distinguish. Crashes are never independent of one another, as crashing one actor can
+    /// prevent another from crashing. Properties are assumed not to distinguish the order in
+    /// which distinct actors take steps.
+    fn independent(&self, state: &Self::State, a1: &Self::Action, a2: &Self::Action) -> bool {
+        fn actors<Msg, Timer>(action: &ActorModelAction<Msg, Timer>) -> [Id; 2] {
+            match action {
+                ActorModelAction::Deliver { dst, .. } => [*dst, *dst],
+                ActorModelAction::Drop(env) => [env.dst, env.src],
+                ActorModelAction::Timeout(id, _) | ActorModelAction::Crash(id) => [*id, *id],
+            }
+        }
+        if actors(a1).iter().any(|id| actors(a2).contains(id)) {
+            return false;
+        }
+        if matches!(
+            (a1, a2),
+            (ActorModelAction::Crash(_), ActorModelAction::Crash(_))
+        ) {
+            return false;
+        }
+        !self.records_history(state, a1) && !self.records_history(state, a2)
+    }
 }
 
 #[cfg(test)]
@@ -910,6 +1036,46 @@ mod test {
         );
     }
 
+    #[test]
+    fn can_capture_configuration_in_closures() {
+        let boundary = 3;
+        let checker = (1..=boundary)
+            .fold(
+                PingPongCfg {
+                    max_nat: 5,
+                    maintains_history: false,
+                }
+                .into_model()
+                .within_boundary(move |_, state| {
+                    state.actor_states.iter().all(|count| **count <= boundary)
+                }),
+                |model, n| {
+                    model.property(
+                        Expectation::Sometimes,
+                        format!("reaches {}", n),
+                        move |_, state| state.actor_states.iter().any(|count| **count == n),
+                    )
+                },
+            )
+            .lossy_network(LossyNetwork::No)
+            .checker()
+            .spawn_bfs()
+            .join();
+        assert_eq!(checker.unique_state_count(), 7);
+        checker.assert_no_discovery("can reach max");
+        for n in 1..=boundary {
+            assert!(checker.discovery(&format!("reaches {}", n)).is_some());
+        }
+        assert_eq!(
+            checker
+                .discovery("reaches 3")
+                .unwrap()
+                .last_state()
+                .actor_states,
+            vec![Arc::new(2), Arc::new(3)]
+        );
+    }
+
     #[test]
     fn might_never_reach_beyond_max() {
         // ^ and in fact will never. This is a subtle distinction: we're exercising a
@@ -1163,6 +1329,96 @@ mod test {
             2
         );
     }
+
+    #[derive(Clone)]
+    enum FairnessActor {
+        Ticker,
+        Sender(Id),
+        Receiver,
+        Sleeper,
+    }
+    impl Actor for FairnessActor {
+        type State = u8;
+        type Msg = ();
+        type Timer = ();
+        fn on_start(&self, _: Id, o: &mut Out<Self>) -> Self::State {
+            match self {
+                FairnessActor::Ticker | FairnessActor::Sleeper => o.set_timer((), model_timeout()),
+                FairnessActor::Sender(dst) => o.send(*dst, ()),
+                FairnessActor::Receiver => {}
+            }
+            0
+        }
+        fn on_msg(&self, _: Id, state: &mut Cow<Self::State>, _: Id, _: (), _: &mut Out<Self>) {
+            *state.to_mut() = 1;
+        }
+        fn on_timeout(&self, _: Id, state: &mut Cow<Self::State>, _: &(), o: &mut Out<Self>) {
+            *state.to_mut() = 1 - **state;
+            if let FairnessActor::Ticker = self {
+                o.set_timer((), model_timeout());
+            }
+        }
+    }
+
+    fn fairness_model(actors: Vec<FairnessActor>) -> ActorModel<FairnessActor> {
+        ActorModel::new((), ())
+            .actors(actors)
+            .init_network(Network::new_unordered_nonduplicating([]))
+            .property(Expectation::Eventually, "progress", |_, s| {
+                s.actor_states.iter().skip(1).any(|s| **s == 1)
+            })
+    }
+
+    #[test]
+    fn fair_delivery_prevents_starvation() {
+        let actors = vec![
+            FairnessActor::Ticker,
+            FairnessActor::Sender(Id::from(2)),
+            FairnessActor::Receiver,
+        ];
+        let checker = fairness_model(actors.clone())
+            .checker()
+            .spawn_liveness()
+            .join();
+        assert_eq!(
+            checker.discovery("progress").unwrap().into_actions(),
+            vec![Timeout(Id::from(0), ()), Timeout(Id::from(0), ())]
+        );
+
+        fairness_model(actors.clone())
+            .fairness(ActorModelActionKind::Timeout, Fairness::Weak)
+            .checker()
+            .spawn_liveness()
+            .join()
+            .assert_discovery(
+                "progress",
+                vec![Timeout(Id::from(0), ()), Timeout(Id::from(0), ())],
+            );
+
+        fairness_model(actors)
+            .fairness(ActorModelActionKind::Deliver, Fairness::Weak)
+            .checker()
+            .spawn_liveness()
+            .join()
+            .assert_properties();
+    }
+
+    #[test]
+    fn fair_timeouts_prevent_starvation() {
+        let actors = vec![FairnessActor::Ticker, FairnessActor::Sleeper];
+        let checker = fairness_model(actors.clone())
+            .checker()
+            .spawn_liveness()
+            .join();
+        assert_eq!(checker.discovery("progress").unwrap().loop_start(), Some(0));
+
+        fairness_model(actors)
+            .fairness(ActorModelActionKind::Timeout, Fairness::Weak)
+            .checker()
+            .spawn_liveness()
+            .join()
+            .assert_properties();
+    }
 }
 
 #[cfg(test)]
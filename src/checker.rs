@@ -1,31 +1,54 @@
 //! Private module for selective re-export.
 
+mod best_first;
 mod bfs;
+mod bitstate;
+mod checkpoint;
+mod control;
 mod dfs;
+mod discoveries;
+mod disk;
+mod distributed;
+mod dpor;
 mod explorer;
+mod graph;
+mod limits;
+mod liveness;
+mod ltl;
 mod on_demand;
 mod path;
+mod por;
+mod refinement;
 mod representative;
 mod rewrite;
 mod rewrite_plan;
+mod shrink;
 mod simulation;
+mod swarm;
+mod trace;
 mod visitor;
 
 use crate::report::{ReportData, ReportDiscovery, Reporter};
-use crate::{Expectation, Fingerprint, Model};
+use crate::{Expectation, Fingerprint, Model, Property};
 use std::collections::{BTreeMap, HashMap};
 use std::fmt::{Debug, Display};
 use std::hash::Hash;
 use std::num::NonZeroUsize;
 use std::sync::{Arc, Mutex};
 use std::thread::JoinHandle;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
+pub use control::CheckerControl;
+pub use graph::{GraphFormat, GraphRecorder, StateGraph};
+pub use limits::Limit;
+pub use ltl::Ltl;
 pub use path::*;
+pub use refinement::Refinement;
 pub use representative::*;
 pub use rewrite::*;
 pub use rewrite_plan::*;
 pub use simulation::{Chooser, UniformChooser};
+pub use trace::{Observation, TraceMismatch, TraceValidator};
 pub use visitor::*;
 
 #[derive(Clone, Copy)]
@@ -63,21 +86,43 @@ impl Display for DiscoveryClassification {
               Consider calling spawn_bfs() or spawn_dfs()."]
 pub struct CheckerBuilder<M: Model> {
     model: M,
+    bitstate: Option<(usize, usize)>,
+    checkpoint: Option<checkpoint::CheckpointOptions<M::State>>,
+    deadlock_detection: bool,
+    disk: Option<disk::DiskOptions<M::State>>,
+    max_discoveries_per_property: usize,
+    partial_order_reduction: bool,
+    resume: Option<checkpoint::ResumeOptions<M::State>>,
+    shrink: Option<shrink::Shrink<M>>,
     #[allow(clippy::type_complexity)]
     symmetry: Option<fn(&M::State) -> M::State>,
     target_state_count: Option<NonZeroUsize>,
     target_max_depth: Option<NonZeroUsize>,
+    target_duration: Option<Duration>,
+    target_memory: Option<usize>,
     thread_count: usize,
+    track_eventually_bits: bool,
     visitor: Option<Box<dyn CheckerVisitor<M> + Send + Sync>>,
 }
 impl<M: Model> CheckerBuilder<M> {
     pub(crate) fn new(model: M) -> Self {
         Self {
             model,
+            bitstate: None,
+            checkpoint: None,
+            deadlock_detection: false,
+            disk: None,
+            max_discoveries_per_property: 1,
+            partial_order_reduction: false,
+            resume: None,
+            shrink: None,
             target_state_count: None,
             target_max_depth: None,
+            target_duration: None,
+            target_memory: None,
             symmetry: None,
             thread_count: 1,
+            track_eventually_bits: false,
             visitor: None,
         }
     }
@@ -162,6 +207,29 @@ impl<M: Model> CheckerBuilder<M> {
         bfs::BfsChecker::spawn(self)
     }
 
+    /// Spawns a best-first search model checker, which checks states in order of the priority
+    /// that the `heuristic` assigns them, highest first, and states of equal priority in
+    /// breadth-first order. A heuristic that favors suspicious states, such as those with many
+    /// messages in flight or replicas that disagree, can lead to discoveries far sooner than a
+    /// blind search. As with [`CheckerBuilder::spawn_bfs`], the predecessor of each generated
+    /// state is remembered so that paths can be reconstructed, but paths are not necessarily the
+    /// shortest.
+    ///
+    /// Checking is single threaded, and [`CheckerBuilder::threads`] and
+    /// [`CheckerBuilder::partial_order_reduction`] are ignored.
+    ///
+    /// This call does not block the current thread. Call [`Checker::join`] to block until checking
+    /// completes.
+    #[must_use = "Checkers run on background threads. \
+                  Consider calling join() or report(...), for example."]
+    pub fn spawn_best_first(self, heuristic: fn(&M, &M::State) -> i64) -> impl Checker<M>
+    where
+        M: Model + Send + Sync + 'static,
+        M::State: Hash + Send + Sync + 'static,
+    {
+        best_first::BestFirstChecker::spawn(self, heuristic)
+    }
+
     /// Spawns an on-demand model checker. This traversal strategy doesn't compute any states until
     /// it is asked to, useful for lightweight exploration. Internally the exploration strategy is
     /// very similar to that of [`CheckerBuilder::spawn_bfs`].
@@ -194,6 +262,98 @@ impl<M: Model> CheckerBuilder<M> {
         dfs::DfsChecker::spawn(self)
     }
 
+    /// Spawns a depth-first search model checker that applies dynamic partial order reduction
+    /// (DPOR), exploring only one interleaving of steps that [`Model::independent`] indicates
+    /// commute. Steps that change the outcome of a property's condition are never reordered, so
+    /// the same properties are discovered as with [`CheckerBuilder::spawn_dfs`], although perhaps
+    /// via different paths.
+    ///
+    /// States are not cached, so the same state may be visited via several paths, and a path
+    /// that returns to a state along it is cut short. This strategy is therefore best suited to
+    /// models whose paths are acyclic, which can be ensured with a boundary or
+    /// [`CheckerBuilder::target_max_depth`]. Checking is single threaded, and
+    /// [`CheckerBuilder::threads`] and [`CheckerBuilder::symmetry`] are ignored.
+    ///
+    /// This call does not block the current thread. Call [`Checker::join`] to block until
+    /// checking completes.
+    #[must_use = "Checkers run on background threads. \
+                  Consider calling join() or report(...), for example."]
+    pub fn spawn_dpor(self) -> impl Checker<M>
+    where
+        M: Model + Send + Sync + 'static,
+        M::State: Hash + Send + Sync + 'static,
+        M::Action: Clone + PartialEq,
+    {
+        dpor::DporChecker::spawn(self)
+    }
+
+    /// Spawns a model checker that coordinates `worker_count` worker processes, which connect to
+    /// the `listener` via [`CheckerBuilder::run_worker`]. Each worker checks the states whose
+    /// fingerprints fall within its part of the fingerprint space and sends the states that it
+    /// generates for other workers to this coordinator, which forwards them, so checking can span
+    /// processes and machines. Counts and discoveries are reported by the returned checker.
+    ///
+    /// States are sent as JSON. Each worker remembers the predecessor of each state that it
+    /// checks, and the paths to discoveries are reconstructed from those once checking is done,
+    /// so discoveries only become available then. Paths are not necessarily the shortest, as
+    /// workers proceed independently. Checking begins once every worker has connected. The
+    /// workers apply their own [`CheckerBuilder::deadlock_detection`],
+    /// [`CheckerBuilder::max_discoveries_per_property`], [`CheckerBuilder::symmetry`],
+    /// [`CheckerBuilder::target_max_depth`], and [`CheckerBuilder::track_eventually_bits`], which
+    /// should match the coordinator's. [`CheckerBuilder::threads`] is ignored, and this call as
+    /// well as [`CheckerBuilder::run_worker`] panic if any other option is set.
+    ///
+    /// This call does not block the current thread. Call [`Checker::join`] to block until
+    /// checking completes.
+    #[must_use = "Checkers run on background threads. \
+                  Consider calling join() or report(...), for example."]
+    pub fn spawn_distributed(
+        self,
+        listener: std::net::TcpListener,
+        worker_count: usize,
+    ) -> impl Checker<M>
+    where
+        M: Model + Send + Sync + 'static,
+        M::State: Hash + Send + serde::Serialize + serde::de::DeserializeOwned + 'static,
+    {
+        distributed::DistributedChecker::spawn(self, listener, worker_count)
+    }
+
+    /// Runs a worker for a checker started via [`CheckerBuilder::spawn_distributed`] at the
+    /// `coordinator` address, blocking the current thread until checking is done. The model must
+    /// be the same as the coordinator's.
+    pub fn run_worker(self, coordinator: impl std::net::ToSocketAddrs) -> std::io::Result<()>
+    where
+        M::State: Hash + serde::Serialize + serde::de::DeserializeOwned,
+    {
+        distributed::run_worker(self, coordinator)
+    }
+
+    /// Spawns a model checker that retains the full state graph so that [`eventually`] properties
+    /// can be judged against infinite behaviors in addition to terminating ones. A behavior that
+    /// cycles forever without satisfying an `eventually` property is reported as a "lasso" shaped
+    /// [`Path`], for which [`Path::loop_start`] indicates where the repeating loop begins. Cycles
+    /// that violate the model's [`Model::fairness`] constraints are not reported.
+    ///
+    /// The state graph is explored in breadth-first order on a single thread, and the cycle
+    /// analysis only begins once exploration completes, so `eventually` discoveries are not
+    /// available until then. [`CheckerBuilder::threads`] and [`CheckerBuilder::symmetry`] are
+    /// ignored.
+    ///
+    /// This call does not block the current thread. Call [`Checker::join`] to block until
+    /// checking completes.
+    ///
+    /// [`eventually`]: crate::Property::eventually
+    #[must_use = "Checkers run on background threads. \
+                  Consider calling join() or report(...), for example."]
+    pub fn spawn_liveness(self) -> impl Checker<M>
+    where
+        M: Model + Send + Sync + 'static,
+        M::State: Hash + Send + Sync + 'static,
+    {
+        liveness::LivenessChecker::spawn(self)
+    }
+
     /// Spawns a simulation model checker. This repeatedly traverses the model from initial states
     /// to a terminal state. This aims to provide faster coverage of deep states for models that
     /// cannot practically be checked exhaustively.
@@ -208,11 +368,235 @@ impl<M: Model> CheckerBuilder<M> {
         M::State: Hash + Send + Sync + 'static,
         C: Chooser<M>,
     {
-        simulation::SimulationChecker::spawn::<C>(self, seed, chooser)
+        simulation::SimulationChecker::spawn::<C>(self, seed, chooser, false)
+    }
+
+    /// Spawns a simulation model checker that only checks the trace that
+    /// [`CheckerBuilder::spawn_simulation`] generates from `seed` with the same `chooser`, such
+    /// as the trace of a discovery reported by [`Checker::discovery_seed`]. Each trace is
+    /// determined by its seed alone, so it is regenerated exactly regardless of
+    /// [`CheckerBuilder::threads`] or the machine, and a discovery seen elsewhere can be reproduced
+    /// and investigated locally.
+    ///
+    /// The trace is only reproduced if the model and the options that shape a trace also match
+    /// the original run: [`CheckerBuilder::target_max_depth`], [`CheckerBuilder::symmetry`] (or
+    /// [`CheckerBuilder::symmetry_fn`]) and the chooser. Otherwise the replayed trace may differ
+    /// from the one reported.
+    ///
+    /// This call does not block the current thread. Call [`Checker::join`] to block until
+    /// checking completes.
+    #[must_use = "Checkers run on background threads. \
+                  Consider calling join() or report(...), for example."]
+    pub fn replay<C>(self, seed: u64, chooser: C) -> impl Checker<M>
+    where
+        M: Model + Send + Sync + 'static,
+        M::State: Hash + Send + Sync + 'static,
+        C: Chooser<M>,
+    {
+        simulation::SimulationChecker::spawn::<C>(self, seed, chooser, true)
+    }
+
+    /// Spawns `instance_count` independent depth-first searches, which is known as "swarm
+    /// verification." Search `i` uses the seed `seed + i`, from which it derives the order in which
+    /// it tries actions (via [`UniformChooser`]), a depth bound between half and all of
+    /// [`CheckerBuilder::target_max_depth`], and the hash function for
+    /// [`CheckerBuilder::bitstate`] hashing. Searches run [`CheckerBuilder::threads`] at a time
+    /// and share their discoveries, so like [`CheckerBuilder::spawn_simulation`] this aims to
+    /// quickly find bugs deep within models that cannot practically be checked exhaustively, but
+    /// each search avoids revisiting states.
+    ///
+    /// Runs with the same `seed` search the same parts of the state space, so a different `seed`
+    /// can be used for each run to cover more of it, and the seed of the search that found a
+    /// discovery is available via [`Checker::discovery_seed`].
+    ///
+    /// Searches do not share the states that they visit, so
+    /// [`Checker::unique_state_count`] sums those of each search, and
+    /// [`Checker::omission_probability`] is that of the search with the highest.
+    ///
+    /// This call does not block the current thread. Call [`Checker::join`] to block until
+    /// checking completes.
+    #[must_use = "Checkers run on background threads. \
+                  Consider calling join() or report(...), for example."]
+    pub fn spawn_swarm(self, seed: u64, instance_count: usize) -> impl Checker<M>
+    where
+        M: Model + Send + Sync + 'static,
+        M::State: Hash + Send + Sync + 'static,
+    {
+        swarm::SwarmChecker::spawn(self, seed, instance_count, UniformChooser)
+    }
+
+    /// Enables bitstate hashing (also known as "supertrace"), which approximates the set of
+    /// visited states with an array of `bits` bits, `hash_count` of which are set for each visited
+    /// state. Checking then uses a bounded amount of memory for visited states, but a state that
+    /// has not been visited is occasionally mistaken for one that has, in which case it and any
+    /// states only reachable via it are omitted. [`Checker::omission_probability`] estimates the
+    /// chance of that happening to a newly reached state. SPIN, which popularized the technique,
+    /// defaults to 3 hashes.
+    ///
+    /// Honored by [`CheckerBuilder::spawn_dfs`] and [`CheckerBuilder::spawn_swarm`].
+    pub fn bitstate(self, bits: usize, hash_count: usize) -> Self {
+        Self {
+            bitstate: Some((bits, hash_count)),
+            ..self
+        }
+    }
+
+    /// Periodically saves the checker's progress to a file at `path`, every `interval`, so that a
+    /// later checker for the same model can continue from there via
+    /// [`CheckerBuilder::resume_from`]. The file holds the fingerprints of the generated states,
+    /// the states that are awaiting checking (serialized as JSON), the discoveries, and the
+    /// counters reported by [`Checker`]. Checker threads pause while a checkpoint is written, and
+    /// each checkpoint replaces the previous one only once complete.
+    ///
+    /// Honored by [`CheckerBuilder::spawn_bfs`], except in combination with
+    /// [`CheckerBuilder::disk_backed`].
+    pub fn checkpoint(self, path: impl Into<std::path::PathBuf>, interval: Duration) -> Self
+    where
+        M::State: serde::Serialize,
+    {
+        Self {
+            checkpoint: Some(checkpoint::CheckpointOptions {
+                path: path.into(),
+                interval,
+                serialize: |state| serde_json::to_vec(state).expect("Failed to serialize state"),
+            }),
+            ..self
+        }
+    }
+
+    /// Reports a discovery named `"deadlock"` for a reachable state that has no next states,
+    /// unless [`Model::valid_end_state`] indicates that the system may stop there. This saves
+    /// writing an `always` property that checks for actions, and the discovery is included in
+    /// [`Checker::properties`] as such a property.
+    ///
+    /// Honored by [`CheckerBuilder::spawn_bfs`], [`CheckerBuilder::spawn_best_first`],
+    /// [`CheckerBuilder::spawn_dfs`], [`CheckerBuilder::spawn_distributed`],
+    /// [`CheckerBuilder::spawn_liveness`], [`CheckerBuilder::spawn_on_demand`] (and therefore
+    /// [`CheckerBuilder::serve`], which labels deadlocked states),
+    /// [`CheckerBuilder::spawn_simulation`], and [`CheckerBuilder::spawn_swarm`].
+    ///
+    /// Panics if the model already has a property named `"deadlock"`.
+    pub fn deadlock_detection(self) -> Self {
+        assert!(
+            self.model.properties().iter().all(|p| p.name != DEADLOCK),
+            "The property name {:?} is reserved by deadlock_detection",
+            DEADLOCK
+        );
+        Self {
+            deadlock_detection: true,
+            ..self
+        }
+    }
+
+    /// Keeps the generated states and the states awaiting checking in files within `dir` when
+    /// they do not fit within roughly `memory_budget` bytes, which allows checking models whose
+    /// state spaces do not fit in memory. The budget does not account for heap memory owned by
+    /// states. States are serialized as JSON when written to a file, and paths to discoveries are
+    /// reconstructed by looking up each preceding state's fingerprint in the files. The directory
+    /// should not be shared by checkers that run concurrently.
+    ///
+    /// Honored by [`CheckerBuilder::spawn_bfs`].
+    pub fn disk_backed(self, dir: impl Into<std::path::PathBuf>, memory_budget: usize) -> Self
+    where
+        M::State: serde::Serialize + serde::de::DeserializeOwned,
+    {
+        Self {
+            disk: Some(disk::DiskOptions {
+                dir: dir.into(),
+                memory_budget,
+                serialize: |state| serde_json::to_vec(state).expect("Failed to serialize state"),
+                deserialize: |bytes| {
+                    serde_json::from_slice(bytes).expect("Failed to deserialize state")
+                },
+            }),
+            ..self
+        }
+    }
+
+    /// Collects up to `count` discoveries for each property rather than only the first, each
+    /// ending in a distinct state, so that more than one bug can be seen per run. Checking
+    /// continues until each property has that many discoveries or no states remain. The
+    /// discoveries are available via [`Checker::discoveries_all`], while
+    /// [`Checker::discoveries`] returns the first for each property. Panics if `count` is zero.
+    ///
+    /// Honored by [`CheckerBuilder::spawn_bfs`], [`CheckerBuilder::spawn_best_first`],
+    /// [`CheckerBuilder::spawn_dfs`], [`CheckerBuilder::spawn_distributed`],
+    /// [`CheckerBuilder::spawn_dpor`], [`CheckerBuilder::spawn_liveness`] (which nonetheless
+    /// reports a single behavior for each `eventually` or LTL property),
+    /// [`CheckerBuilder::spawn_on_demand`] (and therefore [`CheckerBuilder::serve`]),
+    /// [`CheckerBuilder::spawn_simulation`], and [`CheckerBuilder::spawn_swarm`].
+    pub fn max_discoveries_per_property(self, count: usize) -> Self {
+        assert!(count > 0, "At least one discovery per property is required");
+        Self {
+            max_discoveries_per_property: count,
+            ..self
+        }
+    }
+
+    /// Enables partial order reduction, which explores a single step from a state when that step
+    /// is [independent] of all others, so that fewer interleavings of commuting steps are
+    /// explored. The step must also leave every property's condition unchanged and lead to a state
+    /// that has not yet been visited. Safety properties are discovered as without the reduction,
+    /// although perhaps via different paths. This relies on an action that is independent of every
+    /// other enabled action also being independent of the actions that they enable.
+    ///
+    /// Honored by [`CheckerBuilder::spawn_bfs`] and [`CheckerBuilder::spawn_dfs`].
+    ///
+    /// [independent]: crate::Model::independent
+    pub fn partial_order_reduction(self) -> Self {
+        Self {
+            partial_order_reduction: true,
+            ..self
+        }
+    }
+
+    /// Continues checking from a file previously written due to [`CheckerBuilder::checkpoint`]
+    /// rather than from the model's initial states. The model must be the same as that of the
+    /// checker that wrote the file, as must any options that affect which states are considered
+    /// the same, such as [`CheckerBuilder::symmetry`]. Checking then ends with the same
+    /// discoveries and [`Checker::unique_state_count`] as if it had not been interrupted.
+    ///
+    /// Honored by [`CheckerBuilder::spawn_bfs`], except in combination with
+    /// [`CheckerBuilder::disk_backed`].
+    pub fn resume_from(self, path: impl Into<std::path::PathBuf>) -> Self
+    where
+        M::State: serde::de::DeserializeOwned,
+    {
+        Self {
+            resume: Some(checkpoint::ResumeOptions {
+                path: path.into(),
+                deserialize: |bytes| {
+                    serde_json::from_slice(bytes).expect("Failed to deserialize state")
+                },
+            }),
+            ..self
+        }
+    }
+
+    /// Minimizes each discovery before it is returned by [`Checker::discoveries`] (and therefore
+    /// before it is reported), as with [`Checker::shrunk_discovery`].
+    ///
+    /// Honored by [`CheckerBuilder::spawn_dfs`], [`CheckerBuilder::spawn_simulation`], and
+    /// [`CheckerBuilder::spawn_swarm`], whose discoveries tend to include irrelevant steps.
+    pub fn shrink_discoveries(self) -> Self
+    where
+        M::State: PartialEq,
+        M::Action: PartialEq,
+    {
+        Self {
+            shrink: Some(shrink::shrink::<M>),
+            ..self
+        }
     }
 
     /// Enables symmetry reduction. Requires the [model state] to implement [`Representative`].
     ///
+    /// Honored by [`CheckerBuilder::spawn_bfs`], [`CheckerBuilder::spawn_dfs`],
+    /// [`CheckerBuilder::spawn_on_demand`], and [`CheckerBuilder::spawn_simulation`]. Paths still
+    /// consist of the states that were actually visited rather than their representatives. The
+    /// explorer started by [`CheckerBuilder::serve`] additionally omits next states that are
+    /// symmetric to an earlier one.
+    ///
     /// [model state]: crate::Model::State
     pub fn symmetry(self) -> Self
     where
@@ -248,6 +632,51 @@ impl<M: Model> CheckerBuilder<M> {
         }
     }
 
+    /// Sets how long the checker should aim to run for, after which it stops and reports the
+    /// discoveries found so far. The time is measured from when the checker is spawned, and for
+    /// performance reasons the checker may exceed it slightly.
+    ///
+    /// Honored by [`CheckerBuilder::spawn_bfs`], [`CheckerBuilder::spawn_dfs`],
+    /// [`CheckerBuilder::spawn_liveness`] (which still analyzes the states explored so far),
+    /// [`CheckerBuilder::spawn_on_demand`], and [`CheckerBuilder::spawn_simulation`].
+    pub fn target_duration(self, duration: Duration) -> Self {
+        Self {
+            target_duration: Some(duration),
+            ..self
+        }
+    }
+
+    /// Sets the resident memory in bytes at which the checker should stop and report the
+    /// discoveries found so far. Memory is measured for the whole process and only on Linux, where
+    /// it is read from `/proc/self/status`; elsewhere this limit has no effect.
+    ///
+    /// Honored by [`CheckerBuilder::spawn_bfs`], [`CheckerBuilder::spawn_dfs`],
+    /// [`CheckerBuilder::spawn_liveness`] (which still analyzes the states explored so far),
+    /// [`CheckerBuilder::spawn_on_demand`], and [`CheckerBuilder::spawn_simulation`].
+    pub fn target_memory(self, bytes: usize) -> Self {
+        Self {
+            target_memory: Some(bytes),
+            ..self
+        }
+    }
+
+    /// Includes the `eventually` properties that have yet to be satisfied along a path in the
+    /// identity of each visited state. Without this, a state reached by two paths (a DAG join) is
+    /// only explored for the first path, so an [`eventually`] counterexample that requires the
+    /// second path can be missed. With this enabled the state is explored once per distinct set of
+    /// unsatisfied `eventually` properties, and [`Checker::unique_state_count`] counts those
+    /// (state, properties) pairs rather than states.
+    ///
+    /// Honored by [`CheckerBuilder::spawn_bfs`] and [`CheckerBuilder::spawn_dfs`].
+    ///
+    /// [`eventually`]: crate::Property::eventually
+    pub fn track_eventually_bits(self) -> Self {
+        Self {
+            track_eventually_bits: true,
+            ..self
+        }
+    }
+
     /// Sets the number of threads available for model checking. For maximum performance this
     /// should match the number of cores.
     pub fn threads(self, thread_count: usize) -> Self {
@@ -295,9 +724,47 @@ pub trait Checker<M: Model> {
     /// Indicates the maximum depth that has been explored.
     fn max_depth(&self) -> usize;
 
+    /// Estimates the probability that a state reached for the first time was mistaken for a
+    /// visited state and therefore omitted, or returns `None` if checking is exhaustive. Only
+    /// applicable to [`CheckerBuilder::bitstate`] hashing.
+    fn omission_probability(&self) -> Option<f64> {
+        None
+    }
+
+    /// Returns a handle for pausing, resuming, or cancelling this checker, or `None` if the
+    /// checker does not support such control. Supported by [`CheckerBuilder::spawn_bfs`],
+    /// [`CheckerBuilder::spawn_dfs`], [`CheckerBuilder::spawn_liveness`],
+    /// [`CheckerBuilder::spawn_on_demand`], and [`CheckerBuilder::spawn_simulation`].
+    fn control(&self) -> Option<CheckerControl> {
+        None
+    }
+
+    /// Indicates which limit ended checking, if checking stopped at one such as
+    /// [`CheckerBuilder::target_duration`] rather than completing.
+    fn limit_reached(&self) -> Option<Limit> {
+        None
+    }
+
+    /// Returns the properties that are being checked, which are those of the [`Model`] along with
+    /// one for [`CheckerBuilder::deadlock_detection`] if enabled.
+    fn properties(&self) -> Vec<Property<M>> {
+        self.model().properties()
+    }
+
     /// Returns a map from property name to corresponding "discovery" (indicated
     /// by a [`Path`]).
-    fn discoveries(&self) -> HashMap<&'static str, Path<M::State, M::Action>>;
+    fn discoveries(&self) -> HashMap<String, Path<M::State, M::Action>>;
+
+    /// Returns a map from property name to each of the discoveries collected for it, in the order
+    /// in which they were found. More than one is only collected per property if requested via
+    /// [`CheckerBuilder::max_discoveries_per_property`].
+    #[allow(clippy::type_complexity)]
+    fn discoveries_all(&self) -> HashMap<String, Vec<Path<M::State, M::Action>>> {
+        self.discoveries()
+            .into_iter()
+            .map(|(name, path)| (name, vec![path]))
+            .collect()
+    }
 
     /// Blocks the current thread until checking [`is_done`] or each thread evaluates
     /// a specified maximum number of states.
@@ -321,10 +788,34 @@ pub trait Checker<M: Model> {
     fn is_done(&self) -> bool;
 
     /// Looks up a discovery by property name. Panics if the property does not exist.
-    fn discovery(&self, name: &'static str) -> Option<Path<M::State, M::Action>> {
+    fn discovery(&self, name: &str) -> Option<Path<M::State, M::Action>> {
         self.discoveries().remove(name)
     }
 
+    /// Looks up the seed of the [`CheckerBuilder::spawn_simulation`] trace or
+    /// [`CheckerBuilder::spawn_swarm`] search that produced the discovery for a property. The seed
+    /// of a trace can be passed to [`CheckerBuilder::replay`] to regenerate the trace, which is
+    /// the discovery before any [`CheckerBuilder::shrink_discoveries`] minimization. Only known
+    /// for those two checkers.
+    fn discovery_seed(&self, _name: &str) -> Option<u64> {
+        None
+    }
+
+    /// Looks up a discovery by property name and minimizes it by delta debugging: runs of steps
+    /// are removed and adjacent steps are swapped for as long as replaying the remaining actions
+    /// via [`Model::next_steps`] still leads to a discovery for the property. This is useful for
+    /// the lengthy discoveries of [`CheckerBuilder::spawn_dfs`] and
+    /// [`CheckerBuilder::spawn_simulation`]. Discoveries for [`Expectation::Ltl`] properties are
+    /// not minimized.
+    fn shrunk_discovery(&self, name: &str) -> Option<Path<M::State, M::Action>>
+    where
+        M::State: PartialEq,
+        M::Action: PartialEq,
+    {
+        self.discovery(name)
+            .map(|path| shrink::shrink(self.model(), &self.properties(), name, path))
+    }
+
     /// Wait for all threads to finish whilst reporting, reporting the finish more accurately than
     /// the interval used for the reporting.
     fn join_and_report<R>(mut self, reporter: &mut R) -> Self
@@ -349,6 +840,8 @@ pub trait Checker<M: Model> {
                         total_states: slf.state_count(),
                         unique_states: slf.unique_state_count(),
                         max_depth: slf.max_depth(),
+                        omission_probability: slf.omission_probability(),
+                        limit_reached: slf.limit_reached(),
                         duration: method_start.elapsed(),
                         done: false,
                     });
@@ -366,6 +859,8 @@ pub trait Checker<M: Model> {
                 total_states: self.state_count(),
                 unique_states: self.unique_state_count(),
                 max_depth: self.max_depth(),
+                omission_probability: self.omission_probability(),
+                limit_reached: self.limit_reached(),
                 duration: method_start2.elapsed(),
                 done: true,
             });
@@ -375,7 +870,8 @@ pub trait Checker<M: Model> {
             for (name, path) in slf.discoveries() {
                 let discovery = ReportDiscovery {
                     path,
-                    classification: slf.discovery_classification(name),
+                    classification: slf.discovery_classification(&name),
+                    seed: slf.discovery_seed(&name),
                 };
                 discoveries.insert(name, discovery);
             }
@@ -402,6 +898,8 @@ pub trait Checker<M: Model> {
                 total_states: self.state_count(),
                 unique_states: self.unique_state_count(),
                 max_depth: self.max_depth(),
+                omission_probability: self.omission_probability(),
+                limit_reached: self.limit_reached(),
                 duration: method_start.elapsed(),
                 done: false,
             });
@@ -412,6 +910,8 @@ pub trait Checker<M: Model> {
             total_states: self.state_count(),
             unique_states: self.unique_state_count(),
             max_depth: self.max_depth(),
+            omission_probability: self.omission_probability(),
+            limit_reached: self.limit_reached(),
             duration: method_start.elapsed(),
             done: true,
         });
@@ -421,7 +921,8 @@ pub trait Checker<M: Model> {
         for (name, path) in self.discoveries() {
             let discovery = ReportDiscovery {
                 path,
-                classification: self.discovery_classification(name),
+                classification: self.discovery_classification(&name),
+                seed: self.discovery_seed(&name),
             };
             discoveries.insert(name, discovery);
         }
@@ -432,36 +933,39 @@ pub trait Checker<M: Model> {
 
     /// Indicates whether a discovery is an `"example"` or `"counterexample"`.
     fn discovery_classification(&self, name: &str) -> DiscoveryClassification {
-        let properties = self.model().properties();
+        let properties = self.properties();
         let property = properties.iter().find(|p| p.name == name).unwrap();
         match property.expectation {
-            Expectation::Always | Expectation::Eventually => {
-                DiscoveryClassification::Counterexample
-            }
+            Expectation::Always
+            | Expectation::AlwaysStep
+            | Expectation::Eventually
+            | Expectation::Ltl => DiscoveryClassification::Counterexample,
             Expectation::Sometimes => DiscoveryClassification::Example,
         }
     }
 
     /// A helper that verifies examples exist for all `sometimes` properties and no counterexamples
-    /// exist for any `always`/`eventually` properties.
+    /// exist for any `always`/`always_step`/`eventually`/`ltl` properties.
     fn assert_properties(&self)
     where
         M::Action: Debug,
         M::State: Debug,
     {
-        for p in self.model().properties() {
+        for p in self.properties() {
             match p.expectation {
-                Expectation::Always => self.assert_no_discovery(p.name),
-                Expectation::Eventually => self.assert_no_discovery(p.name),
+                Expectation::Always => self.assert_no_discovery(&p.name),
+                Expectation::AlwaysStep => self.assert_no_discovery(&p.name),
+                Expectation::Eventually => self.assert_no_discovery(&p.name),
+                Expectation::Ltl => self.assert_no_discovery(&p.name),
                 Expectation::Sometimes => {
-                    self.assert_any_discovery(p.name);
+                    self.assert_any_discovery(&p.name);
                 }
             }
         }
     }
 
     /// Panics if a particular discovery is not found.
-    fn assert_any_discovery(&self, name: &'static str) -> Path<M::State, M::Action> {
+    fn assert_any_discovery(&self, name: &str) -> Path<M::State, M::Action> {
         if let Some(found) = self.discovery(name) {
             return found;
         }
@@ -474,7 +978,7 @@ pub trait Checker<M: Model> {
     }
 
     /// Panics if a particular discovery is found.
-    fn assert_no_discovery(&self, name: &'static str)
+    fn assert_no_discovery(&self, name: &str)
     where
         M::Action: Debug,
         M::State: Debug,
@@ -497,7 +1001,7 @@ pub trait Checker<M: Model> {
 
     /// Panics if the specified actions do not result in a discovery for the specified property
     /// name.
-    fn assert_discovery(&self, name: &'static str, actions: Vec<M::Action>)
+    fn assert_discovery(&self, name: &str, actions: Vec<M::Action>)
     where
         M::State: Debug + PartialEq,
         M::Action: Debug + PartialEq,
@@ -505,40 +1009,85 @@ pub trait Checker<M: Model> {
         let mut additional_info: Vec<&'static str> = Vec::new();
 
         let found = self.assert_any_discovery(name);
+        // Includes the property of `CheckerBuilder::deadlock_detection`.
+        let properties = self.properties();
+        let property = properties
+            .iter()
+            .find(|p| p.name == name)
+            .unwrap_or_else(|| panic!("Unknown property. requested={}", name));
         for init_state in self.model().init_states() {
             if let Some(path) = Path::from_actions(self.model(), init_state, &actions) {
-                let property = self.model().property(name);
                 match property.expectation {
                     Expectation::Always => {
-                        if !(property.condition)(self.model(), path.last_state()) {
+                        if !property.holds(self.model(), path.last_state()) {
                             return;
                         }
                     }
+                    Expectation::AlwaysStep => {
+                        if let [.., (state, Some(action)), (next_state, None)] =
+                            path.into_vec().as_slice()
+                        {
+                            let step_condition = property.step_condition.as_ref().unwrap();
+                            if !step_condition(self.model(), state, action, next_state) {
+                                return;
+                            }
+                        }
+                    }
                     Expectation::Eventually => {
                         let states = path.into_states();
                         let is_liveness_satisfied =
-                            states.iter().any(|s| (property.condition)(self.model(), s));
+                            states.iter().any(|s| property.holds(self.model(), s));
                         let is_path_terminal = {
                             let mut actions = Vec::new();
                             self.model().actions(states.last().unwrap(), &mut actions);
                             actions.is_empty()
                         };
-                        if !is_liveness_satisfied && is_path_terminal {
+                        let is_path_cyclic =
+                            states[..states.len() - 1].contains(states.last().unwrap());
+                        if !is_liveness_satisfied && (is_path_terminal || is_path_cyclic) {
                             return;
                         }
                         if is_liveness_satisfied {
                             additional_info
                                 .push("incorrect counterexample satisfies eventually property");
                         }
-                        if !is_path_terminal {
-                            additional_info.push("incorrect counterexample is nonterminal");
+                        if !is_path_terminal && !is_path_cyclic {
+                            additional_info
+                                .push("incorrect counterexample is nonterminal and acyclic");
                         }
                     }
                     Expectation::Sometimes => {
-                        if (property.condition)(self.model(), path.last_state()) {
+                        if property.holds(self.model(), path.last_state()) {
                             return;
                         }
                     }
+                    Expectation::Ltl => {
+                        // Judge the infinite behavior described by the path, either repeating its
+                        // loop or remaining in its terminal state forever.
+                        let mut states = path.into_states();
+                        let last = states.len() - 1;
+                        let mut successors: Vec<_> = (1..=last).collect();
+                        if let Some(loop_start) =
+                            states[..last].iter().position(|s| s == &states[last])
+                        {
+                            states.pop();
+                            successors[last - 1] = loop_start;
+                        } else {
+                            let mut actions = Vec::new();
+                            self.model().actions(&states[last], &mut actions);
+                            if !actions.is_empty() {
+                                additional_info
+                                    .push("incorrect counterexample is nonterminal and acyclic");
+                                continue;
+                            }
+                            successors.push(last);
+                        }
+                        let formula = property.formula.as_ref().unwrap();
+                        if !ltl::holds(formula, self.model(), &states, &successors) {
+                            return;
+                        }
+                        additional_info.push("incorrect counterexample satisfies ltl property");
+                    }
                 }
             }
         }
@@ -565,10 +1114,146 @@ pub trait Checker<M: Model> {
 // a counterexample to the property.
 type EventuallyBits = id_set::IdSet;
 
+/// Combines a state fingerprint with the `eventually` properties that have yet to be satisfied on
+/// the path reaching that state. Used as the visited-state key when
+/// [`CheckerBuilder::track_eventually_bits`] is enabled. The state fingerprint itself is returned
+/// once every `eventually` property has been satisfied.
+fn eventually_fingerprint(state_fp: Fingerprint, ebits: &EventuallyBits) -> Fingerprint {
+    if ebits.is_empty() {
+        return state_fp;
+    }
+    crate::fingerprint(&(state_fp, ebits.iter().collect::<Vec<_>>()))
+}
+
+/// The name of the discovery reported by [`CheckerBuilder::deadlock_detection`].
+const DEADLOCK: &str = "deadlock";
+
+/// Returns the properties of a model along with one for [`CheckerBuilder::deadlock_detection`] if
+/// enabled. The latter is last so that the indexes of the model's properties are unaffected.
+/// Its condition generates the next states of a state, so checkers instead detect deadlocks via
+/// the steps they take, and only use this property for reporting and replaying discoveries.
+fn checked_properties<M: Model>(model: &M, deadlock_detection: bool) -> Vec<Property<M>> {
+    let mut properties = model.properties();
+    if deadlock_detection {
+        properties.push(Property::always(DEADLOCK, |model: &M, state| {
+            model.valid_end_state(state) || !model.next_states(state).is_empty()
+        }));
+    }
+    properties
+}
+
+/// Returns the first step from the `state` that violates an [`Expectation::AlwaysStep`]
+/// `property`, ignoring steps to states outside the [`Model::within_boundary`].
+fn violating_step<M: Model>(
+    model: &M,
+    property: &Property<M>,
+    state: &M::State,
+) -> Option<(M::Action, M::State)> {
+    let step_condition = property.step_condition.as_ref()?;
+    model
+        .next_steps(state)
+        .into_iter()
+        .find(|(action, next_state)| {
+            model.within_boundary(next_state) && !step_condition(model, state, action, next_state)
+        })
+}
+
+/// Generates copies of the actions from the `state` if there are `step_properties` to judge steps
+/// with, as the actions that are taken are consumed by [`Model::next_state`].
+fn copy_actions<M: Model>(
+    model: &M,
+    state: &M::State,
+    step_properties: &[&Property<M>],
+) -> Vec<M::Action> {
+    let mut copies = Vec::new();
+    if !step_properties.is_empty() {
+        model.actions(state, &mut copies);
+    }
+    copies
+}
+
+/// Takes each of the `actions` from the `state`, yielding the resulting steps. Each step includes
+/// the corresponding action of the `copies`, if any, so that it can be judged by
+/// [`violated_by`].
+fn take_steps<'a, M: Model>(
+    model: &'a M,
+    state: &'a M::State,
+    actions: impl IntoIterator<Item = M::Action> + 'a,
+    copies: Vec<M::Action>,
+) -> impl Iterator<Item = (Option<M::Action>, M::State)> + 'a {
+    let mut copies = copies.into_iter();
+    actions.into_iter().filter_map(move |action| {
+        let copy = copies.next();
+        model
+            .next_state(state, action)
+            .map(|next_state| (copy, next_state))
+    })
+}
+
+/// Returns the [`Expectation::AlwaysStep`] `step_properties` that the step from the `state` via the
+/// `action` to the `next_state` violates. Steps without a copy of their action are not judged.
+fn violated_by<'a, M: Model>(
+    model: &M,
+    step_properties: &[&'a Property<M>],
+    state: &M::State,
+    action: Option<&M::Action>,
+    next_state: &M::State,
+) -> Vec<&'a Property<M>> {
+    let Some(action) = action else {
+        return Vec::new();
+    };
+    step_properties
+        .iter()
+        .copied()
+        .filter(|property| {
+            property
+                .step_condition
+                .as_ref()
+                .is_some_and(|condition| !condition(model, state, action, next_state))
+        })
+        .collect()
+}
+
+/// Checkers record a discovery for an [`Expectation::AlwaysStep`] property at the state from which
+/// the violating step is taken, as the state after the step may have been reached some other way.
+/// This extends such a discovery through the violating step.
+fn complete_discovery<M: Model>(
+    model: &M,
+    properties: &[Property<M>],
+    name: &str,
+    mut path: Path<M::State, M::Action>,
+) -> Path<M::State, M::Action> {
+    let property = properties.iter().find(|p| p.name == name);
+    if let Some(property) = property.filter(|p| p.expectation == Expectation::AlwaysStep) {
+        if let Some((action, next_state)) = violating_step(model, property, path.last_state()) {
+            path.push(action, next_state);
+        }
+    }
+    path
+}
+
+/// Computes the key under which a state is recorded as visited. This is the fingerprint of the
+/// state's representative if symmetry reduction is enabled, combined with the `ebits` if they are
+/// tracked.
+fn visit_key<State: Hash>(
+    state: &State,
+    symmetry: Option<fn(&State) -> State>,
+    ebits: Option<&EventuallyBits>,
+) -> Fingerprint {
+    let fp = match symmetry {
+        Some(representative) => crate::fingerprint(&representative(state)),
+        None => crate::fingerprint(state),
+    };
+    match ebits {
+        Some(ebits) => eventually_fingerprint(fp, ebits),
+        None => fp,
+    }
+}
+
 #[cfg(test)]
 mod test_eventually_property_checker {
     use crate::test_util::dgraph::DGraph;
-    use crate::{Checker, Property};
+    use crate::{Checker, Model, Property};
 
     fn eventually_odd() -> Property<DGraph> {
         Property::eventually("odd", |_, s| s % 2 == 1)
@@ -639,23 +1324,74 @@ mod test_eventually_property_checker {
     }
 
     #[test]
-    fn fixme_can_miss_counterexample_when_revisiting_a_state() {
-        // i.e. incorrectly verify
+    fn can_discover_counterexample_when_revisiting_a_state() {
+        let cycle = DGraph::with_property(eventually_odd()).with_path(vec![0, 2, 4, 2]);
         assert_eq!(
-            DGraph::with_property(eventually_odd())
-                .with_path(vec![0, 2, 4, 2]) // cycle
-                .check()
-                .discovery("odd"),
-            None
-        ); // FIXME: `unwrap().into_states()` should be [0, 2, 4, 2]
+            cycle
+                .checker()
+                .spawn_liveness()
+                .join()
+                .discovery("odd")
+                .unwrap()
+                .into_states(),
+            vec![0, 2, 4, 2]
+        );
+        let dag_join = DGraph::with_property(eventually_odd())
+            .with_path(vec![0, 2, 4])
+            .with_path(vec![1, 4, 6]); // revisiting 4
         assert_eq!(
-            DGraph::with_property(eventually_odd())
-                .with_path(vec![0, 2, 4])
-                .with_path(vec![1, 4, 6]) // revisiting 4
-                .check()
-                .discovery("odd"),
-            None
-        ); // FIXME: `unwrap().into_states()` should be [0, 2, 4, 6]
+            dag_join
+                .checker()
+                .track_eventually_bits()
+                .spawn_bfs()
+                .join()
+                .discovery("odd")
+                .unwrap()
+                .into_states(),
+            vec![0, 2, 4, 6]
+        );
+    }
+
+    #[test]
+    fn can_discover_counterexample_at_dag_join_when_tracking_eventually_bits() {
+        let dgraph = DGraph::with_property(eventually_odd())
+            .with_path(vec![0, 2, 4])
+            .with_path(vec![1, 4, 6]); // revisiting 4
+        assert_eq!(
+            dgraph
+                .clone()
+                .checker()
+                .track_eventually_bits()
+                .spawn_bfs()
+                .join()
+                .discovery("odd")
+                .unwrap()
+                .into_states(),
+            vec![0, 2, 4, 6]
+        );
+        assert_eq!(
+            dgraph
+                .checker()
+                .track_eventually_bits()
+                .spawn_dfs()
+                .join()
+                .discovery("odd")
+                .unwrap()
+                .into_states(),
+            vec![0, 2, 4, 6]
+        );
+    }
+
+    #[test]
+    fn tracking_eventually_bits_still_validates() {
+        DGraph::with_property(eventually_odd())
+            .with_path(vec![0, 1, 4, 6])
+            .with_path(vec![2, 3, 4, 6]) // revisiting 4 after satisfying
+            .checker()
+            .track_eventually_bits()
+            .spawn_bfs()
+            .join()
+            .assert_properties();
     }
 }
 
@@ -685,6 +1421,353 @@ mod test_path {
     }
 }
 
+#[cfg(test)]
+mod test_deadlock_detection {
+    use super::*;
+    use crate::report::WriteReporter;
+    use crate::test_util::counter::Counter;
+
+    /// Counts to 2, where it may stop, unless it jams along the way.
+    fn counter() -> Counter {
+        Counter::new(2)
+            .with_jump("jam", 1, 100)
+            .with_valid_end_state(2)
+    }
+
+    #[test]
+    fn reports_states_without_next_states() {
+        let checkers: Vec<Box<dyn Fn() -> CheckerBuilder<Counter>>> = vec![
+            Box::new(|| counter().checker()),
+            Box::new(|| counter().checker().deadlock_detection()),
+        ];
+        for (i, builder) in checkers.iter().enumerate() {
+            let is_detected = i == 1;
+            let discoveries = vec![
+                builder().spawn_bfs().join().discovery(DEADLOCK),
+                builder()
+                    .spawn_best_first(|_, _| 0)
+                    .join()
+                    .discovery(DEADLOCK),
+                builder().spawn_dfs().join().discovery(DEADLOCK),
+                builder().spawn_liveness().join().discovery(DEADLOCK),
+                {
+                    let checker = builder().spawn_on_demand();
+                    checker.run_to_completion();
+                    while !checker.is_done() {
+                        std::thread::sleep(std::time::Duration::from_millis(1));
+                    }
+                    checker.discovery(DEADLOCK)
+                },
+                builder()
+                    .spawn_simulation(0, UniformChooser)
+                    .join()
+                    .discovery(DEADLOCK),
+                builder().spawn_swarm(0, 4).join().discovery(DEADLOCK),
+            ];
+            for discovery in discoveries {
+                assert_eq!(
+                    discovery.map(|path| path.into_actions()),
+                    is_detected.then(|| vec!["increment", "jam"])
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn reports_deadlocks_as_counterexamples() {
+        let checker = counter().checker().deadlock_detection().spawn_bfs().join();
+        assert_eq!(checker.properties().last().unwrap().name, DEADLOCK);
+        checker.assert_discovery(DEADLOCK, vec!["increment", "jam"]);
+
+        let mut written: Vec<u8> = Vec::new();
+        checker.report(&mut WriteReporter::new(&mut written));
+        let output = String::from_utf8(written).unwrap();
+        assert!(
+            output.contains("Discovered \"deadlock\" counterexample Path[2]:"),
+            "output={:?}",
+            output
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "reserved by deadlock_detection")]
+    fn rejects_properties_named_deadlock() {
+        let model = counter().with_property(Property::always(DEADLOCK, |_, _| true));
+        let _ = model.checker().deadlock_detection();
+    }
+}
+
+#[cfg(test)]
+mod test_always_step {
+    use super::*;
+    use crate::test_util::counter::Counter;
+
+    /// Counts to 4, but may reset from 3.
+    fn counter() -> Counter {
+        Counter::new(4)
+            .with_jump("reset", 3, 0)
+            .with_property(Property::always_step(
+                "never decreases",
+                |_, prev, _, next| next >= prev,
+            ))
+            .with_property(Property::always_step(
+                "increments by 1",
+                |_, prev, action, next| *action != "increment" || *next == prev + 1,
+            ))
+    }
+
+    #[test]
+    fn reports_paths_ending_with_violating_steps() {
+        let expected = vec!["increment", "increment", "increment", "reset"];
+        let discoveries = vec![
+            counter()
+                .checker()
+                .spawn_bfs()
+                .join()
+                .discovery("never decreases"),
+            counter()
+                .checker()
+                .spawn_best_first(|_, _| 0)
+                .join()
+                .discovery("never decreases"),
+            counter()
+                .checker()
+                .spawn_dfs()
+                .join()
+                .discovery("never decreases"),
+            counter()
+                .checker()
+                .spawn_dpor()
+                .join()
+                .discovery("never decreases"),
+            counter()
+                .checker()
+                .spawn_liveness()
+                .join()
+                .discovery("never decreases"),
+            counter()
+                .checker()
+                .target_state_count(1_000)
+                .spawn_simulation(0, UniformChooser)
+                .join()
+                .discovery("never decreases"),
+            counter()
+                .checker()
+                .spawn_swarm(0, 4)
+                .join()
+                .discovery("never decreases"),
+        ];
+        for discovery in discoveries {
+            // The last state was also reached by the empty path.
+            let path = discovery.unwrap();
+            assert_eq!(path.last_state(), &0);
+            assert_eq!(path.into_actions(), expected);
+        }
+
+        let checker = counter().checker().spawn_bfs().join();
+        checker.assert_discovery("never decreases", expected);
+        checker.assert_no_discovery("increments by 1");
+    }
+
+    #[test]
+    fn shrinking_keeps_violating_steps() {
+        let checker = counter().checker().shrink_discoveries().spawn_dfs().join();
+        assert_eq!(
+            checker.discovery("never decreases").unwrap().into_actions(),
+            vec!["increment", "increment", "increment", "reset"]
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_max_discoveries_per_property {
+    use super::*;
+    use crate::test_util::linear_equation_solver::LinearEquation;
+    use std::collections::HashSet;
+
+    #[test]
+    fn collects_discoveries_ending_in_distinct_states() {
+        let checkers: Vec<Box<dyn Checker<LinearEquation>>> = vec![
+            Box::new(
+                LinearEquation { a: 2, b: 10, c: 14 }
+                    .checker()
+                    .max_discoveries_per_property(3)
+                    .spawn_bfs()
+                    .join(),
+            ),
+            Box::new(
+                LinearEquation { a: 2, b: 10, c: 14 }
+                    .checker()
+                    .max_discoveries_per_property(3)
+                    .spawn_dfs()
+                    .join(),
+            ),
+            Box::new(
+                LinearEquation { a: 2, b: 10, c: 14 }
+                    .checker()
+                    .max_discoveries_per_property(3)
+                    .spawn_swarm(0, 4)
+                    .join(),
+            ),
+        ];
+        for checker in checkers {
+            let paths = checker.discoveries_all().remove("solvable").unwrap();
+            assert_eq!(paths.len(), 3);
+            let last_states: HashSet<_> = paths.iter().map(|p| *p.last_state()).collect();
+            assert_eq!(last_states.len(), 3);
+            for (x, y) in last_states {
+                assert_eq!(2u8.wrapping_mul(x).wrapping_add(10u8.wrapping_mul(y)), 14);
+            }
+            assert_eq!(checker.discovery("solvable").unwrap(), paths[0]);
+        }
+    }
+
+    #[test]
+    fn keeps_one_discovery_by_default() {
+        let checker = LinearEquation { a: 2, b: 10, c: 14 }
+            .checker()
+            .spawn_bfs()
+            .join();
+        assert_eq!(checker.discoveries_all()["solvable"].len(), 1);
+    }
+}
+
+#[cfg(test)]
+mod test_control {
+    use super::*;
+    use crate::test_util::linear_equation_solver::LinearEquation;
+    use std::thread::sleep;
+
+    #[test]
+    fn can_pause_and_cancel() {
+        // Unsolvable, so checking visits all 65,536 states.
+        let model = || LinearEquation { a: 2, b: 4, c: 7 };
+        let checkers: Vec<Box<dyn Checker<LinearEquation>>> = vec![
+            Box::new(model().checker().spawn_bfs()),
+            Box::new(model().checker().threads(2).spawn_dfs()),
+            Box::new(model().checker().spawn_liveness()),
+            Box::new(model().checker().spawn_on_demand()),
+            Box::new(
+                model()
+                    .checker()
+                    .target_max_depth(100)
+                    .spawn_simulation(0, UniformChooser),
+            ),
+        ];
+        for checker in checkers {
+            let control = checker.control().unwrap();
+            checker.run_to_completion();
+            control.pause();
+            assert!(control.is_paused());
+
+            // Threads finish the block or trace they are checking before pausing.
+            let mut state_count = 0;
+            while state_count != checker.state_count() {
+                state_count = checker.state_count();
+                sleep(Duration::from_millis(100));
+            }
+            assert!(!checker.is_done());
+            assert_eq!(checker.discovery("solvable"), None);
+
+            control.cancel();
+            control.resume();
+            assert!(control.is_cancelled());
+            while !checker.is_done() {
+                sleep(Duration::from_millis(10));
+            }
+            assert_eq!(checker.state_count(), state_count);
+        }
+    }
+
+    #[test]
+    fn can_resume() {
+        let checker = LinearEquation { a: 2, b: 10, c: 14 }.checker().spawn_bfs();
+        let control = checker.control().unwrap();
+        control.pause();
+        control.resume();
+        assert!(!control.is_paused());
+        checker.join().assert_properties();
+    }
+}
+
+#[cfg(test)]
+mod test_limits {
+    use super::*;
+    use crate::report::WriteReporter;
+    use crate::test_util::linear_equation_solver::LinearEquation;
+
+    #[test]
+    fn stops_at_duration_with_discoveries_so_far() {
+        // Collecting many discoveries requires visiting all 65,536 states.
+        let builder = || {
+            LinearEquation { a: 2, b: 10, c: 14 }
+                .checker()
+                .max_discoveries_per_property(1_000)
+                .target_duration(Duration::ZERO)
+        };
+        let on_demand = builder().spawn_on_demand();
+        on_demand.run_to_completion();
+        while !on_demand.is_done() {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        let checkers: Vec<Box<dyn Checker<LinearEquation>>> = vec![
+            Box::new(builder().spawn_bfs().join()),
+            Box::new(builder().spawn_dfs().join()),
+            Box::new(builder().spawn_liveness().join()),
+            Box::new(on_demand),
+            Box::new(
+                builder()
+                    .target_max_depth(20)
+                    .spawn_simulation(0, UniformChooser)
+                    .join(),
+            ),
+        ];
+        for checker in checkers {
+            assert_eq!(checker.limit_reached(), Some(Limit::Duration));
+            assert!(checker.state_count() < 65_536);
+        }
+
+        let checker = builder().spawn_bfs().join();
+        assert!(checker.discovery("solvable").is_some());
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn stops_at_memory() {
+        let checker = LinearEquation { a: 2, b: 4, c: 7 }
+            .checker()
+            .target_memory(1)
+            .spawn_bfs()
+            .join();
+        assert_eq!(checker.limit_reached(), Some(Limit::Memory));
+        assert!(checker.state_count() < 65_536);
+    }
+
+    #[test]
+    fn reports_limit_reached() {
+        let mut written: Vec<u8> = Vec::new();
+        LinearEquation { a: 2, b: 4, c: 7 }
+            .checker()
+            .target_state_count(100)
+            .spawn_bfs()
+            .report(&mut WriteReporter::new(&mut written));
+        let output = String::from_utf8(written).unwrap();
+        assert!(
+            output.contains(", limit=target_state_count\n"),
+            "output={:?}",
+            output
+        );
+
+        let mut written: Vec<u8> = Vec::new();
+        LinearEquation { a: 2, b: 10, c: 14 }
+            .checker()
+            .spawn_bfs()
+            .report(&mut WriteReporter::new(&mut written));
+        let output = String::from_utf8(written).unwrap();
+        assert!(!output.contains("limit="), "output={:?}", output);
+    }
+}
+
 #[cfg(test)]
 mod test_report {
     use super::*;
@@ -0,0 +1,452 @@
+//! Private module for selective re-export.
+
+use crate::checker::bfs::{reconstruct_path, Generated};
+use crate::checker::discoveries::Discoveries;
+use crate::checker::{
+    checked_properties, complete_discovery, copy_actions, take_steps, violated_by, visit_key,
+    Checker, EventuallyBits, Expectation, Path, DEADLOCK,
+};
+use crate::{fingerprint, CheckerBuilder, CheckerVisitor, Fingerprint, Model, Property};
+use dashmap::DashMap;
+use std::cmp::{Ordering as CmpOrdering, Reverse};
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+pub(crate) struct BestFirstChecker<M: Model> {
+    // Immutable state.
+    model: Arc<M>,
+    handles: Vec<std::thread::JoinHandle<()>>,
+    deadlock_detection: bool,
+
+    // Mutable state.
+    state_count: Arc<AtomicUsize>,
+    max_depth: Arc<AtomicUsize>,
+    is_done: Arc<AtomicBool>,
+    generated: Arc<Generated>,
+    discoveries: Arc<Discoveries<Fingerprint>>,
+}
+
+/// A state awaiting checking. States with a higher priority are checked first, and states with
+/// the same priority are checked in the order in which they were generated.
+struct Job<State> {
+    priority: i64,
+    sequence: u64,
+    state: State,
+    /// The visit key, which `generated` maps to the state fingerprint.
+    key: Fingerprint,
+    ebits: EventuallyBits,
+    depth: NonZeroUsize,
+}
+
+impl<State> Job<State> {
+    fn order(&self) -> (i64, Reverse<u64>) {
+        (self.priority, Reverse(self.sequence))
+    }
+}
+
+impl<State> PartialEq for Job<State> {
+    fn eq(&self, other: &Self) -> bool {
+        self.order() == other.order()
+    }
+}
+
+impl<State> Eq for Job<State> {}
+
+impl<State> PartialOrd for Job<State> {
+    fn partial_cmp(&self, other: &Self) -> Option<CmpOrdering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<State> Ord for Job<State> {
+    fn cmp(&self, other: &Self) -> CmpOrdering {
+        self.order().cmp(&other.order())
+    }
+}
+
+impl<M> BestFirstChecker<M>
+where
+    M: Model + Send + Sync + 'static,
+    M::State: Hash + Send + 'static,
+{
+    pub(crate) fn spawn(options: CheckerBuilder<M>, heuristic: fn(&M, &M::State) -> i64) -> Self {
+        let model = Arc::new(options.model);
+        let deadlock_detection = options.deadlock_detection;
+        let symmetry = options.symmetry;
+        let target_state_count = options.target_state_count;
+        let target_max_depth = options.target_max_depth;
+        let track_eventually_bits = options.track_eventually_bits;
+        let visitor = options.visitor;
+
+        let state_count = Arc::new(AtomicUsize::new(0));
+        let max_depth = Arc::new(AtomicUsize::new(0));
+        let is_done = Arc::new(AtomicBool::new(false));
+        let generated = Arc::new(Generated::InMemory {
+            predecessors: DashMap::default(),
+            state_fingerprints: if symmetry.is_some() || track_eventually_bits {
+                Some(DashMap::default())
+            } else {
+                None
+            },
+        });
+        let discoveries = Arc::new(Discoveries::new(options.max_discoveries_per_property));
+
+        let handle = {
+            let model = Arc::clone(&model);
+            let state_count = Arc::clone(&state_count);
+            let max_depth = Arc::clone(&max_depth);
+            let is_done = Arc::clone(&is_done);
+            let generated = Arc::clone(&generated);
+            let discoveries = Arc::clone(&discoveries);
+            std::thread::Builder::new()
+                .name("checker-0".to_string())
+                .spawn(move || {
+                    log::debug!("Thread started.");
+                    Self::search(
+                        &model,
+                        heuristic,
+                        &state_count,
+                        &max_depth,
+                        &generated,
+                        &discoveries,
+                        &visitor,
+                        target_state_count,
+                        target_max_depth,
+                        symmetry,
+                        track_eventually_bits,
+                        deadlock_detection,
+                    );
+                    is_done.store(true, Ordering::Relaxed);
+                    log::debug!("Search complete. Shutting down... gen={}", generated.len());
+                })
+                .expect("Failed to spawn a thread")
+        };
+        BestFirstChecker {
+            model,
+            handles: vec![handle],
+            deadlock_detection,
+            state_count,
+            max_depth,
+            is_done,
+            generated,
+            discoveries,
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    #[allow(clippy::type_complexity)]
+    fn search(
+        model: &M,
+        heuristic: fn(&M, &M::State) -> i64,
+        state_count: &AtomicUsize,
+        max_depth: &AtomicUsize,
+        generated: &Generated,
+        discoveries: &Discoveries<Fingerprint>,
+        visitor: &Option<Box<dyn CheckerVisitor<M> + Send + Sync>>,
+        target_state_count: Option<NonZeroUsize>,
+        target_max_depth: Option<NonZeroUsize>,
+        symmetry: Option<fn(&M::State) -> M::State>,
+        track_eventually_bits: bool,
+        deadlock_detection: bool,
+    ) {
+        // Deadlocks are detected via the steps taken rather than via a property.
+        let properties = model.properties();
+        let property_count = properties.len() + usize::from(deadlock_detection);
+        let ebits = {
+            let mut ebits = EventuallyBits::new();
+            for (i, p) in properties.iter().enumerate() {
+                if let Property {
+                    expectation: Expectation::Eventually,
+                    ..
+                } = p
+                {
+                    ebits.insert(i);
+                }
+            }
+            ebits
+        };
+
+        let mut sequence = 0;
+        let mut pending = BinaryHeap::new();
+        for state in model.init_states() {
+            if !model.within_boundary(&state) {
+                continue;
+            }
+            state_count.fetch_add(1, Ordering::Relaxed);
+            let key = visit_key(&state, symmetry, track_eventually_bits.then_some(&ebits));
+            if generated.insert(key, None, fingerprint(&state)) {
+                pending.push(Job {
+                    priority: heuristic(model, &state),
+                    sequence,
+                    state,
+                    key,
+                    ebits: ebits.clone(),
+                    depth: NonZeroUsize::new(1).unwrap(),
+                });
+                sequence += 1;
+            }
+        }
+
+        let mut actions = Vec::new();
+        while let Some(job) = pending.pop() {
+            let Job {
+                state,
+                key,
+                mut ebits,
+                depth,
+                ..
+            } = job;
+
+            if discoveries.complete_count() == property_count {
+                return;
+            }
+            if let Some(target_state_count) = target_state_count {
+                if target_state_count.get() <= state_count.load(Ordering::Relaxed) {
+                    return;
+                }
+            }
+
+            max_depth.fetch_max(depth.get(), Ordering::Relaxed);
+            if let Some(target_max_depth) = target_max_depth {
+                if depth >= target_max_depth {
+                    log::trace!("Skipping state as past max depth {}", depth);
+                    continue;
+                }
+            }
+
+            if let Some(visitor) = visitor {
+                visitor.visit(model, reconstruct_path(model, generated, key));
+            }
+
+            let mut is_awaiting_discoveries =
+                deadlock_detection && !discoveries.is_complete(DEADLOCK);
+            let mut step_properties = Vec::new();
+            for (i, property) in properties.iter().enumerate() {
+                if discoveries.is_complete(&property.name) {
+                    continue;
+                }
+                match property {
+                    Property {
+                        expectation: Expectation::Always,
+                        ..
+                    } => {
+                        if !property.holds(model, &state) {
+                            discoveries.insert(&property.name, key, key);
+                        }
+                        is_awaiting_discoveries |= !discoveries.is_complete(&property.name);
+                    }
+                    Property {
+                        expectation: Expectation::AlwaysStep,
+                        ..
+                    } => {
+                        // Judged on the steps taken below.
+                        step_properties.push(property);
+                        is_awaiting_discoveries = true;
+                    }
+                    Property {
+                        expectation: Expectation::Sometimes,
+                        ..
+                    } => {
+                        if property.holds(model, &state) {
+                            discoveries.insert(&property.name, key, key);
+                        }
+                        is_awaiting_discoveries |= !discoveries.is_complete(&property.name);
+                    }
+                    Property {
+                        expectation: Expectation::Eventually,
+                        ..
+                    } => {
+                        // Discoveries for "eventually" properties are only identified at terminal
+                        // states, so one is still awaited even if the property is now satisfied.
+                        is_awaiting_discoveries = true;
+                        if property.holds(model, &state) {
+                            ebits.remove(i);
+                        }
+                    }
+                    Property {
+                        expectation: Expectation::Ltl,
+                        ..
+                    } => {
+                        // Only checked by the liveness checker.
+                    }
+                }
+            }
+            if !is_awaiting_discoveries {
+                return;
+            }
+
+            let mut is_terminal = true;
+            let mut is_deadlocked = true;
+            model.actions(&state, &mut actions);
+            let copies = copy_actions(model, &state, &step_properties);
+            for (action, next_state) in take_steps(model, &state, actions.drain(..), copies) {
+                is_deadlocked = false;
+                if !model.within_boundary(&next_state) {
+                    continue;
+                }
+                state_count.fetch_add(1, Ordering::Relaxed);
+                for property in violated_by(
+                    model,
+                    &step_properties,
+                    &state,
+                    action.as_ref(),
+                    &next_state,
+                ) {
+                    discoveries.insert(&property.name, key, key);
+                }
+
+                // As with BFS, a state that was already generated is not treated as terminal.
+                is_terminal = false;
+                let next_key = visit_key(
+                    &next_state,
+                    symmetry,
+                    track_eventually_bits.then_some(&ebits),
+                );
+                if !generated.insert(next_key, Some(key), fingerprint(&next_state)) {
+                    continue;
+                }
+                pending.push(Job {
+                    priority: heuristic(model, &next_state),
+                    sequence,
+                    state: next_state,
+                    key: next_key,
+                    ebits: ebits.clone(),
+                    depth: NonZeroUsize::new(depth.get() + 1).unwrap(),
+                });
+                sequence += 1;
+            }
+            if is_terminal {
+                for (i, property) in properties.iter().enumerate() {
+                    if ebits.contains(i) {
+                        discoveries.insert(&property.name, key, key);
+                    }
+                }
+                // A deadlocked state has no next states at all, even outside the boundary.
+                if deadlock_detection && is_deadlocked && !model.valid_end_state(&state) {
+                    // Races other threads, but that's fine.
+                    discoveries.insert(DEADLOCK, key, key);
+                }
+            }
+        }
+    }
+}
+
+impl<M> Checker<M> for BestFirstChecker<M>
+where
+    M: Model,
+    M::State: Hash,
+{
+    fn model(&self) -> &M {
+        &self.model
+    }
+
+    fn state_count(&self) -> usize {
+        self.state_count.load(Ordering::Relaxed)
+    }
+
+    fn unique_state_count(&self) -> usize {
+        self.generated.len()
+    }
+
+    fn max_depth(&self) -> usize {
+        self.max_depth.load(Ordering::Relaxed)
+    }
+
+    fn properties(&self) -> Vec<Property<M>> {
+        checked_properties(&*self.model, self.deadlock_detection)
+    }
+
+    fn discoveries(&self) -> HashMap<String, Path<M::State, M::Action>> {
+        self.discoveries_all()
+            .into_iter()
+            .map(|(name, mut paths)| (name, paths.swap_remove(0)))
+            .collect()
+    }
+
+    fn discoveries_all(&self) -> HashMap<String, Vec<Path<M::State, M::Action>>> {
+        let properties = self.properties();
+        self.discoveries
+            .all()
+            .into_iter()
+            .map(|(name, discoveries)| {
+                let paths = discoveries
+                    .into_iter()
+                    .map(|fp| {
+                        let path = reconstruct_path(self.model(), &self.generated, fp);
+                        complete_discovery(self.model(), &properties, &name, path)
+                    })
+                    .collect();
+                (name, paths)
+            })
+            .collect()
+    }
+
+    fn handles(&mut self) -> Vec<JoinHandle<()>> {
+        std::mem::take(&mut self.handles)
+    }
+
+    fn is_done(&self) -> bool {
+        self.is_done.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::linear_equation_solver::*;
+    use crate::*;
+
+    #[test]
+    fn visits_states_in_bfs_order_without_preference() {
+        let (recorder, bfs_accessor) = StateRecorder::new_with_accessor();
+        LinearEquation { a: 2, b: 10, c: 14 }
+            .checker()
+            .visitor(recorder)
+            .spawn_bfs()
+            .join();
+        let (recorder, accessor) = StateRecorder::new_with_accessor();
+        LinearEquation { a: 2, b: 10, c: 14 }
+            .checker()
+            .visitor(recorder)
+            .spawn_best_first(|_, _| 0)
+            .join();
+        assert_eq!(accessor(), bfs_accessor());
+    }
+
+    #[test]
+    fn can_steer_toward_discoveries() {
+        // The solution is 200 steps away, so blind search generates many states.
+        let bfs = LinearEquation { a: 1, b: 1, c: 200 }
+            .checker()
+            .spawn_bfs()
+            .join();
+        assert!(bfs.unique_state_count() > 20_000);
+
+        let checker = LinearEquation { a: 1, b: 1, c: 200 }
+            .checker()
+            .spawn_best_first(|_, (x, y)| *x as i64 + *y as i64)
+            .join();
+        checker.assert_properties();
+        assert_eq!(
+            checker.discovery("solvable").unwrap().into_actions().len(),
+            200
+        );
+        assert!(checker.unique_state_count() < 1_000);
+        assert_eq!(checker.max_depth(), 201);
+    }
+
+    #[test]
+    fn can_complete_by_enumerating_all_states() {
+        let checker = LinearEquation { a: 2, b: 4, c: 7 }
+            .checker()
+            .spawn_best_first(|_, (x, _)| -(*x as i64))
+            .join();
+        assert!(checker.is_done());
+        checker.assert_no_discovery("solvable");
+        assert_eq!(checker.unique_state_count(), 256 * 256);
+    }
+}
@@ -1,36 +1,253 @@
 //! Private module for selective re-export.
 
-use crate::checker::{Checker, EventuallyBits, Expectation, Path};
+use crate::checker::checkpoint::{Checkpoint, CheckpointOptions};
+use crate::checker::control::CheckerControl;
+use crate::checker::discoveries::Discoveries;
+use crate::checker::disk::{DiskFingerprints, DiskOptions, DiskQueue};
+use crate::checker::limits::{Limit, Limits};
+use crate::checker::{
+    checked_properties, complete_discovery, copy_actions, por, take_steps, violated_by, visit_key,
+    Checker, EventuallyBits, Expectation, Path, DEADLOCK,
+};
 use crate::job_market::JobBroker;
 use crate::{fingerprint, CheckerBuilder, CheckerVisitor, Fingerprint, Model, Property};
 use dashmap::mapref::entry::Entry;
 use dashmap::DashMap;
 use nohash_hasher::NoHashHasher;
+use parking_lot::Mutex;
 use std::collections::{HashMap, VecDeque};
 use std::hash::{BuildHasherDefault, Hash};
 use std::num::NonZeroUsize;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::thread::JoinHandle;
-
-// While this file is currently quite similar to dfs.rs, a refactoring to lift shared
-// behavior is being postponed until DPOR is implemented.
+use std::time::Instant;
 
 pub(crate) struct BfsChecker<M: Model> {
     // Immutable state.
     model: Arc<M>,
     handles: Vec<std::thread::JoinHandle<()>>,
+    deadlock_detection: bool,
 
     // Mutable state.
     job_broker: JobBroker<Job<M::State>>,
     state_count: Arc<AtomicUsize>,
     max_depth: Arc<AtomicUsize>,
-    generated:
-        Arc<DashMap<Fingerprint, Option<Fingerprint>, BuildHasherDefault<NoHashHasher<u64>>>>,
-    discoveries: Arc<DashMap<&'static str, Fingerprint>>,
+    generated: Arc<Generated>,
+    discoveries: Arc<Discoveries<Fingerprint>>,
+    limits: Arc<Limits>,
+    control: CheckerControl,
 }
 type Job<State> = (State, Fingerprint, EventuallyBits, NonZeroUsize);
 
+/// The generated states, identified by their visit keys, each of which maps to the visit key of
+/// the state from which it was generated (if any) and to the state's fingerprint.
+pub(crate) enum Generated {
+    InMemory {
+        predecessors:
+            DashMap<Fingerprint, Option<Fingerprint>, BuildHasherDefault<NoHashHasher<u64>>>,
+        // Only populated when applying symmetry reduction or tracking eventually bits, as visit
+        // keys are otherwise the state fingerprints themselves.
+        state_fingerprints:
+            Option<DashMap<Fingerprint, Fingerprint, BuildHasherDefault<NoHashHasher<u64>>>>,
+    },
+    /// Sharded by visit key so that threads rarely wait for one another.
+    OnDisk(Vec<Mutex<DiskFingerprints>>),
+}
+
+/// The number of shards of the generated states per checker thread when they are kept on disk.
+const DISK_SHARDS_PER_THREAD: usize = 4;
+
+impl Generated {
+    /// Records a generated state unless its visit key is already present, in which case `false`
+    /// is returned.
+    pub(crate) fn insert(
+        &self,
+        key: Fingerprint,
+        prev: Option<Fingerprint>,
+        state_fp: Fingerprint,
+    ) -> bool {
+        match self {
+            Generated::InMemory {
+                predecessors,
+                state_fingerprints,
+            } => {
+                if let Entry::Vacant(entry) = predecessors.entry(key) {
+                    if let Some(state_fingerprints) = state_fingerprints {
+                        state_fingerprints.insert(key, state_fp);
+                    }
+                    entry.insert(prev);
+                    true
+                } else {
+                    false
+                }
+            }
+            Generated::OnDisk(shards) => shard(shards, key).lock().insert(key, prev, state_fp),
+        }
+    }
+
+    pub(crate) fn contains(&self, key: Fingerprint) -> bool {
+        match self {
+            Generated::InMemory { predecessors, .. } => predecessors.contains_key(&key),
+            Generated::OnDisk(shards) => shard(shards, key).lock().get(key).is_some(),
+        }
+    }
+
+    /// Returns the visit key of the state's predecessor (if any) and the state's fingerprint.
+    pub(crate) fn get(&self, key: Fingerprint) -> Option<(Option<Fingerprint>, Fingerprint)> {
+        match self {
+            Generated::InMemory {
+                predecessors,
+                state_fingerprints,
+            } => {
+                let prev = *predecessors.get(&key)?;
+                let state_fp = match state_fingerprints {
+                    Some(state_fingerprints) => *state_fingerprints.get(&key).unwrap(),
+                    None => key,
+                };
+                Some((prev, state_fp))
+            }
+            Generated::OnDisk(shards) => shard(shards, key).lock().get(key),
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            Generated::InMemory { predecessors, .. } => predecessors.len(),
+            Generated::OnDisk(shards) => shards.iter().map(|shard| shard.lock().len()).sum(),
+        }
+    }
+
+    /// Returns every visit key along with the visit key of the state's predecessor (if any) and
+    /// the state's fingerprint.
+    fn records(&self) -> Vec<(Fingerprint, Option<Fingerprint>, Fingerprint)> {
+        match self {
+            Generated::InMemory {
+                predecessors,
+                state_fingerprints,
+            } => predecessors
+                .iter()
+                .map(|entry| {
+                    let key = *entry.key();
+                    let state_fp = match state_fingerprints {
+                        Some(state_fingerprints) => *state_fingerprints.get(&key).unwrap(),
+                        None => key,
+                    };
+                    (key, *entry.value(), state_fp)
+                })
+                .collect(),
+            Generated::OnDisk(_) => unreachable!("Checkpoints are not taken when disk backed"),
+        }
+    }
+}
+
+/// Returns the shard of the on-disk generated states that holds a visit key.
+fn shard(shards: &[Mutex<DiskFingerprints>], key: Fingerprint) -> &Mutex<DiskFingerprints> {
+    &shards[(key.get() % shards.len() as u64) as usize]
+}
+
+fn encode_job<State>(serialize: fn(&State) -> Vec<u8>, job: &Job<State>) -> Vec<u8> {
+    let (state, fp, ebits, depth) = job;
+    let mut bytes = Vec::new();
+    bytes.extend(fp.get().to_le_bytes());
+    bytes.extend((depth.get() as u64).to_le_bytes());
+    bytes.extend((ebits.len() as u64).to_le_bytes());
+    for i in ebits.iter() {
+        bytes.extend((i as u64).to_le_bytes());
+    }
+    bytes.extend(serialize(state));
+    bytes
+}
+
+fn decode_job<State>(deserialize: fn(&[u8]) -> State, bytes: &[u8]) -> Job<State> {
+    let word = |i: usize| u64::from_le_bytes(bytes[8 * i..8 * i + 8].try_into().unwrap());
+    let fp = Fingerprint::new(word(0)).unwrap();
+    let depth = NonZeroUsize::new(word(1) as usize).unwrap();
+    let ebits_len = word(2) as usize;
+    let mut ebits = EventuallyBits::new();
+    for i in 0..ebits_len {
+        ebits.insert(word(3 + i) as usize);
+    }
+    let state = deserialize(&bytes[8 * (3 + ebits_len)..]);
+    (state, fp, ebits, depth)
+}
+
+/// Jobs that are pending for a thread but do not fit within its share of the memory budget, in
+/// which case they wait in a file. Once that happens, subsequent jobs also wait in the file until
+/// it is drained so that they are checked in breadth-first order.
+struct Overflow<State> {
+    queue: DiskQueue,
+    capacity: usize,
+    serialize: fn(&State) -> Vec<u8>,
+    deserialize: fn(&[u8]) -> State,
+}
+
+impl<State> Overflow<State> {
+    fn push(&mut self, pending: &mut VecDeque<Job<State>>, job: Job<State>) {
+        if self.queue.is_empty() && pending.len() < self.capacity {
+            pending.push_front(job);
+            return;
+        }
+        self.queue.push(&encode_job(self.serialize, &job));
+    }
+
+    /// Moves jobs from the file into `pending` until the latter is full.
+    fn refill(&mut self, pending: &mut VecDeque<Job<State>>) {
+        while pending.len() < self.capacity {
+            let bytes = match self.queue.pop() {
+                None => return,
+                Some(bytes) => bytes,
+            };
+            pending.push_front(decode_job(self.deserialize, &bytes));
+        }
+    }
+}
+
+/// Periodically saves the progress of a checker.
+struct Checkpointer<State> {
+    options: CheckpointOptions<State>,
+    next: Mutex<Instant>,
+}
+
+impl<State> Checkpointer<State> {
+    fn is_due(&self) -> bool {
+        *self.next.lock() <= Instant::now()
+    }
+
+    /// Saves the progress of a checker whose threads have all paused, unless another thread
+    /// recently did so.
+    fn save(
+        &self,
+        generated: &Generated,
+        jobs: &[VecDeque<Job<State>>],
+        discoveries: &Discoveries<Fingerprint>,
+        state_count: &AtomicUsize,
+        max_depth: &AtomicUsize,
+    ) {
+        if !self.is_due() {
+            return;
+        }
+        log::debug!("Saving a checkpoint to {}.", self.options.path.display());
+        Checkpoint {
+            state_count: state_count.load(Ordering::Relaxed),
+            max_depth: max_depth.load(Ordering::Relaxed),
+            generated: generated.records(),
+            pending: jobs
+                .iter()
+                .flatten()
+                .map(|job| encode_job(self.options.serialize, job))
+                .collect(),
+            discoveries: discoveries
+                .all()
+                .into_iter()
+                .flat_map(|(name, fps)| fps.into_iter().map(move |fp| (name.clone(), fp)))
+                .collect(),
+        }
+        .write(&self.options.path);
+        *self.next.lock() = Instant::now() + self.options.interval;
+    }
+}
+
 impl<M> BfsChecker<M>
 where
     M: Model + Send + Sync + 'static,
@@ -38,11 +255,31 @@ where
 {
     pub(crate) fn spawn(options: CheckerBuilder<M>) -> Self {
         let model = Arc::new(options.model);
-        let target_state_count = options.target_state_count;
+        let deadlock_detection = options.deadlock_detection;
+        let control = CheckerControl::new();
+        let limits = Arc::new(Limits::new(
+            options.target_state_count,
+            options.target_duration,
+            options.target_memory,
+        ));
         let target_max_depth = options.target_max_depth;
+        let symmetry = options.symmetry;
+        let track_eventually_bits = options.track_eventually_bits;
+        let partial_order_reduction = options.partial_order_reduction;
         let thread_count = options.thread_count;
+        let disk = options.disk;
+        assert!(
+            disk.is_none() || (options.checkpoint.is_none() && options.resume.is_none()),
+            "Checkpoints are unsupported when disk backed"
+        );
+        let checkpointer = options.checkpoint.map(|options| {
+            Arc::new(Checkpointer {
+                next: Mutex::new(Instant::now() + options.interval),
+                options,
+            })
+        });
         let visitor = Arc::new(options.visitor);
-        let property_count = model.properties().len();
+        let property_count = checked_properties(&*model, deadlock_detection).len();
 
         let init_states: Vec<_> = model
             .init_states()
@@ -51,13 +288,6 @@ where
             .collect();
         let state_count = Arc::new(AtomicUsize::new(init_states.len()));
         let max_depth = Arc::new(AtomicUsize::new(0));
-        let generated = Arc::new({
-            let generated = DashMap::default();
-            for s in &init_states {
-                generated.insert(fingerprint(s), None);
-            }
-            generated
-        });
         let ebits = {
             let mut ebits = EventuallyBits::new();
             for (i, p) in model.properties().iter().enumerate() {
@@ -71,14 +301,64 @@ where
             }
             ebits
         };
-        let pending: VecDeque<_> = init_states
-            .into_iter()
-            .map(|s| {
-                let fp = fingerprint(&s);
-                (s, fp, ebits.clone(), NonZeroUsize::new(1).unwrap())
-            })
-            .collect();
-        let discoveries = Arc::new(DashMap::default());
+        // Half of any memory budget is for generated states, and the rest is for pending states.
+        let generated = Arc::new(match &disk {
+            Some(disk) => {
+                let shard_count = DISK_SHARDS_PER_THREAD * thread_count;
+                let capacity = disk.memory_budget
+                    / 2
+                    / shard_count
+                    / (2 * std::mem::size_of::<(Fingerprint, Option<Fingerprint>, Fingerprint)>());
+                Generated::OnDisk(
+                    (0..shard_count)
+                        .map(|i| {
+                            let name = format!("fingerprints-{}", i);
+                            Mutex::new(DiskFingerprints::new(&disk.dir, &name, capacity))
+                        })
+                        .collect(),
+                )
+            }
+            None => Generated::InMemory {
+                predecessors: DashMap::default(),
+                state_fingerprints: if symmetry.is_some() || track_eventually_bits {
+                    Some(DashMap::default())
+                } else {
+                    None
+                },
+            },
+        });
+        let discoveries = Arc::new(Discoveries::new(options.max_discoveries_per_property));
+        let pending: VecDeque<_> = match options.resume {
+            Some(resume) => {
+                let checkpoint = Checkpoint::read(&resume.path);
+                state_count.store(checkpoint.state_count, Ordering::Relaxed);
+                max_depth.store(checkpoint.max_depth, Ordering::Relaxed);
+                for (key, prev, state_fp) in checkpoint.generated {
+                    generated.insert(key, prev, state_fp);
+                }
+                let properties = checked_properties(&*model, deadlock_detection);
+                for (name, fp) in checkpoint.discoveries {
+                    let property = properties
+                        .iter()
+                        .find(|p| p.name == name)
+                        .unwrap_or_else(|| panic!("Checkpoint has unknown property: {}", name));
+                    discoveries.insert(&property.name, fp, fp);
+                }
+                checkpoint
+                    .pending
+                    .iter()
+                    .map(|bytes| decode_job(resume.deserialize, bytes))
+                    .collect()
+            }
+            None => init_states
+                .into_iter()
+                .map(|s| {
+                    let key = visit_key(&s, symmetry, track_eventually_bits.then_some(&ebits));
+                    generated.insert(key, None, fingerprint(&s));
+                    (s, key, ebits.clone(), NonZeroUsize::new(1).unwrap())
+                })
+                .collect(),
+        };
         let mut handles = Vec::new();
 
         let mut job_broker = JobBroker::new(thread_count);
@@ -91,6 +371,19 @@ where
             let max_depth = Arc::clone(&max_depth);
             let generated = Arc::clone(&generated);
             let discoveries = Arc::clone(&discoveries);
+            let limits = Arc::clone(&limits);
+            let control = control.clone();
+            let checkpointer = checkpointer.clone();
+            let mut overflow = disk.as_ref().map(|disk: &DiskOptions<M::State>| Overflow {
+                queue: DiskQueue::new(&disk.dir, &format!("pending-{}", t)),
+                capacity: (disk.memory_budget
+                    / 2
+                    / thread_count
+                    / std::mem::size_of::<Job<M::State>>())
+                .max(1),
+                serialize: disk.serialize,
+                deserialize: disk.deserialize,
+            });
             handles.push(
                 std::thread::Builder::new()
                     .name(format!("checker-{}", t))
@@ -98,7 +391,21 @@ where
                         log::debug!("{}: Thread started.", t);
                         let mut pending = VecDeque::new();
                         loop {
+                            if !control.wait_while_paused() {
+                                log::debug!(
+                                    "{}: Cancelled. Shutting down... gen={}",
+                                    t,
+                                    generated.len()
+                                );
+                                return;
+                            }
+
                             // Step 1: Do work.
+                            if let Some(overflow) = &mut overflow {
+                                if pending.is_empty() {
+                                    overflow.refill(&mut pending);
+                                }
+                            }
                             if pending.is_empty() {
                                 pending = {
                                     let jobs = job_broker.pop();
@@ -119,13 +426,31 @@ where
                                 &state_count,
                                 &generated,
                                 &mut pending,
+                                &mut overflow,
                                 &discoveries,
                                 &visitor,
                                 1500,
                                 target_max_depth,
                                 &max_depth,
+                                partial_order_reduction,
+                                symmetry,
+                                track_eventually_bits,
+                                deadlock_detection,
                             );
-                            if discoveries.len() == property_count {
+                            if let Some(checkpointer) = &checkpointer {
+                                if checkpointer.is_due() || job_broker.is_pause_requested() {
+                                    job_broker.pause(&mut pending, |jobs| {
+                                        checkpointer.save(
+                                            &generated,
+                                            jobs,
+                                            &discoveries,
+                                            &state_count,
+                                            &max_depth,
+                                        )
+                                    });
+                                }
+                            }
+                            if discoveries.complete_count() == property_count {
                                 log::debug!(
                                     "{}: Discovery complete. Shutting down... gen={}",
                                     t,
@@ -133,15 +458,14 @@ where
                                 );
                                 return;
                             }
-                            if let Some(target_state_count) = target_state_count {
-                                if target_state_count.get() <= state_count.load(Ordering::Relaxed) {
-                                    log::debug!(
-                                        "{}: Reached target state count. Shutting down... gen={}",
-                                        t,
-                                        generated.len()
-                                    );
-                                    return;
-                                }
+                            if let Some(limit) = limits.check(state_count.load(Ordering::Relaxed)) {
+                                log::debug!(
+                                    "{}: Reached {} limit. Shutting down... gen={}",
+                                    t,
+                                    limit,
+                                    generated.len()
+                                );
+                                return;
                             }
 
                             // Step 2: Share work.
@@ -156,30 +480,36 @@ where
         BfsChecker {
             model,
             handles,
+            deadlock_detection,
             job_broker,
             state_count,
             max_depth,
             generated,
             discoveries,
+            limits,
+            control,
         }
     }
 
     #[allow(clippy::too_many_arguments)]
+    #[allow(clippy::type_complexity)]
     fn check_block(
         model: &M,
         state_count: &AtomicUsize,
-        generated: &DashMap<
-            Fingerprint,
-            Option<Fingerprint>,
-            BuildHasherDefault<NoHashHasher<u64>>,
-        >,
+        generated: &Generated,
         pending: &mut VecDeque<Job<M::State>>,
-        discoveries: &DashMap<&'static str, Fingerprint>,
+        overflow: &mut Option<Overflow<M::State>>,
+        discoveries: &Discoveries<Fingerprint>,
         visitor: &Option<Box<dyn CheckerVisitor<M> + Send + Sync>>,
         mut max_count: usize,
         target_max_depth: Option<NonZeroUsize>,
         global_max_depth: &AtomicUsize,
+        partial_order_reduction: bool,
+        symmetry: Option<fn(&M::State) -> M::State>,
+        track_eventually_bits: bool,
+        deadlock_detection: bool,
     ) {
+        // Deadlocks are detected via the steps taken rather than via a property.
         let properties = model.properties();
 
         let mut current_max_depth = global_max_depth.load(Ordering::Relaxed);
@@ -192,6 +522,8 @@ where
             max_count -= 1;
 
             // Done if none pending.
+            // When applying symmetry reduction or tracking eventually bits, `state_fp` is the visit
+            // key rather than the state fingerprint, which `generated` maps back to.
             let (state, state_fp, mut ebits, max_depth) = match pending.pop_back() {
                 None => return,
                 Some(pair) => pair,
@@ -219,39 +551,44 @@ where
             }
 
             // Done if discoveries found for all properties.
-            let mut is_awaiting_discoveries = false;
+            let mut is_awaiting_discoveries =
+                deadlock_detection && !discoveries.is_complete(DEADLOCK);
+            let mut step_properties = Vec::new();
             for (i, property) in properties.iter().enumerate() {
-                if discoveries.contains_key(property.name) {
+                if discoveries.is_complete(&property.name) {
                     continue;
                 }
                 match property {
                     Property {
                         expectation: Expectation::Always,
-                        condition: always,
                         ..
                     } => {
-                        if !always(model, &state) {
+                        if !property.holds(model, &state) {
                             // Races other threads, but that's fine.
-                            discoveries.insert(property.name, state_fp);
-                        } else {
-                            is_awaiting_discoveries = true;
+                            discoveries.insert(&property.name, state_fp, state_fp);
                         }
+                        is_awaiting_discoveries |= !discoveries.is_complete(&property.name);
+                    }
+                    Property {
+                        expectation: Expectation::AlwaysStep,
+                        ..
+                    } => {
+                        // Judged on the steps taken below.
+                        step_properties.push(property);
+                        is_awaiting_discoveries = true;
                     }
                     Property {
                         expectation: Expectation::Sometimes,
-                        condition: sometimes,
                         ..
                     } => {
-                        if sometimes(model, &state) {
+                        if property.holds(model, &state) {
                             // Races other threads, but that's fine.
-                            discoveries.insert(property.name, state_fp);
-                        } else {
-                            is_awaiting_discoveries = true;
+                            discoveries.insert(&property.name, state_fp, state_fp);
                         }
+                        is_awaiting_discoveries |= !discoveries.is_complete(&property.name);
                     }
                     Property {
                         expectation: Expectation::Eventually,
-                        condition: eventually,
                         ..
                     } => {
                         // The checker early exits after finding discoveries for every property,
@@ -260,10 +597,16 @@ where
                         // discovery regardless of whether the eventually property is now satisfied
                         // (i.e. it might be falsifiable via a different path).
                         is_awaiting_discoveries = true;
-                        if eventually(model, &state) {
+                        if property.holds(model, &state) {
                             ebits.remove(i);
                         }
                     }
+                    Property {
+                        expectation: Expectation::Ltl,
+                        ..
+                    } => {
+                        // Only checked by the liveness checker.
+                    }
                 }
             }
             if !is_awaiting_discoveries {
@@ -272,27 +615,60 @@ where
 
             // Otherwise enqueue newly generated states (with related metadata).
             let mut is_terminal = true;
+            let mut is_deadlocked = true;
             model.actions(&state, &mut actions);
-            let next_states = actions.drain(..).flat_map(|a| model.next_state(&state, a));
-            for next_state in next_states {
+            let reduced = if partial_order_reduction {
+                por::reduce(model, &properties, &state, &mut actions, |next_state| {
+                    let next_key = visit_key(
+                        next_state,
+                        symmetry,
+                        track_eventually_bits.then_some(&ebits),
+                    );
+                    !generated.contains(next_key)
+                })
+            } else {
+                Vec::new()
+            };
+            let copies = copy_actions(model, &state, &step_properties);
+            let next_steps = reduced
+                .into_iter()
+                .map(|next_state| (None, next_state))
+                .chain(take_steps(model, &state, actions.drain(..), copies));
+            for (action, next_state) in next_steps {
+                is_deadlocked = false;
                 // Skip if outside boundary.
                 if !model.within_boundary(&next_state) {
                     continue;
                 }
                 state_count.fetch_add(1, Ordering::Relaxed);
 
+                // Judge the step even if the next state was already generated.
+                for property in violated_by(
+                    model,
+                    &step_properties,
+                    &state,
+                    action.as_ref(),
+                    &next_state,
+                ) {
+                    // Races other threads, but that's fine.
+                    discoveries.insert(&property.name, state_fp, state_fp);
+                }
+
                 // Skip if already generated.
                 //
-                // FIXME: we should really include ebits in the fingerprint here --
-                // it is possible to arrive at a DAG join with two different ebits
-                // values, and subsequently treat the fact that some eventually
-                // property held on the path leading to the first visit as meaning
-                // that it holds in the path leading to the second visit -- another
-                // possible false-negative.
-                let next_fingerprint = fingerprint(&next_state);
-                if let Entry::Vacant(next_entry) = generated.entry(next_fingerprint) {
-                    next_entry.insert(Some(state_fp));
-                } else {
+                // Unless eventually bits are tracked, it is possible to arrive at a DAG join with
+                // two different ebits values, and subsequently treat the fact that some eventually
+                // property held on the path leading to the first visit as meaning that it holds in
+                // the path leading to the second visit -- a possible false-negative.
+                //
+                // With symmetry reduction, the search continues from the state itself rather than
+                // its representative so that the path to it remains valid.
+                let next_fingerprint = visit_key(
+                    &next_state,
+                    symmetry,
+                    track_eventually_bits.then_some(&ebits),
+                );
+                if !generated.insert(next_fingerprint, Some(state_fp), fingerprint(&next_state)) {
                     // FIXME: arriving at an already-known state may be a loop (in which case it
                     // could, in a fancier implementation, be considered a terminal state for
                     // purposes of eventually-property checking) but it might also be a join in
@@ -307,20 +683,29 @@ where
 
                 // Otherwise further checking is applicable.
                 is_terminal = false;
-                pending.push_front((
+                let job = (
                     next_state,
                     next_fingerprint,
                     ebits.clone(),
                     NonZeroUsize::new(max_depth.get() + 1).unwrap(),
-                ));
+                );
+                match overflow {
+                    Some(overflow) => overflow.push(pending, job),
+                    None => pending.push_front(job),
+                }
             }
             if is_terminal {
                 for (i, property) in properties.iter().enumerate() {
                     if ebits.contains(i) {
                         // Races other threads, but that's fine.
-                        discoveries.insert(property.name, state_fp);
+                        discoveries.insert(&property.name, state_fp, state_fp);
                     }
                 }
+                // A deadlocked state has no next states at all, even outside the boundary.
+                if deadlock_detection && is_deadlocked && !model.valid_end_state(&state) {
+                    // Races other threads, but that's fine.
+                    discoveries.insert(DEADLOCK, state_fp, state_fp);
+                }
             }
         }
     }
@@ -347,14 +732,39 @@ where
         self.max_depth.load(Ordering::Relaxed)
     }
 
-    fn discoveries(&self) -> HashMap<&'static str, Path<M::State, M::Action>> {
+    fn control(&self) -> Option<CheckerControl> {
+        Some(self.control.clone())
+    }
+
+    fn limit_reached(&self) -> Option<Limit> {
+        self.limits.reached()
+    }
+
+    fn properties(&self) -> Vec<Property<M>> {
+        checked_properties(&*self.model, self.deadlock_detection)
+    }
+
+    fn discoveries(&self) -> HashMap<String, Path<M::State, M::Action>> {
+        self.discoveries_all()
+            .into_iter()
+            .map(|(name, mut paths)| (name, paths.swap_remove(0)))
+            .collect()
+    }
+
+    fn discoveries_all(&self) -> HashMap<String, Vec<Path<M::State, M::Action>>> {
+        let properties = self.properties();
         self.discoveries
-            .iter()
-            .map(|mapref| {
-                (
-                    <&'static str>::clone(mapref.key()),
-                    reconstruct_path(self.model(), &self.generated, *mapref.value()),
-                )
+            .all()
+            .into_iter()
+            .map(|(name, discoveries)| {
+                let paths = discoveries
+                    .into_iter()
+                    .map(|fp| {
+                        let path = reconstruct_path(self.model(), &self.generated, fp);
+                        complete_discovery(self.model(), &properties, &name, path)
+                    })
+                    .collect();
+                (name, paths)
             })
             .collect()
     }
@@ -364,13 +774,13 @@ where
     }
 
     fn is_done(&self) -> bool {
-        self.job_broker.is_closed() || self.discoveries.len() == self.model.properties().len()
+        self.job_broker.is_closed() || self.discoveries.complete_count() == self.properties().len()
     }
 }
 
-fn reconstruct_path<M>(
+pub(crate) fn reconstruct_path<M>(
     model: &M,
-    generated: &DashMap<Fingerprint, Option<Fingerprint>, BuildHasherDefault<NoHashHasher<u64>>>,
+    generated: &Generated,
     fp: Fingerprint,
 ) -> Path<M::State, M::Action>
 where
@@ -384,16 +794,11 @@ where
 
     let mut fingerprints = VecDeque::new();
     let mut next_fp = fp;
-    while let Some(source) = generated.get(&next_fp) {
-        match *source {
-            Some(prev_fingerprint) => {
-                fingerprints.push_front(next_fp);
-                next_fp = prev_fingerprint;
-            }
-            None => {
-                fingerprints.push_front(next_fp);
-                break;
-            }
+    while let Some((prev, state_fp)) = generated.get(next_fp) {
+        fingerprints.push_front(state_fp);
+        match prev {
+            Some(prev_fingerprint) => next_fp = prev_fingerprint,
+            None => break,
         }
     }
     Path::from_fingerprints(model, fingerprints)
@@ -466,6 +871,174 @@ mod test {
         );
     }
 
+    #[test]
+    fn can_apply_symmetry_reduction() {
+        use crate::test_util::symmetric_processes::*;
+
+        // 9 states without symmetry reduction, and 6 with it. `PathRecorder` panics upon
+        // encountering an invalid path, as would happen if paths followed representatives.
+        let checker = Sys.checker().spawn_bfs().join();
+        assert_eq!(checker.unique_state_count(), 9);
+        let (visitor, _) = PathRecorder::new_with_accessor();
+        let checker = Sys.checker().symmetry().visitor(visitor).spawn_bfs().join();
+        assert_eq!(checker.unique_state_count(), 6);
+        checker.assert_discovery("a process pauses", vec![1.into(), 1.into()]);
+    }
+
+    #[test]
+    fn can_keep_states_on_disk() {
+        let dir = std::env::temp_dir().join(format!("stateright-bfs-{}", std::process::id()));
+        let model = || LinearEquation { a: 2, b: 4, c: 7 };
+        let in_memory = model().checker().target_max_depth(40).spawn_bfs().join();
+        // A small budget ensures that states spill to disk.
+        let on_disk = model()
+            .checker()
+            .disk_backed(&dir, 4_096)
+            .target_max_depth(40)
+            .spawn_bfs()
+            .join();
+        on_disk.assert_no_discovery("solvable");
+        assert_eq!(on_disk.unique_state_count(), in_memory.unique_state_count());
+        drop(on_disk);
+
+        let checker = LinearEquation { a: 2, b: 10, c: 14 }
+            .checker()
+            .disk_backed(&dir, 4_096)
+            .spawn_bfs()
+            .join();
+        checker.assert_properties();
+        assert_eq!(checker.unique_state_count(), 12);
+        assert_eq!(
+            checker.discovery("solvable").unwrap().into_actions(),
+            vec![Guess::IncreaseX, Guess::IncreaseX, Guess::IncreaseY,]
+        );
+        drop(checker);
+        let _ = std::fs::remove_dir(dir);
+    }
+
+    #[test]
+    fn can_keep_states_on_disk_with_several_threads() {
+        let dir = std::env::temp_dir().join(format!("stateright-bfs-mt-{}", std::process::id()));
+        let checker = LinearEquation { a: 2, b: 4, c: 7 }
+            .checker()
+            .threads(4)
+            .disk_backed(&dir, 1 << 20)
+            .spawn_bfs()
+            .join();
+        checker.assert_no_discovery("solvable");
+        assert_eq!(checker.unique_state_count(), 256 * 256);
+        assert_eq!(checker.state_count(), 1 + 2 * 256 * 256);
+        drop(checker);
+
+        let checker = LinearEquation { a: 2, b: 10, c: 14 }
+            .checker()
+            .threads(4)
+            .disk_backed(&dir, 1 << 20)
+            .spawn_bfs()
+            .join();
+        checker.assert_properties();
+        let (x, y) = *checker.discovery("solvable").unwrap().last_state();
+        assert_eq!((2 * x as usize + 10 * y as usize) % 256, 14);
+        drop(checker);
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+        let _ = std::fs::remove_dir(dir);
+    }
+
+    #[test]
+    fn can_resume_from_checkpoint() {
+        let path = std::env::temp_dir().join(format!("stateright-bfs-{}.ckpt", std::process::id()));
+
+        // Checkpoints are saved after every block of states until checking stops early.
+        let interrupted = LinearEquation { a: 2, b: 4, c: 7 }
+            .checker()
+            .threads(4)
+            .checkpoint(&path, std::time::Duration::ZERO)
+            .target_state_count(10_000)
+            .spawn_bfs()
+            .join();
+        assert!(interrupted.unique_state_count() < 256 * 256);
+        let resumed = LinearEquation { a: 2, b: 4, c: 7 }
+            .checker()
+            .threads(4)
+            .resume_from(&path)
+            .spawn_bfs()
+            .join();
+        resumed.assert_no_discovery("solvable");
+        assert_eq!(resumed.unique_state_count(), 256 * 256);
+
+        // The solution is 100 steps away, so it is discovered after resuming.
+        let uninterrupted = LinearEquation { a: 1, b: 1, c: 100 }
+            .checker()
+            .spawn_bfs()
+            .join();
+        let interrupted = LinearEquation { a: 1, b: 1, c: 100 }
+            .checker()
+            .checkpoint(&path, std::time::Duration::ZERO)
+            .target_state_count(1_000)
+            .spawn_bfs()
+            .join();
+        interrupted.assert_no_discovery("solvable");
+        let resumed = LinearEquation { a: 1, b: 1, c: 100 }
+            .checker()
+            .resume_from(&path)
+            .spawn_bfs()
+            .join();
+        assert_eq!(
+            resumed.discovery("solvable").unwrap().into_actions(),
+            uninterrupted.discovery("solvable").unwrap().into_actions()
+        );
+        assert_eq!(
+            resumed.unique_state_count(),
+            uninterrupted.unique_state_count()
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn can_resume_deadlock_discoveries_from_checkpoint() {
+        let path = std::env::temp_dir().join(format!(
+            "stateright-bfs-deadlock-{}.ckpt",
+            std::process::id()
+        ));
+
+        // Increments either coordinate, but stops at (1, 1). The property keeps checking going.
+        let grid: fn(Option<&(u8, u8)>, &mut Vec<_>) = |prev_state, next_states| match prev_state {
+            None => next_states.push((0, 0)),
+            Some((1, 1)) => {}
+            Some(&(x, y)) => {
+                next_states.push((x.wrapping_add(1), y));
+                next_states.push((x, y.wrapping_add(1)));
+            }
+        };
+        let model = || {
+            crate::combinators::with_properties(
+                grid,
+                vec![Property::sometimes("unreachable", |_, _| false)],
+            )
+        };
+        let interrupted = model()
+            .checker()
+            .deadlock_detection()
+            .checkpoint(&path, std::time::Duration::ZERO)
+            .target_state_count(10_000)
+            .spawn_bfs()
+            .join();
+        assert!(interrupted.discovery(DEADLOCK).is_some());
+        assert!(interrupted.unique_state_count() < 256 * 256);
+        let resumed = model()
+            .checker()
+            .deadlock_detection()
+            .resume_from(&path)
+            .spawn_bfs()
+            .join();
+        assert_eq!(
+            resumed.discovery(DEADLOCK).unwrap().into_states(),
+            vec![(0, 0), (1, 0), (1, 1)]
+        );
+        assert_eq!(resumed.unique_state_count(), 256 * 256);
+        std::fs::remove_file(path).unwrap();
+    }
+
     // test that the checker shuts down all threads properly after a checker thread encounters a
     // panic in the model execution.
     #[test]
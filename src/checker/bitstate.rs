@@ -0,0 +1,135 @@
+//! Private module for selective re-export.
+
+use crate::Fingerprint;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+/// A probabilistic set of fingerprints, as used by the SPIN model checker's bitstate search (also
+/// known as "supertrace"). Each fingerprint sets `hash_count` bits of a fixed-size bit array, and
+/// a fingerprint is considered present if all of its bits are set. The set therefore uses a
+/// bounded amount of memory, but it can claim to contain a fingerprint that was never inserted,
+/// in which case a checker omits that state.
+///
+/// Which fingerprints collide depends on a `seed`, so searches with different seeds omit
+/// different states.
+pub(crate) struct Bitstate {
+    words: Vec<AtomicU64>,
+    hash_count: usize,
+    seed: u64,
+    len: AtomicUsize,
+}
+
+impl Bitstate {
+    /// Creates an empty set of (at least) `bits` bits, each fingerprint of which sets `hash_count`
+    /// bits, hashing them with the given `seed`.
+    pub(crate) fn new(bits: usize, hash_count: usize, seed: u64) -> Self {
+        assert!(bits > 0, "A bitstate search requires at least one bit");
+        assert!(
+            hash_count > 0,
+            "A bitstate search requires at least one hash"
+        );
+        Self {
+            words: (0..bits.div_ceil(64)).map(|_| AtomicU64::new(0)).collect(),
+            hash_count,
+            seed,
+            len: AtomicUsize::new(0),
+        }
+    }
+
+    /// The number of fingerprints that have been inserted, not counting those that seemed to be
+    /// present already.
+    pub(crate) fn len(&self) -> usize {
+        self.len.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn contains(&self, fp: &Fingerprint) -> bool {
+        self.bits(*fp)
+            .all(|(word, mask)| self.words[word].load(Ordering::Relaxed) & mask != 0)
+    }
+
+    /// Sets the bits for a fingerprint, returning `false` if they were all set already.
+    pub(crate) fn insert(&self, fp: Fingerprint) -> bool {
+        let mut is_new = false;
+        for (word, mask) in self.bits(fp) {
+            is_new |= self.words[word].fetch_or(mask, Ordering::Relaxed) & mask == 0;
+        }
+        if is_new {
+            self.len.fetch_add(1, Ordering::Relaxed);
+        }
+        is_new
+    }
+
+    /// The probability that a fingerprint that was never inserted is nonetheless considered
+    /// present, which is the chance that checking omits a state reached for the first time. This
+    /// is estimated from the fraction of bits that are set, so it only grows as checking
+    /// progresses.
+    pub(crate) fn omission_probability(&self) -> f64 {
+        let set_bits: u32 = self
+            .words
+            .iter()
+            .map(|word| word.load(Ordering::Relaxed).count_ones())
+            .sum();
+        let fraction = set_bits as f64 / (64 * self.words.len()) as f64;
+        fraction.powi(self.hash_count as i32)
+    }
+
+    /// The words and masks of the bits for a fingerprint, which are derived via double hashing.
+    fn bits(&self, fp: Fingerprint) -> impl Iterator<Item = (usize, u64)> {
+        let bits = 64 * self.words.len() as u64;
+        // Mixing decorrelates the seeded first hash from the fingerprint and the second hash from
+        // the first.
+        let h1 = mix(fp.get() ^ self.seed);
+        let h2 = mix(h1) | 1;
+        (0..self.hash_count as u64).map(move |i| {
+            let bit = h1.wrapping_add(i.wrapping_mul(h2)) % bits;
+            ((bit / 64) as usize, 1 << (bit % 64))
+        })
+    }
+}
+
+/// The SplitMix64 finalizer.
+fn mix(mut z: u64) -> u64 {
+    z = z.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn tracks_inserted_fingerprints() {
+        let fp = |n| Fingerprint::new(n).unwrap();
+        let bitstate = Bitstate::new(1 << 16, 3, 0);
+        assert_eq!(bitstate.omission_probability(), 0.0);
+        for n in 1..=100 {
+            assert!(bitstate.insert(fp(n)));
+        }
+        for n in 1..=100 {
+            assert!(bitstate.contains(&fp(n)));
+            assert!(!bitstate.insert(fp(n)));
+        }
+        assert_eq!(bitstate.len(), 100);
+        assert!(bitstate.omission_probability() < 1e-6);
+
+        // With a single word, every bit is soon set.
+        let bitstate = Bitstate::new(1, 1, 0);
+        for n in 1..=1_000 {
+            bitstate.insert(fp(n));
+        }
+        assert!(bitstate.len() <= 64);
+        assert!(bitstate.omission_probability() > 0.9);
+
+        // Seeds change which fingerprints collide.
+        let seeded = |seed| {
+            let bitstate = Bitstate::new(64, 1, seed);
+            for n in 1..=16 {
+                bitstate.insert(fp(n));
+            }
+            bitstate
+        };
+        let (a, b) = (seeded(1), seeded(2));
+        assert!((17..=1_000).any(|n| a.contains(&fp(n)) != b.contains(&fp(n))));
+    }
+}
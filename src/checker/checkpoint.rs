@@ -0,0 +1,159 @@
+//! Private module for selective re-export.
+
+use crate::checker::disk::{decode_record, encode_record, RECORD_LEN};
+use crate::Fingerprint;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Configures a checker to periodically save its progress. See
+/// [`CheckerBuilder::checkpoint`](crate::CheckerBuilder::checkpoint).
+pub(crate) struct CheckpointOptions<State> {
+    pub(crate) path: PathBuf,
+    pub(crate) interval: Duration,
+    pub(crate) serialize: fn(&State) -> Vec<u8>,
+}
+
+/// Configures a checker to start from saved progress. See
+/// [`CheckerBuilder::resume_from`](crate::CheckerBuilder::resume_from).
+pub(crate) struct ResumeOptions<State> {
+    pub(crate) path: PathBuf,
+    pub(crate) deserialize: fn(&[u8]) -> State,
+}
+
+/// Identifies checkpoint files, including the version of their format.
+const MAGIC: &[u8; 8] = b"SRCKPT01";
+
+/// The progress of a checker: the generated states (identified by visit key) along with the visit
+/// key of each one's predecessor and each one's fingerprint, the encoded jobs that are pending,
+/// the discoveries, and the counters reported by the checker.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Checkpoint {
+    pub(crate) state_count: usize,
+    pub(crate) max_depth: usize,
+    pub(crate) generated: Vec<(Fingerprint, Option<Fingerprint>, Fingerprint)>,
+    pub(crate) pending: Vec<Vec<u8>>,
+    pub(crate) discoveries: Vec<(String, Fingerprint)>,
+}
+
+impl Checkpoint {
+    /// Writes the checkpoint to `path`, replacing any previous checkpoint only once the new one is
+    /// complete so that an interrupted write leaves the previous one intact.
+    pub(crate) fn write(&self, path: &Path) {
+        let partial_path = path.with_extension("partial");
+        let mut writer =
+            BufWriter::new(File::create(&partial_path).expect("Failed to create checkpoint"));
+        let mut write = |bytes: &[u8]| writer.write_all(bytes).expect("Failed to write checkpoint");
+        let word = |n: usize| (n as u64).to_le_bytes();
+
+        write(MAGIC);
+        write(&word(self.state_count));
+        write(&word(self.max_depth));
+        write(&word(self.generated.len()));
+        for (key, prev, state_fp) in &self.generated {
+            write(&encode_record((
+                key.get(),
+                prev.map_or(0, |p| p.get()),
+                state_fp.get(),
+            )));
+        }
+        write(&word(self.pending.len()));
+        for job in &self.pending {
+            write(&word(job.len()));
+            write(job);
+        }
+        write(&word(self.discoveries.len()));
+        for (name, fp) in &self.discoveries {
+            write(&word(name.len()));
+            write(name.as_bytes());
+            write(&fp.get().to_le_bytes());
+        }
+        writer
+            .into_inner()
+            .expect("Failed to write checkpoint")
+            .sync_all()
+            .expect("Failed to write checkpoint");
+        std::fs::rename(&partial_path, path).expect("Failed to replace checkpoint");
+    }
+
+    /// Reads a checkpoint previously written to `path`.
+    pub(crate) fn read(path: &Path) -> Self {
+        let mut reader = BufReader::new(File::open(path).expect("Failed to open checkpoint"));
+        let reader = &mut reader;
+        let fingerprint = |n: u64| Fingerprint::new(n).expect("Corrupt checkpoint");
+
+        assert_eq!(
+            read_bytes(reader, MAGIC.len()),
+            MAGIC,
+            "Not a checkpoint: {}",
+            path.display()
+        );
+        let state_count = read_word(reader) as usize;
+        let max_depth = read_word(reader) as usize;
+        let generated = (0..read_word(reader))
+            .map(|_| {
+                let (key, prev, state_fp) = decode_record(&read_bytes(reader, RECORD_LEN));
+                (
+                    fingerprint(key),
+                    Fingerprint::new(prev),
+                    fingerprint(state_fp),
+                )
+            })
+            .collect();
+        let pending = (0..read_word(reader))
+            .map(|_| {
+                let len = read_word(reader) as usize;
+                read_bytes(reader, len)
+            })
+            .collect();
+        let discoveries = (0..read_word(reader))
+            .map(|_| {
+                let len = read_word(reader) as usize;
+                let name = String::from_utf8(read_bytes(reader, len)).expect("Corrupt checkpoint");
+                (name, fingerprint(read_word(reader)))
+            })
+            .collect();
+        Checkpoint {
+            state_count,
+            max_depth,
+            generated,
+            pending,
+            discoveries,
+        }
+    }
+}
+
+fn read_bytes(reader: &mut impl Read, len: usize) -> Vec<u8> {
+    let mut bytes = vec![0; len];
+    reader
+        .read_exact(&mut bytes)
+        .expect("Failed to read checkpoint");
+    bytes
+}
+
+fn read_word(reader: &mut impl Read) -> u64 {
+    u64::from_le_bytes(read_bytes(reader, 8).try_into().unwrap())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn can_read_what_was_written() {
+        let fp = |n| Fingerprint::new(n).unwrap();
+        let checkpoint = Checkpoint {
+            state_count: 7,
+            max_depth: 3,
+            generated: vec![(fp(1), None, fp(2)), (fp(3), Some(fp(1)), fp(4))],
+            pending: vec![b"job".to_vec(), Vec::new()],
+            discoveries: vec![("a property".to_string(), fp(3))],
+        };
+        let path =
+            std::env::temp_dir().join(format!("stateright-checkpoint-{}", std::process::id()));
+        checkpoint.write(&path);
+        assert_eq!(Checkpoint::read(&path), checkpoint);
+        std::fs::remove_file(path).unwrap();
+    }
+}
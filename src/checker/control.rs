@@ -0,0 +1,92 @@
+//! Private module for selective re-export.
+
+use parking_lot::{Condvar, Mutex};
+use std::sync::Arc;
+
+/// A cloneable handle for pausing, resuming, or cancelling a running checker from other threads.
+/// Obtained via [`Checker::control`](crate::Checker::control).
+///
+/// Worker threads act on a request once they finish the block of states (or for
+/// [`CheckerBuilder::spawn_simulation`], the trace) they are checking, and the checker remains
+/// queryable throughout, so for instance [`Checker::discoveries`] can be
+/// inspected while paused or after cancelling.
+///
+/// [`Checker::discoveries`]: crate::Checker::discoveries
+/// [`CheckerBuilder::spawn_simulation`]: crate::CheckerBuilder::spawn_simulation
+///
+/// # Example
+///
+/// ```
+/// # use stateright::*; let model = ();
+/// let checker = model.checker().spawn_bfs();
+/// let control = checker.control().unwrap();
+/// control.pause();
+/// println!("{:?}", checker.discoveries());
+/// control.cancel();
+/// checker.join();
+/// ```
+#[derive(Clone)]
+pub struct CheckerControl {
+    state: Arc<(Mutex<ControlState>, Condvar)>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ControlState {
+    Running,
+    Paused,
+    Cancelled,
+}
+
+impl CheckerControl {
+    pub(crate) fn new() -> Self {
+        Self {
+            state: Arc::new((Mutex::new(ControlState::Running), Condvar::new())),
+        }
+    }
+
+    /// Asks the worker threads to wait until [`CheckerControl::resume`] or
+    /// [`CheckerControl::cancel`] is called.
+    pub fn pause(&self) {
+        self.transition(ControlState::Paused);
+    }
+
+    /// Lets paused worker threads continue checking.
+    pub fn resume(&self) {
+        self.transition(ControlState::Running);
+    }
+
+    /// Asks the worker threads to shut down, after which the checker is done. Cannot be undone.
+    pub fn cancel(&self) {
+        self.transition(ControlState::Cancelled);
+    }
+
+    /// Indicates whether [`CheckerControl::pause`] was called without a later resumption.
+    pub fn is_paused(&self) -> bool {
+        *self.state.0.lock() == ControlState::Paused
+    }
+
+    /// Indicates whether [`CheckerControl::cancel`] was called.
+    pub fn is_cancelled(&self) -> bool {
+        *self.state.0.lock() == ControlState::Cancelled
+    }
+
+    /// Blocks the calling worker thread while paused, returning whether it should continue
+    /// checking, which is not the case once cancelled.
+    pub(crate) fn wait_while_paused(&self) -> bool {
+        let (state, changed) = &*self.state;
+        let mut state = state.lock();
+        while *state == ControlState::Paused {
+            changed.wait(&mut state);
+        }
+        *state == ControlState::Running
+    }
+
+    fn transition(&self, next: ControlState) {
+        let (state, changed) = &*self.state;
+        let mut state = state.lock();
+        if *state != ControlState::Cancelled {
+            *state = next;
+            changed.notify_all();
+        }
+    }
+}
@@ -1,9 +1,17 @@
 //! Private module for selective re-export.
 
-use crate::checker::{Checker, EventuallyBits, Expectation, Path};
+use crate::checker::bitstate::Bitstate;
+use crate::checker::control::CheckerControl;
+use crate::checker::discoveries::Discoveries;
+use crate::checker::limits::{Limit, Limits};
+use crate::checker::shrink::Shrink;
+use crate::checker::{
+    checked_properties, complete_discovery, copy_actions, por, take_steps, violated_by, visit_key,
+    Checker, EventuallyBits, Expectation, Path, DEADLOCK,
+};
 use crate::job_market::JobBroker;
 use crate::{fingerprint, CheckerBuilder, CheckerVisitor, Fingerprint, Model, Property};
-use dashmap::{DashMap, DashSet};
+use dashmap::DashSet;
 use nohash_hasher::NoHashHasher;
 use std::collections::{HashMap, VecDeque};
 use std::hash::{BuildHasherDefault, Hash};
@@ -12,22 +20,53 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::thread::JoinHandle;
 
-// While this file is currently quite similar to bfs.rs, a refactoring to lift shared
-// behavior is being postponed until DPOR is implemented.
-
 pub(crate) struct DfsChecker<M: Model> {
     // Immutable state.
     model: Arc<M>,
     handles: Vec<std::thread::JoinHandle<()>>,
+    deadlock_detection: bool,
 
     // Mutable state.
     job_broker: JobBroker<Job<M::State>>,
     state_count: Arc<AtomicUsize>,
     max_depth: Arc<AtomicUsize>,
-    generated: Arc<DashSet<Fingerprint, BuildHasherDefault<NoHashHasher<u64>>>>,
-    discoveries: Arc<DashMap<&'static str, Vec<Fingerprint>>>,
+    generated: Arc<Generated>,
+    discoveries: Arc<Discoveries<Vec<Fingerprint>>>,
+    limits: Arc<Limits>,
+    control: CheckerControl,
+    shrink: Option<Shrink<M>>,
+}
+pub(crate) type Job<State> = (State, Vec<Fingerprint>, EventuallyBits, NonZeroUsize);
+
+/// The visit keys of the generated states, which are approximated when applying bitstate hashing.
+pub(crate) enum Generated {
+    Exact(DashSet<Fingerprint, BuildHasherDefault<NoHashHasher<u64>>>),
+    Bitstate(Bitstate),
+}
+
+impl Generated {
+    /// Records a visit key, returning `false` if it was already present.
+    pub(crate) fn insert(&self, key: Fingerprint) -> bool {
+        match self {
+            Generated::Exact(set) => set.insert(key),
+            Generated::Bitstate(bitstate) => bitstate.insert(key),
+        }
+    }
+
+    pub(crate) fn contains(&self, key: &Fingerprint) -> bool {
+        match self {
+            Generated::Exact(set) => set.contains(key),
+            Generated::Bitstate(bitstate) => bitstate.contains(key),
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            Generated::Exact(set) => set.len(),
+            Generated::Bitstate(bitstate) => bitstate.len(),
+        }
+    }
 }
-type Job<State> = (State, Vec<Fingerprint>, EventuallyBits, NonZeroUsize);
 
 impl<M> DfsChecker<M>
 where
@@ -36,12 +75,21 @@ where
 {
     pub(crate) fn spawn(options: CheckerBuilder<M>) -> Self {
         let model = Arc::new(options.model);
+        let deadlock_detection = options.deadlock_detection;
+        let shrink = options.shrink;
         let symmetry = options.symmetry;
-        let target_state_count = options.target_state_count;
+        let control = CheckerControl::new();
+        let limits = Arc::new(Limits::new(
+            options.target_state_count,
+            options.target_duration,
+            options.target_memory,
+        ));
         let target_max_depth = options.target_max_depth;
+        let partial_order_reduction = options.partial_order_reduction;
         let thread_count = options.thread_count;
+        let track_eventually_bits = options.track_eventually_bits;
         let visitor = Arc::new(options.visitor);
-        let property_count = model.properties().len();
+        let property_count = checked_properties(&*model, deadlock_detection).len();
 
         let init_states: Vec<_> = model
             .init_states()
@@ -50,17 +98,6 @@ where
             .collect();
         let state_count = Arc::new(AtomicUsize::new(init_states.len()));
         let max_depth = Arc::new(AtomicUsize::new(0));
-        let generated = Arc::new({
-            let generated = DashSet::default();
-            for s in &init_states {
-                if let Some(representative) = symmetry {
-                    generated.insert(fingerprint(&representative(s)));
-                } else {
-                    generated.insert(fingerprint(s));
-                }
-            }
-            generated
-        });
         let ebits = {
             let mut ebits = EventuallyBits::new();
             for (i, p) in model.properties().iter().enumerate() {
@@ -74,6 +111,20 @@ where
             }
             ebits
         };
+        let generated = Arc::new({
+            let generated = match options.bitstate {
+                Some((bits, hash_count)) => Generated::Bitstate(Bitstate::new(bits, hash_count, 0)),
+                None => Generated::Exact(DashSet::default()),
+            };
+            for s in &init_states {
+                generated.insert(visit_key(
+                    s,
+                    symmetry,
+                    track_eventually_bits.then_some(&ebits),
+                ));
+            }
+            generated
+        });
         let pending: VecDeque<_> = init_states
             .into_iter()
             .map(|s| {
@@ -81,7 +132,7 @@ where
                 (s, vec![fp], ebits.clone(), NonZeroUsize::new(1).unwrap())
             })
             .collect();
-        let discoveries = Arc::new(DashMap::default());
+        let discoveries = Arc::new(Discoveries::new(options.max_discoveries_per_property));
         let mut handles = Vec::new();
 
         let mut job_broker = JobBroker::new(thread_count);
@@ -94,6 +145,8 @@ where
             let max_depth = Arc::clone(&max_depth);
             let generated = Arc::clone(&generated);
             let discoveries = Arc::clone(&discoveries);
+            let limits = Arc::clone(&limits);
+            let control = control.clone();
             handles.push(
                 std::thread::Builder::new()
                     .name(format!("checker-{}", t))
@@ -101,6 +154,15 @@ where
                         log::debug!("{}: Thread started.", t);
                         let mut pending = VecDeque::new();
                         loop {
+                            if !control.wait_while_paused() {
+                                log::debug!(
+                                    "{}: Cancelled. Shutting down... gen={}",
+                                    t,
+                                    generated.len()
+                                );
+                                return;
+                            }
+
                             // Step 1: Do work.
                             if pending.is_empty() {
                                 pending = {
@@ -123,13 +185,18 @@ where
                                 &generated,
                                 &mut pending,
                                 &discoveries,
+                                |fingerprints| fingerprints,
                                 &visitor,
                                 1500,
                                 target_max_depth,
                                 &max_depth,
+                                partial_order_reduction,
                                 symmetry,
+                                track_eventually_bits,
+                                deadlock_detection,
+                                None,
                             );
-                            if discoveries.len() == property_count {
+                            if discoveries.complete_count() == property_count {
                                 log::debug!(
                                     "{}: Discovery complete. Shutting down... gen={}",
                                     t,
@@ -137,15 +204,14 @@ where
                                 );
                                 return;
                             }
-                            if let Some(target_state_count) = target_state_count {
-                                if target_state_count.get() <= state_count.load(Ordering::Relaxed) {
-                                    log::debug!(
-                                        "{}: Reached target state count. Shutting down... gen={}",
-                                        t,
-                                        generated.len()
-                                    );
-                                    return;
-                                }
+                            if let Some(limit) = limits.check(state_count.load(Ordering::Relaxed)) {
+                                log::debug!(
+                                    "{}: Reached {} limit. Shutting down... gen={}",
+                                    t,
+                                    limit,
+                                    generated.len()
+                                );
+                                return;
                             }
 
                             // Step 2: Share work.
@@ -160,28 +226,45 @@ where
         DfsChecker {
             model,
             handles,
+            deadlock_detection,
             job_broker,
             state_count,
             max_depth,
             generated,
             discoveries,
+            limits,
+            control,
+            shrink,
         }
     }
 
+    /// Checks up to `max_count` of the `pending` jobs, last in first out. If `order_actions` is
+    /// provided, then it may reorder the actions of each state before their next states are
+    /// generated, which changes the order in which those are checked. It must reorder the copies
+    /// of the actions that are made for judging steps, if any, in the same way. Discoveries are recorded as
+    /// `discovery` converts their fingerprint paths.
     #[allow(clippy::too_many_arguments)]
     #[allow(clippy::type_complexity)]
-    fn check_block(
+    pub(crate) fn check_block<D: Clone>(
         model: &M,
         state_count: &AtomicUsize,
-        generated: &DashSet<Fingerprint, BuildHasherDefault<NoHashHasher<u64>>>,
+        generated: &Generated,
         pending: &mut VecDeque<Job<M::State>>,
-        discoveries: &DashMap<&'static str, Vec<Fingerprint>>,
+        discoveries: &Discoveries<D>,
+        discovery: impl Fn(Vec<Fingerprint>) -> D,
         visitor: &Option<Box<dyn CheckerVisitor<M> + Send + Sync>>,
         mut max_count: usize,
         target_max_depth: Option<NonZeroUsize>,
         global_max_depth: &AtomicUsize,
+        partial_order_reduction: bool,
         symmetry: Option<fn(&M::State) -> M::State>,
+        track_eventually_bits: bool,
+        deadlock_detection: bool,
+        mut order_actions: Option<
+            &mut dyn FnMut(&M::State, &mut Vec<M::Action>, &mut Vec<M::Action>),
+        >,
     ) {
+        // Deadlocks are detected via the steps taken rather than via a property.
         let properties = model.properties();
 
         let mut current_max_depth = global_max_depth.load(Ordering::Relaxed);
@@ -223,39 +306,52 @@ where
             }
 
             // Done if discoveries found for all properties.
-            let mut is_awaiting_discoveries = false;
+            let mut is_awaiting_discoveries =
+                deadlock_detection && !discoveries.is_complete(DEADLOCK);
+            let mut step_properties = Vec::new();
             for (i, property) in properties.iter().enumerate() {
-                if discoveries.contains_key(property.name) {
+                if discoveries.is_complete(&property.name) {
                     continue;
                 }
                 match property {
                     Property {
                         expectation: Expectation::Always,
-                        condition: always,
                         ..
                     } => {
-                        if !always(model, &state) {
+                        if !property.holds(model, &state) {
                             // Races other threads, but that's fine.
-                            discoveries.insert(property.name, fingerprints.clone());
-                        } else {
-                            is_awaiting_discoveries = true;
+                            discoveries.insert(
+                                &property.name,
+                                *fingerprints.last().unwrap(),
+                                discovery(fingerprints.clone()),
+                            );
                         }
+                        is_awaiting_discoveries |= !discoveries.is_complete(&property.name);
+                    }
+                    Property {
+                        expectation: Expectation::AlwaysStep,
+                        ..
+                    } => {
+                        // Judged on the steps taken below.
+                        step_properties.push(property);
+                        is_awaiting_discoveries = true;
                     }
                     Property {
                         expectation: Expectation::Sometimes,
-                        condition: sometimes,
                         ..
                     } => {
-                        if sometimes(model, &state) {
+                        if property.holds(model, &state) {
                             // Races other threads, but that's fine.
-                            discoveries.insert(property.name, fingerprints.clone());
-                        } else {
-                            is_awaiting_discoveries = true;
+                            discoveries.insert(
+                                &property.name,
+                                *fingerprints.last().unwrap(),
+                                discovery(fingerprints.clone()),
+                            );
                         }
+                        is_awaiting_discoveries |= !discoveries.is_complete(&property.name);
                     }
                     Property {
                         expectation: Expectation::Eventually,
-                        condition: eventually,
                         ..
                     } => {
                         // The checker early exits after finding discoveries for every property,
@@ -264,10 +360,16 @@ where
                         // discovery regardless of whether the eventually property is now satisfied
                         // (i.e. it might be falsifiable via a different path).
                         is_awaiting_discoveries = true;
-                        if eventually(model, &state) {
+                        if property.holds(model, &state) {
                             ebits.remove(i);
                         }
                     }
+                    Property {
+                        expectation: Expectation::Ltl,
+                        ..
+                    } => {
+                        // Only checked by the liveness checker.
+                    }
                 }
             }
             if !is_awaiting_discoveries {
@@ -276,61 +378,87 @@ where
 
             // Otherwise enqueue newly generated states (with related metadata).
             let mut is_terminal = true;
+            let mut is_deadlocked = true;
             model.actions(&state, &mut actions);
-            for action in actions.drain(..) {
-                let next_state = match model.next_state(&state, action) {
-                    None => continue,
-                    Some(next_state) => next_state,
-                };
-
+            let mut copies = copy_actions(model, &state, &step_properties);
+            if let Some(order_actions) = &mut order_actions {
+                order_actions(&state, &mut actions, &mut copies);
+            }
+            let reduced = if partial_order_reduction {
+                por::reduce(model, &properties, &state, &mut actions, |next_state| {
+                    let next_key = visit_key(
+                        next_state,
+                        symmetry,
+                        track_eventually_bits.then_some(&ebits),
+                    );
+                    !generated.contains(&next_key)
+                })
+            } else {
+                Vec::new()
+            };
+            let next_steps = reduced
+                .into_iter()
+                .map(|next_state| (None, next_state))
+                .chain(take_steps(model, &state, actions.drain(..), copies));
+            for (action, next_state) in next_steps {
+                is_deadlocked = false;
                 // Skip if outside boundary.
                 if !model.within_boundary(&next_state) {
                     continue;
                 }
                 state_count.fetch_add(1, Ordering::Relaxed);
 
+                // Judge the step even if the next state was already generated.
+                for property in violated_by(
+                    model,
+                    &step_properties,
+                    &state,
+                    action.as_ref(),
+                    &next_state,
+                ) {
+                    // Races other threads, but that's fine.
+                    discoveries.insert(
+                        &property.name,
+                        *fingerprints.last().unwrap(),
+                        discovery(fingerprints.clone()),
+                    );
+                }
+
                 // Skip if already generated.
                 //
-                // FIXME: we should really include ebits in the fingerprint here --
-                // it is possible to arrive at a DAG join with two different ebits
-                // values, and subsequently treat the fact that some eventually
-                // property held on the path leading to the first visit as meaning
-                // that it holds in the path leading to the second visit -- another
-                // possible false-negative.
-                let next_fingerprint = if let Some(representative) = symmetry {
-                    let representative_fingerprint = fingerprint(&representative(&next_state));
-                    if !generated.insert(representative_fingerprint) {
-                        is_terminal = false;
-                        continue;
-                    }
-                    // IMPORTANT: continue the path with the pre-canonicalized state/fingerprint to
-                    // avoid jumping to another part of the state space for which there may not be
-                    // a path extension from the previously collected path.
-                    fingerprint(&next_state)
-                } else {
-                    let next_fingerprint = fingerprint(&next_state);
-                    if !generated.insert(next_fingerprint) {
-                        // FIXME: arriving at an already-known state may be a loop (in which case it
-                        // could, in a fancier implementation, be considered a terminal state for
-                        // purposes of eventually-property checking) but it might also be a join in
-                        // a DAG, which makes it non-terminal. These cases can be disambiguated (at
-                        // some cost), but for now we just _don't_ treat them as terminal, and tell
-                        // users they need to explicitly ensure model path-acyclicality when they're
-                        // using eventually properties (using a boundary or empty actions or
-                        // whatever).
-                        is_terminal = false;
-                        continue;
-                    }
-                    next_fingerprint
-                };
+                // Unless eventually bits are tracked, it is possible to arrive at a DAG join with
+                // two different ebits values, and subsequently treat the fact that some eventually
+                // property held on the path leading to the first visit as meaning that it holds in
+                // the path leading to the second visit -- a possible false-negative.
+                let next_key = visit_key(
+                    &next_state,
+                    symmetry,
+                    track_eventually_bits.then_some(&ebits),
+                );
+                if !generated.insert(next_key) {
+                    // FIXME: arriving at an already-known state may be a loop (in which case it
+                    // could, in a fancier implementation, be considered a terminal state for
+                    // purposes of eventually-property checking) but it might also be a join in
+                    // a DAG, which makes it non-terminal. These cases can be disambiguated (at
+                    // some cost), but for now we just _don't_ treat them as terminal, and tell
+                    // users they need to explicitly ensure model path-acyclicality when they're
+                    // using eventually properties (using a boundary or empty actions or
+                    // whatever).
+                    is_terminal = false;
+                    continue;
+                }
 
                 // Otherwise further checking is applicable.
+                //
+                // With symmetry reduction, the path continues with the state itself rather than
+                // its representative, as the previously collected path may not extend to the
+                // latter.
                 is_terminal = false;
                 let mut next_fingerprints = Vec::with_capacity(1 + fingerprints.len());
                 for f in &fingerprints {
                     next_fingerprints.push(*f);
                 }
-                next_fingerprints.push(next_fingerprint);
+                next_fingerprints.push(fingerprint(&next_state));
                 pending.push_back((
                     next_state,
                     next_fingerprints,
@@ -342,9 +470,22 @@ where
                 for (i, property) in properties.iter().enumerate() {
                     if ebits.contains(i) {
                         // Races other threads, but that's fine.
-                        discoveries.insert(property.name, fingerprints.clone());
+                        discoveries.insert(
+                            &property.name,
+                            *fingerprints.last().unwrap(),
+                            discovery(fingerprints.clone()),
+                        );
                     }
                 }
+                // A deadlocked state has no next states at all, even outside the boundary.
+                if deadlock_detection && is_deadlocked && !model.valid_end_state(&state) {
+                    // Races other threads, but that's fine.
+                    discoveries.insert(
+                        DEADLOCK,
+                        *fingerprints.last().unwrap(),
+                        discovery(fingerprints.clone()),
+                    );
+                }
             }
         }
     }
@@ -371,14 +512,51 @@ where
         self.max_depth.load(Ordering::Relaxed)
     }
 
-    fn discoveries(&self) -> HashMap<&'static str, Path<M::State, M::Action>> {
+    fn control(&self) -> Option<CheckerControl> {
+        Some(self.control.clone())
+    }
+
+    fn limit_reached(&self) -> Option<Limit> {
+        self.limits.reached()
+    }
+
+    fn omission_probability(&self) -> Option<f64> {
+        match &*self.generated {
+            Generated::Exact(_) => None,
+            Generated::Bitstate(bitstate) => Some(bitstate.omission_probability()),
+        }
+    }
+
+    fn properties(&self) -> Vec<Property<M>> {
+        checked_properties(&*self.model, self.deadlock_detection)
+    }
+
+    fn discoveries(&self) -> HashMap<String, Path<M::State, M::Action>> {
+        self.discoveries_all()
+            .into_iter()
+            .map(|(name, mut paths)| (name, paths.swap_remove(0)))
+            .collect()
+    }
+
+    fn discoveries_all(&self) -> HashMap<String, Vec<Path<M::State, M::Action>>> {
+        let properties = self.properties();
         self.discoveries
-            .iter()
-            .map(|mapref| {
-                (
-                    <&'static str>::clone(mapref.key()),
-                    Path::from_fingerprints(self.model(), VecDeque::from(mapref.value().clone())),
-                )
+            .all()
+            .into_iter()
+            .map(|(name, discoveries)| {
+                let paths = discoveries
+                    .into_iter()
+                    .map(|fingerprints| {
+                        let path =
+                            Path::from_fingerprints(self.model(), VecDeque::from(fingerprints));
+                        let path = complete_discovery(self.model(), &properties, &name, path);
+                        match self.shrink {
+                            Some(shrink) => shrink(self.model(), &properties, &name, path),
+                            None => path,
+                        }
+                    })
+                    .collect();
+                (name, paths)
             })
             .collect()
     }
@@ -388,7 +566,7 @@ where
     }
 
     fn is_done(&self) -> bool {
-        self.job_broker.is_closed() || self.discoveries.len() == self.model.properties().len()
+        self.job_broker.is_closed() || self.discoveries.complete_count() == self.properties().len()
     }
 }
 
@@ -476,79 +654,7 @@ mod test {
 
     #[test]
     fn can_apply_symmetry_reduction() {
-        use crate::actor::Id;
-        #[derive(Clone, Debug, Eq, Hash, PartialEq)]
-        struct Sys;
-        #[derive(Clone, Debug, Eq, Hash, PartialEq)]
-        struct SysState(Vec<ProcState>);
-        #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
-        enum ProcState {
-            // A process advances from `Loading` to `Running` to cycling between `Paused` and
-            // `Running`. There is no way for a process to move from `Loading` to `Paused` or any
-            // non-`Loading` state to `Loading`, but a previous implementation of symmetry
-            // reduction would mistakenly collect a path with an invalid step because it would
-            // enqueue the representative of a state rather than the original state.
-            //
-            // Here is an example of the steps that would manifest that bug:
-            //
-            // 1. System starts: `[Loading, Loading]`
-            // 2. Second process advances: `[Loading, Running]`
-            // 3. Second process advances: `[Loading, Paused]`
-            //
-            // But in the third state above, the representative function swaps the process order
-            // because `Paused < Loading`, so the collected path becomes:
-            //
-            // 1. `[Loading, Loading]`
-            // 2. `[Loading, Running]`
-            // 3. `[Paused, Loading]`
-            //
-            // Both `Loading -> Loading -> Paused` (process 0) and `Loading -> Running -> Loading`
-            // (process 1) are invalid.
-            Paused,
-            Loading,
-            Running,
-        }
-        impl Model for Sys {
-            type Action = Id;
-            type State = SysState;
-            fn init_states(&self) -> Vec<SysState> {
-                vec![SysState(vec![ProcState::Loading, ProcState::Loading])]
-            }
-            fn actions(&self, _: &Self::State, actions: &mut Vec<Self::Action>) {
-                // Either process can run next.
-                actions.push(Id::from(0));
-                actions.push(Id::from(1));
-            }
-            fn next_state(&self, state: &Self::State, action: Self::Action) -> Option<Self::State> {
-                let i = usize::from(action);
-                let mut state = state.clone();
-                match state.0[i] {
-                    ProcState::Loading => state.0[i] = ProcState::Running,
-                    ProcState::Running => state.0[i] = ProcState::Paused,
-                    ProcState::Paused => state.0[i] = ProcState::Running,
-                }
-                Some(state)
-            }
-            fn properties(&self) -> Vec<Property<Self>> {
-                vec![
-                    Property::<Self>::always("visit all states", |_, _| true),
-                    Property::<Self>::sometimes("a process pauses", |_, s| {
-                        s.0[0] == ProcState::Paused || s.0[1] == ProcState::Paused
-                    }),
-                ]
-            }
-        }
-        impl Representative for SysState {
-            fn representative(&self) -> Self {
-                let plan = RewritePlan::from_values_to_sort(&self.0);
-                SysState(plan.reindex(&self.0))
-            }
-        }
-        impl Rewrite<Id> for ProcState {
-            fn rewrite<S>(&self, _: &RewritePlan<Id, S>) -> Self {
-                self.clone()
-            }
-        }
+        use crate::test_util::symmetric_processes::*;
 
         // 9 states without symmetry reduction.
         let checker = Sys.checker().spawn_dfs().join();
@@ -563,6 +669,46 @@ mod test {
         assert_eq!(checker.unique_state_count(), 6);
     }
 
+    #[test]
+    fn can_apply_bitstate_hashing() {
+        // With ample bits, hash collisions are improbable, so the same states are visited.
+        let exact = LinearEquation { a: 2, b: 4, c: 7 }
+            .checker()
+            .target_max_depth(50)
+            .spawn_dfs()
+            .join();
+        let checker = LinearEquation { a: 2, b: 4, c: 7 }
+            .checker()
+            .bitstate(1 << 20, 3)
+            .target_max_depth(50)
+            .spawn_dfs()
+            .join();
+        checker.assert_no_discovery("solvable");
+        assert_eq!(checker.unique_state_count(), exact.unique_state_count());
+        assert!(checker.omission_probability().unwrap() < 1e-3);
+        assert_eq!(exact.omission_probability(), None);
+
+        // Discoveries still have paths.
+        let checker = LinearEquation { a: 2, b: 10, c: 14 }
+            .checker()
+            .bitstate(1 << 16, 3)
+            .spawn_dfs()
+            .join();
+        assert_eq!(
+            checker.discovery("solvable").unwrap().into_actions(),
+            vec![Guess::IncreaseY; 27]
+        );
+
+        // Otherwise states are omitted.
+        let checker = LinearEquation { a: 2, b: 4, c: 7 }
+            .checker()
+            .bitstate(1 << 10, 3)
+            .spawn_dfs()
+            .join();
+        assert!(checker.unique_state_count() < 1 << 10);
+        assert!(checker.omission_probability().unwrap() > 0.5);
+    }
+
     // test that the checker shuts down all threads properly after a checker thread encounters a
     // panic in the model execution.
     #[test]
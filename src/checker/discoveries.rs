@@ -0,0 +1,100 @@
+//! Private module for selective re-export.
+
+use crate::Fingerprint;
+use dashmap::DashMap;
+
+/// The discoveries of a checker by property name, each of which is represented by a checker
+/// specific value `D` from which the path can be reconstructed. Up to `max_per_property`
+/// discoveries are kept for each property, each ending in a distinct state, and a property is
+/// considered complete once that many have been found.
+pub(crate) struct Discoveries<D> {
+    max_per_property: usize,
+    discoveries: DashMap<String, Vec<(Fingerprint, D)>>,
+}
+
+impl<D: Clone> Discoveries<D> {
+    pub(crate) fn new(max_per_property: usize) -> Self {
+        Self {
+            max_per_property,
+            discoveries: DashMap::default(),
+        }
+    }
+
+    /// Indicates whether no further discoveries are sought for a property.
+    pub(crate) fn is_complete(&self, name: &str) -> bool {
+        self.discoveries
+            .get(name)
+            .is_some_and(|discoveries| discoveries.len() >= self.max_per_property)
+    }
+
+    /// The number of properties for which no further discoveries are sought.
+    pub(crate) fn complete_count(&self) -> usize {
+        self.discoveries
+            .iter()
+            .filter(|discoveries| discoveries.len() >= self.max_per_property)
+            .count()
+    }
+
+    /// Records a discovery that ends in the state with fingerprint `state_fp`, unless the property
+    /// is complete or already has a discovery ending in that state.
+    pub(crate) fn insert(&self, name: &str, state_fp: Fingerprint, discovery: D) {
+        let mut discoveries = self.discoveries.entry(name.to_string()).or_default();
+        if discoveries.len() < self.max_per_property
+            && discoveries.iter().all(|(fp, _)| *fp != state_fp)
+        {
+            discoveries.push((state_fp, discovery));
+        }
+    }
+
+    /// Returns the first discovery for a property, if any.
+    pub(crate) fn first(&self, name: &str) -> Option<D> {
+        let discoveries = self.discoveries.get(name)?;
+        discoveries.first().map(|(_, d)| d.clone())
+    }
+
+    /// Returns the discoveries for each property in the order in which they were found.
+    pub(crate) fn all(&self) -> Vec<(String, Vec<D>)> {
+        self.discoveries
+            .iter()
+            .map(|mapref| {
+                let discoveries = mapref.value().iter().map(|(_, d)| d.clone()).collect();
+                (mapref.key().clone(), discoveries)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::num::NonZeroU64;
+
+    fn fp(n: u64) -> Fingerprint {
+        NonZeroU64::new(n).unwrap()
+    }
+
+    #[test]
+    fn keeps_discoveries_ending_in_distinct_states() {
+        let discoveries = Discoveries::new(2);
+        discoveries.insert("p", fp(1), "a");
+        assert!(!discoveries.is_complete("p"));
+        discoveries.insert("p", fp(1), "b");
+        assert!(!discoveries.is_complete("p"));
+        discoveries.insert("p", fp(2), "c");
+        discoveries.insert("p", fp(3), "d");
+        discoveries.insert("q", fp(1), "e");
+        assert!(discoveries.is_complete("p"));
+        assert!(!discoveries.is_complete("q"));
+        assert_eq!(discoveries.complete_count(), 1);
+
+        let mut all = discoveries.all();
+        all.sort();
+        assert_eq!(
+            all,
+            vec![
+                ("p".to_string(), vec!["a", "c"]),
+                ("q".to_string(), vec!["e"])
+            ]
+        );
+    }
+}
@@ -0,0 +1,328 @@
+//! Private module for selective re-export.
+
+use crate::Fingerprint;
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// Configures a checker to keep some of its bookkeeping in files. See
+/// [`CheckerBuilder::disk_backed`](crate::CheckerBuilder::disk_backed).
+pub(crate) struct DiskOptions<State> {
+    pub(crate) dir: PathBuf,
+    pub(crate) memory_budget: usize,
+    pub(crate) serialize: fn(&State) -> Vec<u8>,
+    pub(crate) deserialize: fn(&[u8]) -> State,
+}
+
+impl<State> Clone for DiskOptions<State> {
+    fn clone(&self) -> Self {
+        Self {
+            dir: self.dir.clone(),
+            memory_budget: self.memory_budget,
+            serialize: self.serialize,
+            deserialize: self.deserialize,
+        }
+    }
+}
+
+/// The number of bytes in a record of a [`DiskFingerprints`] file or a checkpoint: the visit key,
+/// the visit key of the predecessor (zero if none), and the state fingerprint.
+pub(crate) const RECORD_LEN: usize = 24;
+
+/// The number of records that are read at once when looking up a fingerprint on disk.
+const PAGE_RECORDS: usize = 256;
+
+/// A set of visited fingerprints, each mapped to that of its predecessor and the fingerprint of
+/// the visited state. Recent insertions are kept in memory, and once there are more than fit the
+/// `capacity` they are merged into a file of records sorted by fingerprint. Only the first
+/// fingerprint of each page of that file is kept in memory, so a lookup reads at most one page.
+/// This is the approach taken by the TLC model checker's disk-based fingerprint set.
+pub(crate) struct DiskFingerprints {
+    path: PathBuf,
+    capacity: usize,
+    memory: HashMap<Fingerprint, (Option<Fingerprint>, Fingerprint)>,
+    file: Option<File>,
+    file_len: usize,
+    page_index: Vec<u64>,
+}
+
+impl DiskFingerprints {
+    /// Creates an empty set that stores up to `capacity` records in memory, spilling the rest to
+    /// a file named `name` in `dir`.
+    pub(crate) fn new(dir: &Path, name: &str, capacity: usize) -> Self {
+        std::fs::create_dir_all(dir).expect("Failed to create the checker's directory");
+        Self {
+            path: dir.join(name),
+            capacity: capacity.max(1),
+            memory: HashMap::new(),
+            file: None,
+            file_len: 0,
+            page_index: Vec::new(),
+        }
+    }
+
+    /// The number of fingerprints in the set.
+    pub(crate) fn len(&self) -> usize {
+        self.memory.len() + self.file_len
+    }
+
+    /// Returns the predecessor and state fingerprint recorded for a fingerprint.
+    pub(crate) fn get(&mut self, key: Fingerprint) -> Option<(Option<Fingerprint>, Fingerprint)> {
+        if let Some(record) = self.memory.get(&key) {
+            return Some(*record);
+        }
+        self.get_from_file(key)
+    }
+
+    /// Inserts a fingerprint unless it is already present, in which case `false` is returned.
+    pub(crate) fn insert(
+        &mut self,
+        key: Fingerprint,
+        prev: Option<Fingerprint>,
+        state_fp: Fingerprint,
+    ) -> bool {
+        if self.memory.contains_key(&key) || self.get_from_file(key).is_some() {
+            return false;
+        }
+        self.memory.insert(key, (prev, state_fp));
+        if self.memory.len() >= self.capacity {
+            self.flush();
+        }
+        true
+    }
+
+    fn get_from_file(&mut self, key: Fingerprint) -> Option<(Option<Fingerprint>, Fingerprint)> {
+        let file = self.file.as_mut()?;
+        let page = match self.page_index.binary_search(&key.get()) {
+            Ok(page) => page,
+            Err(0) => return None,
+            Err(page) => page - 1,
+        };
+        let first = page * PAGE_RECORDS;
+        let count = PAGE_RECORDS.min(self.file_len - first);
+        let mut bytes = vec![0; count * RECORD_LEN];
+        file.seek(SeekFrom::Start((first * RECORD_LEN) as u64))
+            .and_then(|_| file.read_exact(&mut bytes))
+            .expect("Failed to read fingerprints");
+        let records: Vec<_> = bytes.chunks(RECORD_LEN).map(decode_record).collect();
+        let i = records
+            .binary_search_by_key(&key.get(), |(k, _, _)| *k)
+            .ok()?;
+        let (_, prev, state_fp) = records[i];
+        Some((
+            Fingerprint::new(prev),
+            Fingerprint::new(state_fp).expect("Corrupt fingerprint file"),
+        ))
+    }
+
+    /// Merges the records in memory with those in the file.
+    fn flush(&mut self) {
+        let mut memory: Vec<_> = self
+            .memory
+            .drain()
+            .map(|(k, (prev, fp))| (k.get(), prev.map_or(0, |p| p.get()), fp.get()))
+            .collect();
+        memory.sort_unstable();
+
+        let merged_path = self.path.with_extension("merging");
+        let mut writer =
+            BufWriter::new(File::create(&merged_path).expect("Failed to create fingerprints"));
+        let mut page_index = Vec::new();
+        let mut merged_len = 0;
+        let mut write = |record: (u64, u64, u64)| {
+            if merged_len % PAGE_RECORDS == 0 {
+                page_index.push(record.0);
+            }
+            merged_len += 1;
+            writer
+                .write_all(&encode_record(record))
+                .expect("Failed to write fingerprints");
+        };
+        let mut memory = memory.into_iter().peekable();
+        if let Some(file) = self.file.take() {
+            let mut reader = BufReader::new(file);
+            reader
+                .seek(SeekFrom::Start(0))
+                .expect("Failed to read fingerprints");
+            let mut bytes = [0; RECORD_LEN];
+            for _ in 0..self.file_len {
+                reader
+                    .read_exact(&mut bytes)
+                    .expect("Failed to read fingerprints");
+                let record = decode_record(&bytes);
+                while memory.peek().is_some_and(|m| m.0 < record.0) {
+                    write(memory.next().unwrap());
+                }
+                write(record);
+            }
+        }
+        memory.for_each(&mut write);
+        writer.flush().expect("Failed to write fingerprints");
+        drop(writer);
+
+        std::fs::rename(&merged_path, &self.path).expect("Failed to replace fingerprints");
+        self.file = Some(File::open(&self.path).expect("Failed to open fingerprints"));
+        self.file_len = merged_len;
+        self.page_index = page_index;
+    }
+}
+
+impl Drop for DiskFingerprints {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+pub(crate) fn encode_record((key, prev, state_fp): (u64, u64, u64)) -> [u8; RECORD_LEN] {
+    let mut bytes = [0; RECORD_LEN];
+    bytes[0..8].copy_from_slice(&key.to_le_bytes());
+    bytes[8..16].copy_from_slice(&prev.to_le_bytes());
+    bytes[16..24].copy_from_slice(&state_fp.to_le_bytes());
+    bytes
+}
+
+pub(crate) fn decode_record(bytes: &[u8]) -> (u64, u64, u64) {
+    let word = |i: usize| u64::from_le_bytes(bytes[i..i + 8].try_into().unwrap());
+    (word(0), word(8), word(16))
+}
+
+/// A first-in-first-out queue of byte strings backed by files. Entries are appended to one file
+/// while being read from another, and the files swap roles once the one being read is exhausted.
+pub(crate) struct DiskQueue {
+    paths: [PathBuf; 2],
+    writer: BufWriter<File>,
+    reader: Option<BufReader<File>>,
+    written: usize,
+    unread: usize,
+}
+
+impl DiskQueue {
+    /// Creates an empty queue whose files are named after `name` within `dir`.
+    pub(crate) fn new(dir: &Path, name: &str) -> Self {
+        std::fs::create_dir_all(dir).expect("Failed to create the checker's directory");
+        let paths = [
+            dir.join(format!("{}.0", name)),
+            dir.join(format!("{}.1", name)),
+        ];
+        let writer = BufWriter::new(File::create(&paths[0]).expect("Failed to create queue"));
+        Self {
+            paths,
+            writer,
+            reader: None,
+            written: 0,
+            unread: 0,
+        }
+    }
+
+    /// The number of entries in the queue.
+    pub(crate) fn len(&self) -> usize {
+        self.written + self.unread
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Appends an entry to the end of the queue.
+    pub(crate) fn push(&mut self, entry: &[u8]) {
+        self.writer
+            .write_all(&(entry.len() as u64).to_le_bytes())
+            .and_then(|_| self.writer.write_all(entry))
+            .expect("Failed to write queue");
+        self.written += 1;
+    }
+
+    /// Removes the entry at the front of the queue.
+    pub(crate) fn pop(&mut self) -> Option<Vec<u8>> {
+        if self.unread == 0 {
+            if self.written == 0 {
+                return None;
+            }
+            // Read what has been written, and write to the other file.
+            self.writer.flush().expect("Failed to write queue");
+            self.paths.swap(0, 1);
+            let written = std::mem::replace(
+                &mut self.writer,
+                BufWriter::new(File::create(&self.paths[0]).expect("Failed to create queue")),
+            );
+            drop(written);
+            self.reader = Some(BufReader::new(
+                OpenOptions::new()
+                    .read(true)
+                    .open(&self.paths[1])
+                    .expect("Failed to open queue"),
+            ));
+            self.unread = std::mem::take(&mut self.written);
+        }
+        let reader = self.reader.as_mut().unwrap();
+        let mut len = [0; 8];
+        reader.read_exact(&mut len).expect("Failed to read queue");
+        let mut entry = vec![0; u64::from_le_bytes(len) as usize];
+        reader.read_exact(&mut entry).expect("Failed to read queue");
+        self.unread -= 1;
+        Some(entry)
+    }
+}
+
+impl Drop for DiskQueue {
+    fn drop(&mut self) {
+        for path in &self.paths {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn fp(n: u64) -> Fingerprint {
+        Fingerprint::new(n).unwrap()
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("stateright-{}-{}", name, std::process::id()))
+    }
+
+    #[test]
+    fn fingerprints_survive_flushes() {
+        let dir = temp_dir("fingerprints");
+        let mut fingerprints = DiskFingerprints::new(&dir, "fingerprints", 100);
+        // Inserted in an order that interleaves with the records already on disk.
+        let keys: Vec<u64> = (1..=1_000).map(|n| (n * 7_919) % 1_009 + 1).collect();
+        for &n in &keys {
+            assert!(fingerprints.insert(fp(n), Fingerprint::new(n - 1), fp(n + 10_000)));
+        }
+        assert_eq!(fingerprints.len(), 1_000);
+        assert!(!fingerprints.insert(fp(keys[0]), None, fp(1)));
+        for &n in &keys {
+            assert_eq!(
+                fingerprints.get(fp(n)),
+                Some((Fingerprint::new(n - 1), fp(n + 10_000)))
+            );
+        }
+        assert_eq!(fingerprints.get(fp(5_000)), None);
+        drop(fingerprints);
+        let _ = std::fs::remove_dir(dir);
+    }
+
+    #[test]
+    fn queue_is_first_in_first_out() {
+        let dir = temp_dir("queue");
+        let mut queue = DiskQueue::new(&dir, "queue");
+        queue.push(b"a");
+        queue.push(b"bb");
+        assert_eq!(queue.pop(), Some(b"a".to_vec()));
+        queue.push(b"");
+        queue.push(b"ccc");
+        assert_eq!(queue.len(), 3);
+        assert_eq!(queue.pop(), Some(b"bb".to_vec()));
+        assert_eq!(queue.pop(), Some(b"".to_vec()));
+        assert_eq!(queue.pop(), Some(b"ccc".to_vec()));
+        assert_eq!(queue.pop(), None);
+        assert!(queue.is_empty());
+        drop(queue);
+        let _ = std::fs::remove_dir(dir);
+    }
+}
@@ -0,0 +1,790 @@
+//! Private module for selective re-export.
+
+use crate::checker::discoveries::Discoveries;
+use crate::checker::{
+    checked_properties, complete_discovery, copy_actions, take_steps, violated_by, visit_key,
+    Checker, EventuallyBits, Expectation, Path, DEADLOCK,
+};
+use crate::{fingerprint, CheckerBuilder, Fingerprint, Model, Property};
+use nohash_hasher::NoHashHasher;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::hash::{BuildHasherDefault, Hash};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+/// The number of jobs for other workers that a worker accumulates before sending them on.
+const BATCH_SIZE: usize = 1_000;
+
+/// The number of jobs that a worker checks before reporting back to the coordinator.
+const BLOCK_SIZE: usize = 1_500;
+
+/// A state to check, along with its visit key, the visit key of the state from which it was
+/// generated (if any), its depth, and the eventually properties that have yet to hold on the path
+/// leading to it.
+#[derive(Deserialize, Serialize)]
+struct Job<State> {
+    state: State,
+    key: Fingerprint,
+    prev: Option<Fingerprint>,
+    depth: usize,
+    ebits: Vec<usize>,
+}
+
+/// The messages exchanged between the coordinator and a worker as lines of JSON.
+#[derive(Deserialize, Serialize)]
+enum Message<State> {
+    /// Informs a worker which part of the fingerprint space it owns.
+    Assign { index: usize, count: usize },
+    /// Jobs for a worker to check, or jobs that a worker generated for other workers.
+    Jobs(Vec<Job<State>>),
+    /// Informs the coordinator that a worker checked the jobs it was sent, including the jobs
+    /// those led to within its own part of the fingerprint space. Discoveries are identified by
+    /// visit key along with the fingerprint of the state in which they end.
+    Processed {
+        job_count: usize,
+        state_count: usize,
+        unique_state_count: usize,
+        max_depth: usize,
+        discoveries: Vec<(String, Fingerprint, Fingerprint)>,
+    },
+    /// Asks the worker that checked a state for the visit key of its predecessor and for its
+    /// fingerprint, which is how paths to discoveries are reconstructed.
+    Lookup(Fingerprint),
+    /// Answers a lookup.
+    Found {
+        prev: Option<Fingerprint>,
+        state_fp: Fingerprint,
+    },
+    /// Informs a worker that checking is done.
+    Stop,
+}
+
+fn send<State: Serialize>(writer: &mut impl Write, message: &Message<State>) -> io::Result<()> {
+    serde_json::to_writer(&mut *writer, message)?;
+    writer.write_all(b"\n")?;
+    writer.flush()
+}
+
+fn receive<State: DeserializeOwned>(
+    reader: &mut impl BufRead,
+) -> io::Result<Option<Message<State>>> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::from_str(&line)?))
+}
+
+fn unexpected(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Indicates which worker owns a visit key.
+fn owner(key: Fingerprint, worker_count: usize) -> usize {
+    (key.get() % worker_count as u64) as usize
+}
+
+/// Panics if the `options` include any that neither the coordinator nor the workers honor.
+fn assert_supported<M: Model>(options: &CheckerBuilder<M>) {
+    let unsupported = [
+        ("bitstate", options.bitstate.is_some()),
+        ("checkpoint", options.checkpoint.is_some()),
+        ("disk_backed", options.disk.is_some()),
+        ("partial_order_reduction", options.partial_order_reduction),
+        ("resume_from", options.resume.is_some()),
+        ("shrink_discoveries", options.shrink.is_some()),
+        ("target_duration", options.target_duration.is_some()),
+        ("target_memory", options.target_memory.is_some()),
+        ("visitor", options.visitor.is_some()),
+    ];
+    for (option, is_set) in unsupported {
+        assert!(!is_set, "Distributed checking does not support {}", option);
+    }
+}
+
+/// Coordinates workers that each check the states whose visit keys fall within their part of the
+/// fingerprint space. Jobs that a worker generates for other workers are routed through the
+/// coordinator, which tracks how many jobs are outstanding to determine when checking is done.
+pub(crate) struct DistributedChecker<M: Model> {
+    model: Arc<M>,
+    handles: Vec<JoinHandle<()>>,
+    state_count: Arc<AtomicUsize>,
+    unique_state_count: Arc<AtomicUsize>,
+    max_depth: Arc<AtomicUsize>,
+    deadlock_detection: bool,
+    discoveries: Arc<Discoveries<VecDeque<Fingerprint>>>,
+    done: Arc<AtomicBool>,
+}
+
+impl<M> DistributedChecker<M>
+where
+    M: Model + Send + Sync + 'static,
+    M::State: Hash + Serialize + DeserializeOwned + Send + 'static,
+{
+    pub(crate) fn spawn(
+        options: CheckerBuilder<M>,
+        listener: TcpListener,
+        worker_count: usize,
+    ) -> Self {
+        assert!(worker_count > 0, "Distributed checking requires a worker");
+        assert_supported(&options);
+        let model = Arc::new(options.model);
+        let symmetry = options.symmetry;
+        let target_state_count = options.target_state_count;
+        let track_eventually_bits = options.track_eventually_bits;
+        let deadlock_detection = options.deadlock_detection;
+
+        let init_states: Vec<_> = model
+            .init_states()
+            .into_iter()
+            .filter(|s| model.within_boundary(s))
+            .collect();
+        let ebits = {
+            let mut ebits = EventuallyBits::new();
+            for (i, p) in model.properties().iter().enumerate() {
+                if let Property {
+                    expectation: Expectation::Eventually,
+                    ..
+                } = p
+                {
+                    ebits.insert(i);
+                }
+            }
+            ebits
+        };
+        let state_count = Arc::new(AtomicUsize::new(init_states.len()));
+        let init_jobs: Vec<_> = init_states
+            .into_iter()
+            .map(|s| Job {
+                key: visit_key(&s, symmetry, track_eventually_bits.then_some(&ebits)),
+                prev: None,
+                depth: 1,
+                ebits: ebits.iter().collect(),
+                state: s,
+            })
+            .collect();
+
+        let unique_state_count = Arc::new(AtomicUsize::new(0));
+        let max_depth = Arc::new(AtomicUsize::new(0));
+        let discoveries = Arc::new(Discoveries::new(options.max_discoveries_per_property));
+        let done = Arc::new(AtomicBool::new(false));
+        let coordinator = Coordinator {
+            model: Arc::clone(&model),
+            target_state_count,
+            state_count: Arc::clone(&state_count),
+            unique_state_count: Arc::clone(&unique_state_count),
+            max_depth: Arc::clone(&max_depth),
+            deadlock_detection,
+            discoveries: Arc::clone(&discoveries),
+            unresolved_discoveries: Discoveries::new(options.max_discoveries_per_property),
+            outstanding: AtomicUsize::new(0),
+            is_finishing: AtomicBool::new(false),
+            is_stopping: AtomicBool::new(false),
+        };
+        let handle = {
+            let done = Arc::clone(&done);
+            std::thread::Builder::new()
+                .name("coordinator".to_string())
+                .spawn(move || {
+                    if let Err(err) = coordinator.run(listener, worker_count, init_jobs) {
+                        log::error!("Distributed checking failed: {}", err);
+                    }
+                    done.store(true, Ordering::Relaxed);
+                })
+                .expect("Failed to spawn a thread")
+        };
+        DistributedChecker {
+            model,
+            handles: vec![handle],
+            state_count,
+            unique_state_count,
+            max_depth,
+            deadlock_detection,
+            discoveries,
+            done,
+        }
+    }
+}
+
+struct Coordinator<M: Model> {
+    model: Arc<M>,
+    target_state_count: Option<NonZeroUsize>,
+    state_count: Arc<AtomicUsize>,
+    unique_state_count: Arc<AtomicUsize>,
+    max_depth: Arc<AtomicUsize>,
+    deadlock_detection: bool,
+    discoveries: Arc<Discoveries<VecDeque<Fingerprint>>>,
+    /// Discoveries identified by visit key, for which paths have yet to be reconstructed.
+    unresolved_discoveries: Discoveries<Fingerprint>,
+    /// The number of jobs sent to workers that they have yet to report as processed.
+    outstanding: AtomicUsize,
+    /// Whether jobs are no longer being routed, as checking is done aside from reconstructing
+    /// paths.
+    is_finishing: AtomicBool,
+    /// Whether the workers have been told to stop.
+    is_stopping: AtomicBool,
+}
+
+impl<M> Coordinator<M>
+where
+    M: Model + Send + Sync,
+    M::State: Serialize + DeserializeOwned + Send,
+{
+    fn run(
+        &self,
+        listener: TcpListener,
+        worker_count: usize,
+        init_jobs: Vec<Job<M::State>>,
+    ) -> io::Result<()> {
+        let mut workers = Vec::new();
+        for index in 0..worker_count {
+            let (stream, address) = listener.accept()?;
+            log::debug!("Worker {} connected from {}.", index, address);
+            // Messages are flushed as soon as they are complete, so they should not be delayed.
+            stream.set_nodelay(true)?;
+            let mut writer = BufWriter::new(stream.try_clone()?);
+            send(
+                &mut writer,
+                &Message::<M::State>::Assign {
+                    index,
+                    count: worker_count,
+                },
+            )?;
+            workers.push((BufReader::new(stream), writer));
+        }
+
+        // Each worker has a dedicated thread for writing so that routing jobs never blocks on a
+        // worker that is busy writing to the coordinator.
+        let (senders, receivers): (Vec<_>, Vec<_>) =
+            (0..worker_count).map(|_| mpsc::channel()).unzip();
+        let (finish_sender, finish_receiver) = mpsc::channel();
+        let (found_sender, found_receiver) = mpsc::channel();
+        self.route(&senders, init_jobs);
+        if self.outstanding.load(Ordering::SeqCst) == 0 {
+            self.finish(&finish_sender);
+        }
+        std::thread::scope(|scope| {
+            let mut handles = Vec::new();
+            for ((mut reader, mut writer), receiver) in workers.into_iter().zip(receivers) {
+                handles.push(scope.spawn(move || -> io::Result<()> {
+                    for message in receiver {
+                        let is_stop = matches!(message, Message::Stop);
+                        send(&mut writer, &message)?;
+                        if is_stop {
+                            break;
+                        }
+                    }
+                    Ok(())
+                }));
+                let senders = &senders;
+                let finish_sender = finish_sender.clone();
+                let found_sender = found_sender.clone();
+                handles.push(scope.spawn(move || {
+                    let result =
+                        self.receive_from(&mut reader, senders, &finish_sender, &found_sender);
+                    if result.is_err() {
+                        // Paths are not reconstructed, as the worker may have been needed.
+                        self.stop(senders);
+                        self.finish(&finish_sender);
+                        let _ = found_sender.send(None);
+                    }
+                    result
+                }));
+            }
+            drop(found_sender);
+
+            // Wait until checking is done, then reconstruct paths before stopping the workers.
+            let _ = finish_receiver.recv();
+            'resolve: for (name, keys) in self.unresolved_discoveries.all() {
+                for key in keys {
+                    let mut fingerprints = VecDeque::new();
+                    let mut next_key = Some(key);
+                    while let Some(key) = next_key {
+                        let _ = senders[owner(key, worker_count)].send(Message::Lookup(key));
+                        let Ok(Some((prev, state_fp))) = found_receiver.recv() else {
+                            break 'resolve;
+                        };
+                        fingerprints.push_front(state_fp);
+                        next_key = prev;
+                    }
+                    let state_fp = *fingerprints.back().expect("A path has at least one state");
+                    self.discoveries.insert(&name, state_fp, fingerprints);
+                }
+            }
+            self.stop(&senders);
+
+            let mut result = Ok(());
+            for handle in handles {
+                if let Err(err) = handle.join().expect("Failed to join coordinator thread") {
+                    result = Err(err);
+                }
+            }
+            result
+        })
+    }
+
+    /// Handles the messages from a worker until it disconnects.
+    fn receive_from(
+        &self,
+        reader: &mut impl BufRead,
+        senders: &[Sender<Message<M::State>>],
+        finish_sender: &Sender<()>,
+        found_sender: &Sender<Option<(Option<Fingerprint>, Fingerprint)>>,
+    ) -> io::Result<()> {
+        let properties = checked_properties(&*self.model, self.deadlock_detection);
+        loop {
+            match receive(reader)? {
+                None if self.is_stopping.load(Ordering::SeqCst) => return Ok(()),
+                None => return Err(unexpected("A worker disconnected before checking was done")),
+                Some(Message::Jobs(jobs)) => self.route(senders, jobs),
+                Some(Message::Processed {
+                    job_count,
+                    state_count,
+                    unique_state_count,
+                    max_depth,
+                    discoveries,
+                }) => {
+                    self.state_count.fetch_add(state_count, Ordering::Relaxed);
+                    self.unique_state_count
+                        .fetch_add(unique_state_count, Ordering::Relaxed);
+                    self.max_depth.fetch_max(max_depth, Ordering::Relaxed);
+                    for (name, key, state_fp) in discoveries {
+                        if properties.iter().all(|p| p.name != name) {
+                            return Err(unexpected("A worker discovered an unknown property"));
+                        }
+                        self.unresolved_discoveries.insert(&name, state_fp, key);
+                    }
+
+                    let is_exhausted =
+                        self.outstanding.fetch_sub(job_count, Ordering::SeqCst) == job_count;
+                    let is_discovered =
+                        self.unresolved_discoveries.complete_count() == properties.len();
+                    let is_at_target = self.target_state_count.is_some_and(|target| {
+                        target.get() <= self.state_count.load(Ordering::Relaxed)
+                    });
+                    if is_exhausted || is_discovered || is_at_target {
+                        self.finish(finish_sender);
+                    }
+                }
+                Some(Message::Found { prev, state_fp }) => {
+                    let _ = found_sender.send(Some((prev, state_fp)));
+                }
+                Some(_) => return Err(unexpected("Unexpected message from a worker")),
+            }
+        }
+    }
+
+    /// Sends jobs to the workers that own them.
+    fn route(&self, senders: &[Sender<Message<M::State>>], jobs: Vec<Job<M::State>>) {
+        if self.is_finishing.load(Ordering::SeqCst) {
+            return;
+        }
+        let mut batches: Vec<Vec<_>> = senders.iter().map(|_| Vec::new()).collect();
+        for job in jobs {
+            batches[owner(job.key, senders.len())].push(job);
+        }
+        for (sender, batch) in senders.iter().zip(batches) {
+            if batch.is_empty() {
+                continue;
+            }
+            // Counted before sending, so that the count cannot reach zero while the jobs are
+            // outstanding.
+            self.outstanding.fetch_add(batch.len(), Ordering::SeqCst);
+            // The worker is already stopping if it is no longer receiving.
+            let _ = sender.send(Message::Jobs(batch));
+        }
+    }
+
+    /// Stops routing jobs, so that workers become idle.
+    fn finish(&self, finish_sender: &Sender<()>) {
+        if !self.is_finishing.swap(true, Ordering::SeqCst) {
+            let _ = finish_sender.send(());
+        }
+    }
+
+    fn stop(&self, senders: &[Sender<Message<M::State>>]) {
+        if self.is_stopping.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        log::debug!("Stopping workers.");
+        for sender in senders {
+            let _ = sender.send(Message::Stop);
+        }
+    }
+}
+
+/// Checks the jobs that the coordinator at `coordinator` sends to this worker until checking is
+/// done.
+pub(crate) fn run_worker<M>(
+    options: CheckerBuilder<M>,
+    coordinator: impl ToSocketAddrs,
+) -> io::Result<()>
+where
+    M: Model,
+    M::State: Hash + Serialize + DeserializeOwned,
+{
+    assert_supported(&options);
+    let model = options.model;
+    let symmetry = options.symmetry;
+    let target_max_depth = options.target_max_depth;
+    let track_eventually_bits = options.track_eventually_bits;
+    let deadlock_detection = options.deadlock_detection;
+    let properties = model.properties();
+    let property_count = properties.len() + usize::from(deadlock_detection);
+
+    let stream = TcpStream::connect(coordinator)?;
+    stream.set_nodelay(true)?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = BufWriter::new(stream);
+    let (index, worker_count) = match receive::<M::State>(&mut reader)? {
+        Some(Message::Assign { index, count }) => (index, count),
+        _ => return Err(unexpected("Expected an assignment from the coordinator")),
+    };
+    log::debug!("Worker {} of {} started.", index, worker_count);
+
+    // Maps the visit key of each checked state to that of its predecessor and its fingerprint.
+    let mut generated: HashMap<
+        Fingerprint,
+        (Option<Fingerprint>, Fingerprint),
+        BuildHasherDefault<NoHashHasher<u64>>,
+    > = HashMap::default();
+    // The discoveries made by this worker, which are sent on to the coordinator as they are made.
+    let discovered = Discoveries::new(options.max_discoveries_per_property);
+    let mut actions = Vec::new();
+    loop {
+        let jobs = match receive(&mut reader)? {
+            None | Some(Message::Stop) => return Ok(()),
+            Some(Message::Jobs(jobs)) => jobs,
+            Some(Message::Lookup(key)) => {
+                let (prev, state_fp) = *generated
+                    .get(&key)
+                    .ok_or_else(|| unexpected("Lookup of a state that was not checked"))?;
+                send(&mut writer, &Message::<M::State>::Found { prev, state_fp })?;
+                continue;
+            }
+            Some(_) => return Err(unexpected("Unexpected message from the coordinator")),
+        };
+        let job_count = jobs.len();
+        let mut pending = VecDeque::from(jobs);
+        let mut outgoing = Vec::new();
+        let mut checked_count = 0;
+        let mut state_count = 0;
+        let mut unique_state_count = 0;
+        let mut max_depth = 0;
+        let mut discoveries = Vec::new();
+        let mut discover = |name: &str, key: Fingerprint, state_fp: Fingerprint| {
+            if !discovered.is_complete(name) {
+                discovered.insert(name, state_fp, ());
+                discoveries.push((name.to_string(), key, state_fp));
+            }
+        };
+        while let Some(Job {
+            state,
+            key,
+            prev,
+            depth,
+            ebits: ebit_indexes,
+        }) = pending.pop_front()
+        {
+            // Done if discoveries found for all properties.
+            if discovered.complete_count() == property_count {
+                break;
+            }
+
+            // Skip if already checked.
+            if generated.contains_key(&key) {
+                continue;
+            }
+            let state_fp = fingerprint(&state);
+            generated.insert(key, (prev, state_fp));
+            unique_state_count += 1;
+            max_depth = max_depth.max(depth);
+            if let Some(target_max_depth) = target_max_depth {
+                if depth >= target_max_depth.get() {
+                    continue;
+                }
+            }
+
+            let mut ebits = EventuallyBits::new();
+            for i in ebit_indexes {
+                ebits.insert(i);
+            }
+            let mut step_properties = Vec::new();
+            for (i, property) in properties.iter().enumerate() {
+                if discovered.is_complete(&property.name) {
+                    continue;
+                }
+                let is_discovery = match property.expectation {
+                    Expectation::Always => !property.holds(&model, &state),
+                    Expectation::AlwaysStep => {
+                        // Judged on the steps taken below.
+                        step_properties.push(property);
+                        false
+                    }
+                    Expectation::Sometimes => property.holds(&model, &state),
+                    Expectation::Eventually => {
+                        if property.holds(&model, &state) {
+                            ebits.remove(i);
+                        }
+                        false
+                    }
+                    // Only checked by the liveness checker.
+                    Expectation::Ltl => false,
+                };
+                if is_discovery {
+                    discover(&property.name, key, state_fp);
+                }
+            }
+
+            let mut is_terminal = true;
+            let mut is_deadlocked = true;
+            model.actions(&state, &mut actions);
+            let copies = copy_actions(&model, &state, &step_properties);
+            for (action, next_state) in take_steps(&model, &state, actions.drain(..), copies) {
+                is_deadlocked = false;
+                if !model.within_boundary(&next_state) {
+                    continue;
+                }
+                state_count += 1;
+                for property in violated_by(
+                    &model,
+                    &step_properties,
+                    &state,
+                    action.as_ref(),
+                    &next_state,
+                ) {
+                    discover(&property.name, key, state_fp);
+                }
+                is_terminal = false;
+                let next_key = visit_key(
+                    &next_state,
+                    symmetry,
+                    track_eventually_bits.then_some(&ebits),
+                );
+                let job = Job {
+                    state: next_state,
+                    key: next_key,
+                    prev: Some(key),
+                    depth: depth + 1,
+                    ebits: ebits.iter().collect(),
+                };
+                if owner(next_key, worker_count) == index {
+                    if !generated.contains_key(&next_key) {
+                        pending.push_back(job);
+                    }
+                } else {
+                    outgoing.push(job);
+                    if outgoing.len() >= BATCH_SIZE {
+                        send(&mut writer, &Message::Jobs(std::mem::take(&mut outgoing)))?;
+                    }
+                }
+            }
+            if is_terminal {
+                for (i, property) in properties.iter().enumerate() {
+                    if ebits.contains(i) {
+                        discover(&property.name, key, state_fp);
+                    }
+                }
+                // A deadlocked state has no next states at all, even outside the boundary.
+                if deadlock_detection && is_deadlocked && !model.valid_end_state(&state) {
+                    discover(DEADLOCK, key, state_fp);
+                }
+            }
+
+            // Remaining jobs are sent back via the coordinator, so that its messages are read.
+            checked_count += 1;
+            if checked_count == BLOCK_SIZE {
+                outgoing.extend(pending.drain(..));
+            }
+        }
+        if !outgoing.is_empty() {
+            send(&mut writer, &Message::Jobs(outgoing))?;
+        }
+        send(
+            &mut writer,
+            &Message::<M::State>::Processed {
+                job_count,
+                state_count,
+                unique_state_count,
+                max_depth,
+                discoveries,
+            },
+        )?;
+    }
+}
+
+impl<M> Checker<M> for DistributedChecker<M>
+where
+    M: Model,
+    M::State: Hash,
+{
+    fn model(&self) -> &M {
+        &self.model
+    }
+
+    fn state_count(&self) -> usize {
+        self.state_count.load(Ordering::Relaxed)
+    }
+
+    fn unique_state_count(&self) -> usize {
+        self.unique_state_count.load(Ordering::Relaxed)
+    }
+
+    fn max_depth(&self) -> usize {
+        self.max_depth.load(Ordering::Relaxed)
+    }
+
+    fn properties(&self) -> Vec<Property<M>> {
+        checked_properties(&*self.model, self.deadlock_detection)
+    }
+
+    fn discoveries(&self) -> HashMap<String, Path<M::State, M::Action>> {
+        self.discoveries_all()
+            .into_iter()
+            .map(|(name, mut paths)| (name, paths.swap_remove(0)))
+            .collect()
+    }
+
+    fn discoveries_all(&self) -> HashMap<String, Vec<Path<M::State, M::Action>>> {
+        let properties = self.properties();
+        self.discoveries
+            .all()
+            .into_iter()
+            .map(|(name, discoveries)| {
+                let paths = discoveries
+                    .into_iter()
+                    .map(|fingerprints| {
+                        let path = Path::from_fingerprints(self.model(), fingerprints);
+                        complete_discovery(self.model(), &properties, &name, path)
+                    })
+                    .collect();
+                (name, paths)
+            })
+            .collect()
+    }
+
+    fn handles(&mut self) -> Vec<JoinHandle<()>> {
+        std::mem::take(&mut self.handles)
+    }
+
+    fn is_done(&self) -> bool {
+        self.done.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::checker::DEADLOCK;
+    use crate::test_util::counter::Counter;
+    use crate::test_util::linear_equation_solver::*;
+    use crate::*;
+    use std::net::TcpListener;
+    use std::time::Duration;
+
+    fn check_distributed<M>(
+        builder: fn() -> CheckerBuilder<M>,
+        worker_count: usize,
+    ) -> impl Checker<M>
+    where
+        M: Model + Send + Sync + 'static,
+        M::State: Hash + Send + serde::Serialize + serde::de::DeserializeOwned + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let checker = builder().spawn_distributed(listener, worker_count);
+        let workers: Vec<_> = (0..worker_count)
+            .map(|_| std::thread::spawn(move || builder().run_worker(address)))
+            .collect();
+        let checker = checker.join();
+        for worker in workers {
+            worker.join().unwrap().unwrap();
+        }
+        checker
+    }
+
+    #[test]
+    fn can_complete_by_enumerating_all_states() {
+        let checker = check_distributed(|| LinearEquation { a: 2, b: 4, c: 7 }.checker(), 3);
+        assert!(checker.is_done());
+        checker.assert_no_discovery("solvable");
+        assert_eq!(checker.unique_state_count(), 256 * 256);
+        assert_eq!(checker.state_count(), 1 + 2 * 256 * 256);
+    }
+
+    #[test]
+    fn can_complete_by_eliminating_properties() {
+        let checker = check_distributed(|| LinearEquation { a: 2, b: 10, c: 14 }.checker(), 3);
+        checker.assert_properties();
+        let (x, y) = *checker.discovery("solvable").unwrap().last_state();
+        assert_eq!((2 * x as usize + 10 * y as usize) % 256, 14);
+    }
+
+    #[test]
+    fn can_stop_at_target_state_count() {
+        let checker = check_distributed(
+            || {
+                LinearEquation { a: 2, b: 4, c: 7 }
+                    .checker()
+                    .target_state_count(5_000)
+            },
+            1,
+        );
+        assert!(checker.is_done());
+        assert!(checker.state_count() >= 5_000);
+        assert!(checker.unique_state_count() < 256 * 256);
+    }
+
+    #[test]
+    fn can_detect_deadlocks() {
+        let checker = check_distributed(
+            || {
+                Counter::new(2)
+                    .with_jump("jam", 1, 100)
+                    .with_valid_end_state(2)
+                    .checker()
+                    .deadlock_detection()
+            },
+            2,
+        );
+        assert!(checker.is_done());
+        checker.assert_discovery(DEADLOCK, vec!["increment", "jam"]);
+    }
+
+    #[test]
+    fn can_collect_multiple_discoveries_per_property() {
+        let checker = check_distributed(
+            || {
+                LinearEquation { a: 2, b: 10, c: 14 }
+                    .checker()
+                    .max_discoveries_per_property(3)
+            },
+            3,
+        );
+        let paths = checker.discoveries_all().remove("solvable").unwrap();
+        assert_eq!(paths.len(), 3);
+        let mut last_states: Vec<_> = paths.iter().map(|path| *path.last_state()).collect();
+        last_states.sort();
+        last_states.dedup();
+        assert_eq!(last_states.len(), 3);
+        for (x, y) in last_states {
+            assert_eq!((2 * x as usize + 10 * y as usize) % 256, 14);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Distributed checking does not support target_duration")]
+    fn rejects_unsupported_options() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let _ = LinearEquation { a: 2, b: 4, c: 7 }
+            .checker()
+            .target_duration(Duration::from_secs(1))
+            .spawn_distributed(listener, 1);
+    }
+}
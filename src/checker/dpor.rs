@@ -0,0 +1,588 @@
+//! Private module for selective re-export.
+
+use crate::checker::discoveries::Discoveries;
+use crate::checker::{complete_discovery, violated_by, Checker, EventuallyBits, Expectation, Path};
+use crate::{fingerprint, CheckerBuilder, CheckerVisitor, Fingerprint, Model, Property};
+use dashmap::DashSet;
+use id_set::IdSet;
+use nohash_hasher::NoHashHasher;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::{BuildHasherDefault, Hash};
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+pub(crate) struct DporChecker<M: Model> {
+    // Immutable state.
+    model: Arc<M>,
+    handles: Vec<std::thread::JoinHandle<()>>,
+
+    // Mutable state.
+    state_count: Arc<AtomicUsize>,
+    max_depth: Arc<AtomicUsize>,
+    is_done: Arc<AtomicBool>,
+    generated: Arc<DashSet<Fingerprint, BuildHasherDefault<NoHashHasher<u64>>>>,
+    discoveries: Arc<Discoveries<Vec<Fingerprint>>>,
+}
+
+/// A state along the current path of the search, along with the bookkeeping for the steps that
+/// follow it.
+struct Frame<M: Model> {
+    state: M::State,
+    fingerprint: Fingerprint,
+    ebits: EventuallyBits,
+    /// The enabled actions, each paired with its next state until that state is explored.
+    steps: Vec<(M::Action, Option<M::State>)>,
+    /// Whether each step changes the outcome of a property's condition. Such steps are treated as
+    /// dependent on every other step so that no property discoveries are lost.
+    visible: Vec<bool>,
+    /// Steps that must be explored (the "backtrack" or "source" set).
+    backtrack: Vec<bool>,
+    /// Steps that have been explored.
+    done: Vec<bool>,
+    /// Actions that need not be explored because an equivalent interleaving has already been
+    /// explored (the "sleep" set).
+    sleep: Vec<M::Action>,
+    /// The step that is currently being explored.
+    taken: Option<usize>,
+    /// Indexes of the frames whose taken steps happen before this frame's taken step.
+    happens_before: IdSet,
+    /// Whether the state repeats an earlier state along the path.
+    is_on_path: bool,
+}
+
+impl<M> DporChecker<M>
+where
+    M: Model + Send + Sync + 'static,
+    M::State: Hash + Send + 'static,
+    M::Action: Clone + PartialEq,
+{
+    pub(crate) fn spawn(options: CheckerBuilder<M>) -> Self {
+        let model = Arc::new(options.model);
+        let target_state_count = options.target_state_count;
+        let target_max_depth = options.target_max_depth;
+        let visitor = options.visitor;
+
+        let state_count = Arc::new(AtomicUsize::new(0));
+        let max_depth = Arc::new(AtomicUsize::new(0));
+        let is_done = Arc::new(AtomicBool::new(false));
+        let generated = Arc::new(DashSet::default());
+        let discoveries = Arc::new(Discoveries::new(options.max_discoveries_per_property));
+
+        let handle = {
+            let model = Arc::clone(&model);
+            let state_count = Arc::clone(&state_count);
+            let max_depth = Arc::clone(&max_depth);
+            let is_done = Arc::clone(&is_done);
+            let generated = Arc::clone(&generated);
+            let discoveries = Arc::clone(&discoveries);
+            std::thread::Builder::new()
+                .name("checker-0".to_string())
+                .spawn(move || {
+                    log::debug!("Thread started.");
+                    let mut search = Search {
+                        model: &*model,
+                        properties: model.properties(),
+                        state_count: &state_count,
+                        max_depth: &max_depth,
+                        generated: &generated,
+                        discoveries: &discoveries,
+                        visitor: &visitor,
+                        target_state_count,
+                        target_max_depth,
+                        frames: Vec::new(),
+                        on_path: HashSet::new(),
+                    };
+                    search.run();
+                    is_done.store(true, Ordering::Relaxed);
+                    log::debug!("Search complete. Shutting down... gen={}", generated.len());
+                })
+                .expect("Failed to spawn a thread")
+        };
+        DporChecker {
+            model,
+            handles: vec![handle],
+            state_count,
+            max_depth,
+            is_done,
+            generated,
+            discoveries,
+        }
+    }
+}
+
+/// A stateless depth-first search that only explores one interleaving of independent steps, as
+/// described in "Dynamic Partial-Order Reduction for Model Checking Software" (Flanagan and
+/// Godefroid) with the refinements of "Optimal Dynamic Partial Order Reduction" (Abdulla et al).
+struct Search<'a, M: Model> {
+    model: &'a M,
+    properties: Vec<Property<M>>,
+    state_count: &'a AtomicUsize,
+    max_depth: &'a AtomicUsize,
+    generated: &'a DashSet<Fingerprint, BuildHasherDefault<NoHashHasher<u64>>>,
+    discoveries: &'a Discoveries<Vec<Fingerprint>>,
+    visitor: &'a Option<Box<dyn CheckerVisitor<M> + Send + Sync>>,
+    target_state_count: Option<NonZeroUsize>,
+    target_max_depth: Option<NonZeroUsize>,
+    frames: Vec<Frame<M>>,
+    /// Fingerprints of the states along the current path, used to cut cycles short.
+    on_path: HashSet<Fingerprint>,
+}
+
+impl<M> Search<'_, M>
+where
+    M: Model,
+    M::State: Hash,
+    M::Action: Clone + PartialEq,
+{
+    fn run(&mut self) {
+        let mut ebits = EventuallyBits::new();
+        for (i, property) in self.properties.iter().enumerate() {
+            if property.expectation == Expectation::Eventually {
+                ebits.insert(i);
+            }
+        }
+        for init_state in self.model.init_states() {
+            if !self.model.within_boundary(&init_state) {
+                continue;
+            }
+            self.state_count.fetch_add(1, Ordering::Relaxed);
+            if !self.push(init_state, ebits.clone(), Vec::new()) {
+                return;
+            }
+            while let Some(frame) = self.frames.last_mut() {
+                let next = (0..frame.steps.len()).find(|&t| {
+                    frame.backtrack[t] && !frame.done[t] && !frame.sleep.contains(&frame.steps[t].0)
+                });
+                let t = match next {
+                    None => {
+                        self.pop();
+                        continue;
+                    }
+                    Some(t) => t,
+                };
+                if !self.take(t) {
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Explores a step from the last frame. Returns `false` if checking should stop.
+    fn take(&mut self, t: usize) -> bool {
+        let n = self.frames.len() - 1;
+        let happens_before = self.happens_before(n, &self.frames[n].steps[t].0);
+        let frame = &mut self.frames[n];
+        frame.taken = Some(t);
+        frame.happens_before = happens_before;
+        let next_state = frame.steps[t].1.take().expect("step explored twice");
+
+        // Steps that were already explored or skipped from this state need not be explored after
+        // this step if they are independent of it, as doing so would reach the same states.
+        let frame = &self.frames[n];
+        let action = &frame.steps[t].0;
+        let explored = frame
+            .steps
+            .iter()
+            .enumerate()
+            .filter(|&(u, _)| frame.done[u])
+            .map(|(_, (a, _))| a)
+            .chain(&frame.sleep);
+        let mut sleep = Vec::new();
+        for other in explored {
+            let other_visible = frame
+                .steps
+                .iter()
+                .position(|(a, _)| a == other)
+                .map(|u| frame.visible[u]);
+            if other_visible == Some(false)
+                && !frame.visible[t]
+                && self.model.independent(&frame.state, action, other)
+                && !sleep.contains(other)
+            {
+                sleep.push(other.clone());
+            }
+        }
+        let ebits = frame.ebits.clone();
+        self.push(next_state, ebits, sleep)
+    }
+
+    /// Checks properties against a newly reached state and pushes a frame for it, recording
+    /// backtracking points for earlier frames based on the steps enabled in the state. Returns
+    /// `false` if checking should stop.
+    fn push(&mut self, state: M::State, mut ebits: EventuallyBits, sleep: Vec<M::Action>) -> bool {
+        let model = self.model;
+        let fp = fingerprint(&state);
+        self.generated.insert(fp);
+        let depth = self.frames.len() + 1;
+        self.max_depth.fetch_max(depth, Ordering::Relaxed);
+        let fingerprints = || {
+            let mut fingerprints: Vec<_> = self.frames.iter().map(|f| f.fingerprint).collect();
+            fingerprints.push(fp);
+            fingerprints
+        };
+        if let Some(visitor) = self.visitor {
+            visitor.visit(model, Path::from_fingerprints(model, fingerprints().into()));
+        }
+
+        let mut is_awaiting_discoveries = false;
+        let mut step_properties = Vec::new();
+        for (i, property) in self.properties.iter().enumerate() {
+            if self.discoveries.is_complete(&property.name) {
+                continue;
+            }
+            match property {
+                Property {
+                    expectation: Expectation::Always,
+                    ..
+                } => {
+                    if !property.holds(model, &state) {
+                        self.discoveries.insert(&property.name, fp, fingerprints());
+                    }
+                    is_awaiting_discoveries |= !self.discoveries.is_complete(&property.name);
+                }
+                Property {
+                    expectation: Expectation::AlwaysStep,
+                    ..
+                } => {
+                    // Judged on the steps taken below.
+                    step_properties.push(property);
+                    is_awaiting_discoveries = true;
+                }
+                Property {
+                    expectation: Expectation::Sometimes,
+                    ..
+                } => {
+                    if property.holds(model, &state) {
+                        self.discoveries.insert(&property.name, fp, fingerprints());
+                    }
+                    is_awaiting_discoveries |= !self.discoveries.is_complete(&property.name);
+                }
+                Property {
+                    expectation: Expectation::Eventually,
+                    ..
+                } => {
+                    is_awaiting_discoveries = true;
+                    if property.holds(model, &state) {
+                        ebits.remove(i);
+                    }
+                }
+                Property {
+                    expectation: Expectation::Ltl,
+                    ..
+                } => {
+                    // Only checked by the liveness checker.
+                }
+            }
+        }
+        if !is_awaiting_discoveries {
+            return false;
+        }
+        if let Some(target_state_count) = self.target_state_count {
+            if target_state_count.get() <= self.state_count.load(Ordering::Relaxed) {
+                return false;
+            }
+        }
+
+        // Steps are generated even if they will not be explored, as they may race with earlier
+        // steps.
+        let mut steps = Vec::new();
+        let mut visible = Vec::new();
+        let mut actions = Vec::new();
+        model.actions(&state, &mut actions);
+        for action in actions {
+            let next_state = match model.next_state(&state, action.clone()) {
+                None => continue,
+                Some(next_state) => next_state,
+            };
+            if !model.within_boundary(&next_state) {
+                continue;
+            }
+            for property in violated_by(model, &step_properties, &state, Some(&action), &next_state)
+            {
+                self.discoveries.insert(&property.name, fp, fingerprints());
+            }
+            visible.push(self.properties.iter().any(|p| {
+                // Every step is observed by a step property.
+                p.expectation == Expectation::AlwaysStep
+                    || p.expectation != Expectation::Ltl
+                        && p.holds(model, &state) != p.holds(model, &next_state)
+            }));
+            steps.push((action, Some(next_state)));
+        }
+        let is_past_max_depth = self
+            .target_max_depth
+            .is_some_and(|target_max_depth| depth >= target_max_depth.get());
+        let is_explorable = if is_past_max_depth {
+            log::trace!("Skipping state as past max depth {}", depth);
+            false
+        } else if self.on_path.contains(&fp) {
+            // A cycle, which would repeat the same behavior if followed.
+            false
+        } else {
+            self.state_count.fetch_add(steps.len(), Ordering::Relaxed);
+            if steps.is_empty() {
+                for (i, property) in self.properties.iter().enumerate() {
+                    if ebits.contains(i) {
+                        self.discoveries.insert(&property.name, fp, fingerprints());
+                    }
+                }
+            }
+            true
+        };
+
+        // Initially explore a single step, adding others only as needed.
+        let mut backtrack = vec![false; steps.len()];
+        if let Some(t) = steps.iter().position(|(a, _)| !sleep.contains(a)) {
+            backtrack[t] = is_explorable;
+        }
+        let is_on_path = !self.on_path.insert(fp);
+        self.frames.push(Frame {
+            state,
+            fingerprint: fp,
+            ebits,
+            done: vec![false; steps.len()],
+            steps,
+            visible,
+            backtrack,
+            sleep,
+            taken: None,
+            happens_before: IdSet::new(),
+            is_on_path,
+        });
+        let n = self.frames.len() - 1;
+        for t in 0..self.frames[n].steps.len() {
+            self.add_backtracking(n, t);
+        }
+        if n > 0 {
+            // Steps disabled by the step just taken conflict with it, so they must also be
+            // explored in its place.
+            let (previous, current) = self.frames.split_at_mut(n);
+            let previous = previous.last_mut().unwrap();
+            for (u, (a, _)) in previous.steps.iter().enumerate() {
+                if !current[0].steps.iter().any(|(b, _)| a == b) {
+                    previous.backtrack[u] = true;
+                }
+            }
+        }
+        true
+    }
+
+    /// Removes the last frame, marking its step as explored in the previous frame.
+    fn pop(&mut self) {
+        let frame = self.frames.pop().unwrap();
+        if !frame.is_on_path {
+            self.on_path.remove(&frame.fingerprint);
+        }
+        if let Some(parent) = self.frames.last_mut() {
+            if let Some(t) = parent.taken.take() {
+                parent.done[t] = true;
+            }
+        }
+    }
+
+    /// Whether the step taken from the `i`th frame is dependent on an action enabled in a later
+    /// frame, where `is_visible` indicates whether the action is visible in that later frame.
+    /// Either being visible in the `i`th frame also makes them dependent.
+    fn is_dependent(&self, i: usize, action: &M::Action, is_visible: bool) -> bool {
+        let frame = &self.frames[i];
+        let t = frame.taken.unwrap();
+        is_visible
+            || frame.visible[t]
+            || frame
+                .steps
+                .iter()
+                .position(|(a, _)| a == action)
+                .is_some_and(|u| frame.visible[u])
+            || !self
+                .model
+                .independent(&frame.state, &frame.steps[t].0, action)
+    }
+
+    /// The index of the frame whose taken step enabled an action enabled in the `n`th frame, if
+    /// the action was not enabled in every earlier frame.
+    fn enabler(&self, n: usize, action: &M::Action) -> Option<usize> {
+        (0..n)
+            .rev()
+            .find(|&k| !self.frames[k].steps.iter().any(|(a, _)| a == action))
+    }
+
+    /// The indexes of the frames whose taken steps happen before an action enabled in the `n`th
+    /// frame, either because they are dependent on it or because they (transitively) enabled it.
+    fn happens_before(&self, n: usize, action: &M::Action) -> IdSet {
+        let t = self.frames[n]
+            .steps
+            .iter()
+            .position(|(a, _)| a == action)
+            .unwrap();
+        let is_visible = self.frames[n].visible[t];
+        let mut happens_before = IdSet::new();
+        let direct = (0..n)
+            .filter(|&i| self.is_dependent(i, action, is_visible))
+            .chain(self.enabler(n, action));
+        for i in direct.collect::<Vec<_>>() {
+            happens_before.insert(i);
+            happens_before.inplace_union(&self.frames[i].happens_before);
+        }
+        happens_before
+    }
+
+    /// Ensures that a race between the `t`th step of the `n`th frame and the last dependent step
+    /// on the current path is reversed by a later exploration.
+    fn add_backtracking(&mut self, n: usize, t: usize) {
+        let (action, is_visible) = {
+            let frame = &self.frames[n];
+            (&frame.steps[t].0, frame.visible[t])
+        };
+        let i = match (0..n)
+            .rev()
+            .find(|&i| self.is_dependent(i, action, is_visible))
+        {
+            None => return,
+            Some(i) => i,
+        };
+        let to_add = match self.enabler(n, action) {
+            Some(k) if i <= k => {
+                if k == i || self.frames[k].happens_before.contains(i) {
+                    return; // the step cannot be taken before the one with which it races
+                }
+                // The step can only be taken earlier if the steps that enabled it are too, so
+                // start with the earliest of those that is independent of the racing step.
+                let initial = (i + 1..=k)
+                    .find(|&j| {
+                        (j == k || self.frames[k].happens_before.contains(j))
+                            && !self.frames[j].happens_before.contains(i)
+                    })
+                    .map(|j| {
+                        let frame = &self.frames[j];
+                        &frame.steps[frame.taken.unwrap()].0
+                    });
+                initial.and_then(|a| self.frames[i].steps.iter().position(|(b, _)| b == a))
+            }
+            _ => self.frames[i].steps.iter().position(|(b, _)| b == action),
+        };
+        let frame = &mut self.frames[i];
+        match to_add {
+            Some(u) => frame.backtrack[u] = true,
+            None => frame.backtrack.iter_mut().for_each(|b| *b = true),
+        }
+    }
+}
+
+impl<M> Checker<M> for DporChecker<M>
+where
+    M: Model,
+    M::State: Hash,
+{
+    fn model(&self) -> &M {
+        &self.model
+    }
+
+    fn state_count(&self) -> usize {
+        self.state_count.load(Ordering::Relaxed)
+    }
+
+    fn unique_state_count(&self) -> usize {
+        self.generated.len()
+    }
+
+    fn max_depth(&self) -> usize {
+        self.max_depth.load(Ordering::Relaxed)
+    }
+
+    fn discoveries(&self) -> HashMap<String, Path<M::State, M::Action>> {
+        self.discoveries_all()
+            .into_iter()
+            .map(|(name, mut paths)| (name, paths.swap_remove(0)))
+            .collect()
+    }
+
+    fn discoveries_all(&self) -> HashMap<String, Vec<Path<M::State, M::Action>>> {
+        let properties = self.properties();
+        self.discoveries
+            .all()
+            .into_iter()
+            .map(|(name, discoveries)| {
+                let paths = discoveries
+                    .into_iter()
+                    .map(|fingerprints| {
+                        let path =
+                            Path::from_fingerprints(self.model(), VecDeque::from(fingerprints));
+                        complete_discovery(self.model(), &properties, &name, path)
+                    })
+                    .collect();
+                (name, paths)
+            })
+            .collect()
+    }
+
+    fn handles(&mut self) -> Vec<JoinHandle<()>> {
+        std::mem::take(&mut self.handles)
+    }
+
+    fn is_done(&self) -> bool {
+        self.is_done.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::actor::actor_test_util::broadcast::broadcast_model;
+    use crate::actor::actor_test_util::ping_pong::PingPongCfg;
+    use crate::actor::{LossyNetwork, Network};
+    use crate::test_util::linear_equation_solver::*;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn explores_one_interleaving_of_independent_deliveries() {
+        let checker = broadcast_model().checker().spawn_bfs().join();
+        checker.assert_properties();
+        assert_eq!(checker.unique_state_count(), 8);
+
+        let checker = broadcast_model().checker().spawn_dpor().join();
+        checker.assert_properties();
+        assert_eq!(checker.unique_state_count(), 4);
+    }
+
+    #[test]
+    fn explores_every_interleaving_without_independence() {
+        let checker = LinearEquation { a: 2, b: 10, c: 14 }
+            .checker()
+            .target_max_depth(3)
+            .spawn_dpor()
+            .join();
+        assert_eq!(checker.unique_state_count(), 6); // (0,0) to (2,0), (1,1), and (0,2)
+        checker.assert_no_discovery("solvable");
+    }
+
+    #[test]
+    fn discovers_the_same_properties_as_dfs() {
+        for network in [
+            Network::new_unordered_duplicating([]),
+            Network::new_unordered_nonduplicating([]),
+            Network::new_ordered([]),
+        ] {
+            for lossy_network in [LossyNetwork::No, LossyNetwork::Yes] {
+                for maintains_history in [false, true] {
+                    let model = || {
+                        PingPongCfg {
+                            maintains_history,
+                            max_nat: 2,
+                        }
+                        .into_model()
+                        .init_network(network.clone())
+                        .lossy_network(lossy_network)
+                    };
+                    let discovered = |checker: &dyn Checker<_>| {
+                        checker.discoveries().into_keys().collect::<BTreeSet<_>>()
+                    };
+                    let dfs = model().checker().spawn_dfs().join();
+                    let dpor = model().checker().spawn_dpor().join();
+                    assert_eq!(discovered(&dfs), discovered(&dpor));
+                }
+            }
+        }
+    }
+}
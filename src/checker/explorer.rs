@@ -2,19 +2,20 @@ use crate::*;
 use parking_lot::RwLock;
 use serde::ser::{SerializeStruct, Serializer};
 use serde::Serialize;
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
 use std::net::ToSocketAddrs;
 use std::sync::Arc;
 use std::thread::{sleep, spawn};
 use std::time::Duration;
 use tiny_http::{Method, Response, ResponseBox, StatusCode};
 
-// (expectation, name, encoded path to discovery)
-type Property = (Expectation, String, Option<String>);
+// (expectation, name, encoded path to discovery, encoded paths to further discoveries)
+type Property = (Expectation, String, Option<String>, Vec<String>);
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
 struct StatusView {
     done: bool,
+    paused: bool,
     model: String,
     state_count: usize,
     unique_state_count: usize,
@@ -30,6 +31,7 @@ struct StateView<State> {
     state: Option<State>,
     properties: Vec<Property>,
     svg: Option<String>,
+    deadlock: bool,
 }
 
 impl<State> serde::Serialize for StateView<State>
@@ -54,6 +56,9 @@ where
         if let Some(ref svg) = self.svg {
             out.serialize_field("svg", svg)?;
         }
+        if self.deadlock {
+            out.serialize_field("deadlock", &true)?;
+        }
         out.end()
     }
 }
@@ -88,6 +93,7 @@ where
     let snapshot = Arc::new(RwLock::new(Snapshot(true, None)));
     let snapshot_for_visitor = Arc::clone(&snapshot);
     let snapshot_for_server = Arc::clone(&snapshot);
+    let symmetry = checker_builder.symmetry;
     spawn(move || loop {
         sleep(Duration::from_secs(4));
         snapshot.write().0 = true;
@@ -95,12 +101,14 @@ where
     let checker = checker_builder
         .visitor(snapshot_for_visitor)
         .spawn_on_demand();
-    serve_checker(checker, snapshot_for_server, addresses)
+    serve_checker(checker, snapshot_for_server, symmetry, addresses)
 }
 
+#[allow(clippy::type_complexity)]
 fn serve_checker<M, C>(
     checker: C,
     snapshot: Arc<RwLock<Snapshot<M::Action>>>,
+    symmetry: Option<fn(&M::State) -> M::State>,
     addresses: impl ToSocketAddrs,
 ) -> Arc<impl Checker<M>>
 where
@@ -142,9 +150,12 @@ where
                 Response::from_data(status_json).boxed()
             }
             (Method::Post, "/.runtocompletion") => run_to_completion(Arc::clone(&data)),
+            (Method::Post, "/.pause") => control(Arc::clone(&data), CheckerControl::pause),
+            (Method::Post, "/.resume") => control(Arc::clone(&data), CheckerControl::resume),
+            (Method::Post, "/.cancel") => control(Arc::clone(&data), CheckerControl::cancel),
             (Method::Get, url) => {
                 if let Some(fingerprints) = url.strip_prefix("/.states") {
-                    match states(fingerprints, Arc::clone(&data)) {
+                    match states(fingerprints, Arc::clone(&data), symmetry) {
                         Ok(states) => {
                             let states_json = serde_json::to_vec(&states).unwrap();
                             Response::from_data(states_json).boxed()
@@ -181,6 +192,7 @@ where
     StatusView {
         model: std::any::type_name::<M>().to_string(),
         done: checker.is_done(),
+        paused: checker.control().is_some_and(|c| c.is_paused()),
         state_count: checker.state_count(),
         unique_state_count: checker.unique_state_count(),
         max_depth: checker.max_depth(),
@@ -201,27 +213,50 @@ where
     Response::empty(StatusCode(200)).boxed()
 }
 
+fn control<M, C>(data: Data<M::Action, C>, request: fn(&CheckerControl)) -> ResponseBox
+where
+    M: Model,
+    C: Checker<M>,
+{
+    let checker = &data.1;
+    match checker.control() {
+        Some(control) => {
+            request(&control);
+            Response::empty(StatusCode(200)).boxed()
+        }
+        None => Response::empty(StatusCode(404)).boxed(),
+    }
+}
+
 fn get_properties<C, M>(checker: &Arc<C>) -> Vec<Property>
 where
     M: Model,
     M::State: Hash,
     C: Checker<M>,
 {
+    let mut discoveries = checker.discoveries_all();
     checker
-        .model()
         .properties()
         .into_iter()
         .map(|p| {
-            (
-                p.expectation,
-                p.name.to_string(),
-                checker.discovery(p.name).map(|p| p.encode()),
-            )
+            let mut paths = discoveries
+                .remove(&p.name)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|path| path.encode());
+            (p.expectation, p.name, paths.next(), paths.collect())
         })
         .collect()
 }
 
-fn states<M, C>(path: &str, data: Data<M::Action, C>) -> Result<Vec<StateView<M::State>>, String>
+/// Returns views of the states following a path. If a symmetry reduction function is specified,
+/// then states that are symmetric to an earlier state in the list are omitted.
+#[allow(clippy::type_complexity)]
+fn states<M, C>(
+    path: &str,
+    data: Data<M::Action, C>,
+    symmetry: Option<fn(&M::State) -> M::State>,
+) -> Result<Vec<StateView<M::State>>, String>
 where
     M: Model,
     M::Action: Debug,
@@ -247,10 +282,25 @@ where
         return Err(format!("Unable to parse fingerprints {}", fingerprints_str));
     }
 
+    // Deadlocked states are labeled if the checker detects them.
+    let deadlock = checker
+        .properties()
+        .into_iter()
+        .find(|p| p.name == crate::checker::DEADLOCK);
+    let is_deadlock = |state: &M::State| deadlock.as_ref().is_some_and(|p| !p.holds(model, state));
+
     // now build up all the subsequent `StateView`s
     let mut results = Vec::new();
+    let mut representatives = HashSet::new();
+    let mut is_symmetric_to_earlier = |state: &M::State| match symmetry {
+        Some(representative) => !representatives.insert(fingerprint(&representative(state))),
+        None => false,
+    };
     if fingerprints.is_empty() {
         for state in model.init_states() {
+            if is_symmetric_to_earlier(&state) {
+                continue;
+            }
             let fingerprint = fingerprint(&state);
             checker.check_fingerprint(fingerprint);
             let svg = {
@@ -261,6 +311,7 @@ where
             results.push(StateView {
                 action: None,
                 outcome: None,
+                deadlock: is_deadlock(&state),
                 state: Some(state),
                 properties: get_properties(checker),
                 svg,
@@ -284,6 +335,9 @@ where
                 fingerprint(&state)
             );
             if let Some(state) = state {
+                if is_symmetric_to_earlier(&state) {
+                    continue;
+                }
                 let fingerprint = fingerprint(&state);
                 checker.check_fingerprint(fingerprint);
                 let svg = {
@@ -294,6 +348,7 @@ where
                 results.push(StateView {
                     action: Some(model.format_action(&action)),
                     outcome,
+                    deadlock: is_deadlock(&state),
                     state: Some(state),
                     properties: get_properties(checker),
                     svg,
@@ -306,6 +361,7 @@ where
                     state: None,
                     properties: get_properties(checker),
                     svg: None,
+                    deadlock: false,
                 });
             }
         }
@@ -336,15 +392,17 @@ mod test {
                     action: None,
                     outcome: None,
                     state: Some(0),
-                    properties: vec![(Expectation::Always, "in [0, 1]".to_owned(), None)],
-                    svg: None
+                    properties: vec![(Expectation::Always, "in [0, 1]".to_owned(), None, vec![])],
+                    svg: None,
+                    deadlock: false,
                 },
                 StateView {
                     action: None,
                     outcome: None,
                     state: Some(1),
-                    properties: vec![(Expectation::Always, "in [0, 1]".to_owned(), None)],
-                    svg: None
+                    properties: vec![(Expectation::Always, "in [0, 1]".to_owned(), None, vec![])],
+                    svg: None,
+                    deadlock: false,
                 },
             ]
         );
@@ -374,8 +432,9 @@ mod test {
                 action: Some("GoHigh".to_string()),
                 outcome: Some("1".to_string()),
                 state: Some(1),
-                properties: vec![(Expectation::Always, "in [0, 1]".to_owned(), None)],
+                properties: vec![(Expectation::Always, "in [0, 1]".to_owned(), None, vec![])],
                 svg: None,
+                deadlock: false,
             },]
         );
     }
@@ -432,14 +491,15 @@ mod test {
                         ]),
                     }),
                     properties: vec![
-                        (Expectation::Always, "delta within 1".into(), None),
-                        (Expectation::Sometimes, "can reach max".into(), Some("9825351251631602636/3760012235735042049/7133060688412568841/11622042860899162315".into())),
-                        (Expectation::Eventually, "must reach max".into(), Some("9825351251631602636/3760012235735042049/7133060688412568841/11622042860899162315/5132103924661761264/12325952466011360495".into())),
-                        (Expectation::Eventually, "must exceed max".into(), Some("9825351251631602636/3760012235735042049/7133060688412568841/11622042860899162315/5132103924661761264/12325952466011360495".into())),
-                        (Expectation::Always, "#in <= #out".into(), None),
-                        (Expectation::Eventually, "#out <= #in + 1".into(), None),
+                        (Expectation::Always, "delta within 1".into(), None, vec![]),
+                        (Expectation::Sometimes, "can reach max".into(), Some("9825351251631602636/3760012235735042049/7133060688412568841/11622042860899162315".into()), vec![]),
+                        (Expectation::Eventually, "must reach max".into(), Some("9825351251631602636/3760012235735042049/7133060688412568841/11622042860899162315/5132103924661761264/12325952466011360495".into()), vec![]),
+                        (Expectation::Eventually, "must exceed max".into(), Some("9825351251631602636/3760012235735042049/7133060688412568841/11622042860899162315/5132103924661761264/12325952466011360495".into()), vec![]),
+                        (Expectation::Always, "#in <= #out".into(), None, vec![]),
+                        (Expectation::Eventually, "#out <= #in + 1".into(), None, vec![]),
                     ],
                     svg: Some("<svg version=\'1.1\' baseProfile=\'full\' width=\'500\' height=\'30\' viewbox=\'-20 -20 520 50\' xmlns=\'http://www.w3.org/2000/svg\'><defs><marker class=\'svg-event-shape\' id=\'arrow\' markerWidth=\'12\' markerHeight=\'10\' refX=\'12\' refY=\'5\' orient=\'auto\'><polygon points=\'0 0, 12 5, 0 10\' /></marker></defs><line x1=\'0\' y1=\'0\' x2=\'0\' y2=\'30\' class=\'svg-actor-timeline\' />\n<text x=\'0\' y=\'0\' class=\'svg-actor-label\'>0</text>\n<line x1=\'100\' y1=\'0\' x2=\'100\' y2=\'30\' class=\'svg-actor-timeline\' />\n<text x=\'100\' y=\'0\' class=\'svg-actor-label\'>1</text>\n</svg>\n".to_string()),
+                    deadlock: false,
                 },
             ]);
 
@@ -475,14 +535,15 @@ mod test {
                     network: Network::new_unordered_nonduplicating([]),
                 }),
                 properties: vec![
-                    (Expectation::Always, "delta within 1".into(), None),
-                    (Expectation::Sometimes, "can reach max".into(), Some("9825351251631602636/3760012235735042049/7133060688412568841/11622042860899162315".into())),
-                    (Expectation::Eventually, "must reach max".into(), Some("9825351251631602636/3760012235735042049/7133060688412568841/11622042860899162315/5132103924661761264/12325952466011360495".into())),
-                    (Expectation::Eventually, "must exceed max".into(), Some("9825351251631602636/3760012235735042049/7133060688412568841/11622042860899162315/5132103924661761264/12325952466011360495".into())),
-                    (Expectation::Always, "#in <= #out".into(), None),
-                    (Expectation::Eventually, "#out <= #in + 1".into(), None),
+                    (Expectation::Always, "delta within 1".into(), None, vec![]),
+                    (Expectation::Sometimes, "can reach max".into(), Some("9825351251631602636/3760012235735042049/7133060688412568841/11622042860899162315".into()), vec![]),
+                    (Expectation::Eventually, "must reach max".into(), Some("9825351251631602636/3760012235735042049/7133060688412568841/11622042860899162315/5132103924661761264/12325952466011360495".into()), vec![]),
+                    (Expectation::Eventually, "must exceed max".into(), Some("9825351251631602636/3760012235735042049/7133060688412568841/11622042860899162315/5132103924661761264/12325952466011360495".into()), vec![]),
+                    (Expectation::Always, "#in <= #out".into(), None, vec![]),
+                    (Expectation::Eventually, "#out <= #in + 1".into(), None, vec![]),
                 ],
                 svg: Some("<svg version='1.1' baseProfile='full' width='500' height='60' viewbox='-20 -20 520 80' xmlns='http://www.w3.org/2000/svg'><defs><marker class='svg-event-shape' id='arrow' markerWidth='12' markerHeight='10' refX='12' refY='5' orient='auto'><polygon points='0 0, 12 5, 0 10' /></marker></defs><line x1='0' y1='0' x2='0' y2='60' class='svg-actor-timeline' />\n<text x='0' y='0' class='svg-actor-label'>0</text>\n<line x1='100' y1='0' x2='100' y2='60' class='svg-actor-timeline' />\n<text x='100' y='0' class='svg-actor-label'>1</text>\n</svg>\n".to_string()),
+                deadlock: false,
             });
         assert_eq!(
             states[1],
@@ -502,17 +563,36 @@ mod test {
                     ]),
                 }),
                 properties: vec![
-                    (Expectation::Always, "delta within 1".into(), None),
-                    (Expectation::Sometimes, "can reach max".into(), Some("9825351251631602636/3760012235735042049/7133060688412568841/11622042860899162315".into())),
-                    (Expectation::Eventually, "must reach max".into(), Some("9825351251631602636/3760012235735042049/7133060688412568841/11622042860899162315/5132103924661761264/12325952466011360495".into())),
-                    (Expectation::Eventually, "must exceed max".into(), Some("9825351251631602636/3760012235735042049/7133060688412568841/11622042860899162315/5132103924661761264/12325952466011360495".into())),
-                    (Expectation::Always, "#in <= #out".into(), None),
-                    (Expectation::Eventually, "#out <= #in + 1".into(), None),
+                    (Expectation::Always, "delta within 1".into(), None, vec![]),
+                    (Expectation::Sometimes, "can reach max".into(), Some("9825351251631602636/3760012235735042049/7133060688412568841/11622042860899162315".into()), vec![]),
+                    (Expectation::Eventually, "must reach max".into(), Some("9825351251631602636/3760012235735042049/7133060688412568841/11622042860899162315/5132103924661761264/12325952466011360495".into()), vec![]),
+                    (Expectation::Eventually, "must exceed max".into(), Some("9825351251631602636/3760012235735042049/7133060688412568841/11622042860899162315/5132103924661761264/12325952466011360495".into()), vec![]),
+                    (Expectation::Always, "#in <= #out".into(), None, vec![]),
+                    (Expectation::Eventually, "#out <= #in + 1".into(), None, vec![]),
                 ],
                 svg: Some("<svg version='1.1' baseProfile='full' width='500' height='60' viewbox='-20 -20 520 80' xmlns='http://www.w3.org/2000/svg'><defs><marker class='svg-event-shape' id='arrow' markerWidth='12' markerHeight='10' refX='12' refY='5' orient='auto'><polygon points='0 0, 12 5, 0 10' /></marker></defs><line x1='0' y1='0' x2='0' y2='60' class='svg-actor-timeline' />\n<text x='0' y='0' class='svg-actor-label'>0</text>\n<line x1='100' y1='0' x2='100' y2='60' class='svg-actor-timeline' />\n<text x='100' y='0' class='svg-actor-label'>1</text>\n<line x1='0' x2='100' y1='0' y2='30' marker-end='url(#arrow)' class='svg-event-line' />\n<text x='100' y='30' class='svg-event-label'>Ping(0)</text>\n</svg>\n".to_string()),
+                deadlock: false,
             });
     }
 
+    #[test]
+    fn can_omit_symmetric_states() {
+        use crate::test_util::symmetric_processes::*;
+
+        let checker = Arc::new(Sys.checker().spawn_bfs().join());
+        let data = Arc::new((Arc::new(RwLock::new(Snapshot(true, None))), checker));
+        let path = format!("/{}", fingerprint(&Sys.init_states()[0]));
+
+        // Either process can run first, which results in symmetric states.
+        let all = states(&path, Arc::clone(&data), None).unwrap();
+        assert_eq!(all.len(), 2);
+        let collapsed = states(&path, data, Some(SysState::representative)).unwrap();
+        assert_eq!(
+            collapsed.into_iter().map(|s| s.state).collect::<Vec<_>>(),
+            vec![Some(SysState(vec![ProcState::Running, ProcState::Loading]))]
+        );
+    }
+
     #[test]
     fn smoke_test_status() {
         use crate::actor::actor_test_util::ping_pong::PingPongCfg;
@@ -548,7 +628,7 @@ mod test {
             let match_found = status
                 .properties
                 .iter()
-                .any(|(e, n, d)| e == &expectation && n == name && d.is_some() == has_discovery);
+                .any(|(e, n, d, _)| e == &expectation && n == name && d.is_some() == has_discovery);
             if !match_found {
                 panic!(
                     "Not found. expectation={:?}, name={:?}, has_discovery={:?}, properties={:#?}",
@@ -577,7 +657,7 @@ mod test {
     {
         let snapshot = Arc::new(RwLock::new(Snapshot(true, None)));
         let data = Arc::new((snapshot, checker));
-        states(path_name, data)
+        states(path_name, data, None)
     }
 
     fn get_status<M, C>(checker: Arc<C>, snapshot: Arc<RwLock<Snapshot<M::Action>>>) -> StatusView
@@ -0,0 +1,404 @@
+//! Private module for selective re-export.
+
+use crate::checker::checked_properties;
+use crate::{fingerprint, CheckerVisitor, Expectation, Fingerprint, Model, Path};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+/// A file format for [`StateGraph::write`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GraphFormat {
+    /// The [DOT](https://graphviz.org/doc/info/lang.html) language of Graphviz.
+    Dot,
+    /// The XML based [GraphML](http://graphml.graphdrawing.org/) format.
+    GraphMl,
+}
+
+/// The states evaluated by the model checker and the transitions from them, as recorded by a
+/// [`GraphRecorder`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct StateGraph {
+    nodes: BTreeMap<Fingerprint, Node>,
+    edges: BTreeMap<(Fingerprint, String, Fingerprint), BTreeSet<String>>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Node {
+    label: String,
+    is_init: bool,
+    examples: BTreeSet<String>,
+    counterexamples: BTreeSet<String>,
+}
+
+impl Node {
+    fn new(label: String) -> Self {
+        Node {
+            label,
+            is_init: false,
+            examples: BTreeSet::new(),
+            counterexamples: BTreeSet::new(),
+        }
+    }
+
+    fn discoveries(&self) -> String {
+        let mut discoveries: Vec<_> = self.counterexamples.iter().cloned().collect();
+        discoveries.extend(self.examples.iter().cloned());
+        discoveries.join(", ")
+    }
+}
+
+impl StateGraph {
+    /// The number of states, which includes states reached by a recorded transition that were not
+    /// themselves evaluated.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// The number of distinct transitions.
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Writes the graph in the given `format`. States are labeled with their [`Debug`]
+    /// representation and transitions with [`Model::format_action`]. Initial states are drawn
+    /// with a double border, while states that are discoveries for a property are colored (red for
+    /// counterexamples and green for examples) and annotated with the property names, as are the
+    /// transitions that violate an [`always_step`](crate::Property::always_step) property.
+    pub fn write(&self, writer: &mut impl Write, format: GraphFormat) -> std::io::Result<()> {
+        match format {
+            GraphFormat::Dot => self.write_dot(writer),
+            GraphFormat::GraphMl => self.write_graphml(writer),
+        }
+    }
+
+    fn write_dot(&self, w: &mut impl Write) -> std::io::Result<()> {
+        writeln!(w, "digraph {{")?;
+        for (fp, node) in &self.nodes {
+            write!(w, "  s{} [label=\"{}\"", fp, escape_dot(&node.label))?;
+            if node.is_init {
+                write!(w, ", peripheries=2")?;
+            }
+            if !node.counterexamples.is_empty() || !node.examples.is_empty() {
+                let (color, fillcolor) = if node.counterexamples.is_empty() {
+                    ("green", "honeydew")
+                } else {
+                    ("red", "mistyrose")
+                };
+                write!(
+                    w,
+                    ", color={}, style=filled, fillcolor={}, xlabel=\"{}\"",
+                    color,
+                    fillcolor,
+                    escape_dot(&node.discoveries())
+                )?;
+            }
+            writeln!(w, "];")?;
+        }
+        for ((src, action, dst), violations) in &self.edges {
+            write!(w, "  s{} -> s{} [label=\"{}", src, dst, escape_dot(action))?;
+            if violations.is_empty() {
+                writeln!(w, "\"];")?;
+            } else {
+                let names: Vec<_> = violations.iter().cloned().collect();
+                writeln!(
+                    w,
+                    " ({})\", color=red, fontcolor=red];",
+                    escape_dot(&names.join(", "))
+                )?;
+            }
+        }
+        writeln!(w, "}}")
+    }
+
+    fn write_graphml(&self, w: &mut impl Write) -> std::io::Result<()> {
+        writeln!(w, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(
+            w,
+            r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#
+        )?;
+        for (id, domain, name, ty) in [
+            ("label", "node", "label", "string"),
+            ("init", "node", "init", "boolean"),
+            ("counterexamples", "node", "counterexamples", "string"),
+            ("examples", "node", "examples", "string"),
+            ("action", "edge", "action", "string"),
+            ("violations", "edge", "violations", "string"),
+        ] {
+            writeln!(
+                w,
+                r#"  <key id="{}" for="{}" attr.name="{}" attr.type="{}"/>"#,
+                id, domain, name, ty
+            )?;
+        }
+        writeln!(w, r#"  <graph edgedefault="directed">"#)?;
+        let data = |w: &mut dyn Write, key: &str, value: &str| {
+            writeln!(
+                w,
+                r#"      <data key="{}">{}</data>"#,
+                key,
+                escape_xml(value)
+            )
+        };
+        for (fp, node) in &self.nodes {
+            writeln!(w, r#"    <node id="s{}">"#, fp)?;
+            data(w, "label", &node.label)?;
+            data(w, "init", &node.is_init.to_string())?;
+            for (key, names) in [
+                ("counterexamples", &node.counterexamples),
+                ("examples", &node.examples),
+            ] {
+                if !names.is_empty() {
+                    let names: Vec<_> = names.iter().cloned().collect();
+                    data(w, key, &names.join(", "))?;
+                }
+            }
+            writeln!(w, "    </node>")?;
+        }
+        for ((src, action, dst), violations) in &self.edges {
+            writeln!(w, r#"    <edge source="s{}" target="s{}">"#, src, dst)?;
+            data(w, "action", action)?;
+            if !violations.is_empty() {
+                let names: Vec<_> = violations.iter().cloned().collect();
+                data(w, "violations", &names.join(", "))?;
+            }
+            writeln!(w, "    </edge>")?;
+        }
+        writeln!(w, "  </graph>")?;
+        writeln!(w, "</graphml>")
+    }
+}
+
+fn escape_dot(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// A [`CheckerVisitor`] that records the state graph explored by the model checker: every
+/// evaluated state along with its transitions to the next states within the model's boundary.
+/// The states that are discoveries for a property are also recorded, as judged by the path to
+/// the state. The graph can then be written to a file for external tools via
+/// [`StateGraph::write`]. Intended for small state spaces.
+///
+/// Deadlocked states are only recorded as discoveries if requested via
+/// [`GraphRecorder::deadlock_detection`], as the recorder cannot tell whether
+/// [`CheckerBuilder::deadlock_detection`](crate::CheckerBuilder::deadlock_detection) is enabled.
+///
+/// # Example
+///
+/// ```
+/// # use stateright::*; let model = ();
+/// let (recorder, accessor) = GraphRecorder::new_with_accessor();
+/// model.checker().visitor(recorder).spawn_bfs().join();
+/// let mut dot = Vec::new();
+/// accessor().write(&mut dot, GraphFormat::Dot).unwrap();
+/// assert!(String::from_utf8(dot).unwrap().starts_with("digraph {"));
+/// ```
+pub struct GraphRecorder {
+    graph: Arc<Mutex<StateGraph>>,
+    deadlock_detection: bool,
+}
+impl<M> CheckerVisitor<M> for GraphRecorder
+where
+    M: Model,
+    M::Action: Debug,
+    M::State: Debug + Hash,
+{
+    fn visit(&self, model: &M, path: Path<M::State, M::Action>) {
+        let states = path.into_states();
+        let state = states.last().unwrap();
+        let fp = fingerprint(state);
+        let next_steps: Vec<_> = model
+            .next_steps(state)
+            .into_iter()
+            .filter(|(_, next_state)| model.within_boundary(next_state))
+            .collect();
+        let properties = checked_properties(model, self.deadlock_detection);
+
+        let mut graph = self.graph.lock().unwrap();
+        let node = graph
+            .nodes
+            .entry(fp)
+            .or_insert_with(|| Node::new(format!("{:?}", state)));
+        node.is_init |= states.len() == 1;
+        for property in &properties {
+            let name = property.name.clone();
+            match property.expectation {
+                Expectation::Always if !property.holds(model, state) => {
+                    node.counterexamples.insert(name);
+                }
+                Expectation::Sometimes if property.holds(model, state) => {
+                    node.examples.insert(name);
+                }
+                Expectation::Eventually
+                    if next_steps.is_empty()
+                        && states.iter().all(|s| !property.holds(model, s)) =>
+                {
+                    node.counterexamples.insert(name);
+                }
+                _ => {}
+            }
+        }
+        for (action, next_state) in next_steps {
+            let next_fp = fingerprint(&next_state);
+            let violations = properties
+                .iter()
+                .filter_map(|property| match &property.step_condition {
+                    Some(step_condition) if !step_condition(model, state, &action, &next_state) => {
+                        Some(property.name.clone())
+                    }
+                    _ => None,
+                })
+                .collect();
+            graph
+                .nodes
+                .entry(next_fp)
+                .or_insert_with(|| Node::new(format!("{:?}", next_state)));
+            graph
+                .edges
+                .insert((fp, model.format_action(&action), next_fp), violations);
+        }
+    }
+}
+impl GraphRecorder {
+    /// Instantiates a ([`GraphRecorder`], accessor) pair.
+    pub fn new_with_accessor() -> (Self, impl Fn() -> StateGraph) {
+        let recorder = Self {
+            graph: Arc::new(Mutex::new(Default::default())),
+            deadlock_detection: false,
+        };
+        let accessor = {
+            let r = Arc::clone(&recorder.graph);
+            move || r.lock().unwrap().clone()
+        };
+        (recorder, accessor)
+    }
+
+    /// Also records the `"deadlock"` discoveries of
+    /// [`CheckerBuilder::deadlock_detection`](crate::CheckerBuilder::deadlock_detection).
+    pub fn deadlock_detection(self) -> Self {
+        Self {
+            deadlock_detection: true,
+            ..self
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::counter::Counter;
+    use crate::{Checker, Property};
+
+    /// Counts to 2, or jumps from 0 to 2.
+    fn counter() -> Counter {
+        Counter::new(2)
+            .with_jump("jump", 0, 2)
+            .with_property(Property::always("below 2", |_, state| *state < 2))
+            .with_property(Property::sometimes("reaches 1", |_, state| *state == 1))
+            .with_property(Property::eventually("reaches 1 eventually", |_, state| {
+                *state == 1
+            }))
+            .with_property(Property::always_step("steps by 1", |_, prev, _, next| {
+                *next == prev + 1
+            }))
+    }
+
+    fn graph() -> StateGraph {
+        let (recorder, accessor) = GraphRecorder::new_with_accessor();
+        counter().checker().visitor(recorder).spawn_bfs().join();
+        accessor()
+    }
+
+    #[test]
+    fn records_states_and_transitions() {
+        let graph = graph();
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(graph.edge_count(), 3);
+    }
+
+    #[test]
+    fn can_record_deadlocks() {
+        let (recorder, accessor) = GraphRecorder::new_with_accessor();
+        counter()
+            .checker()
+            .deadlock_detection()
+            .visitor(recorder.deadlock_detection())
+            .spawn_bfs()
+            .join();
+        let deadlocked: Vec<_> = accessor()
+            .nodes
+            .into_values()
+            .filter(|node| node.counterexamples.contains("deadlock"))
+            .map(|node| node.label)
+            .collect();
+        assert_eq!(deadlocked, vec!["2"]);
+    }
+
+    #[test]
+    fn can_write_dot() {
+        let mut written = Vec::new();
+        graph().write(&mut written, GraphFormat::Dot).unwrap();
+        let dot = String::from_utf8(written).unwrap();
+        let (s0, s1, s2) = (fingerprint(&0_u8), fingerprint(&1_u8), fingerprint(&2_u8));
+        assert!(dot.starts_with("digraph {\n"));
+        for line in [
+            format!("  s{} [label=\"0\", peripheries=2];", s0),
+            format!(
+                "  s{} [label=\"1\", color=green, style=filled, fillcolor=honeydew, \
+                 xlabel=\"reaches 1\"];",
+                s1
+            ),
+            format!(
+                "  s{} [label=\"2\", color=red, style=filled, fillcolor=mistyrose, \
+                 xlabel=\"below 2, reaches 1 eventually\"];",
+                s2
+            ),
+            format!("  s{} -> s{} [label=\"\\\"increment\\\"\"];", s0, s1),
+            format!(
+                "  s{} -> s{} [label=\"\\\"jump\\\" (steps by 1)\", color=red, fontcolor=red];",
+                s0, s2
+            ),
+        ] {
+            assert!(dot.contains(&line), "{} not in {}", line, dot);
+        }
+        assert!(dot.ends_with("}\n"));
+    }
+
+    #[test]
+    fn can_write_graphml() {
+        let mut written = Vec::new();
+        graph().write(&mut written, GraphFormat::GraphMl).unwrap();
+        let graphml = String::from_utf8(written).unwrap();
+        let (s0, s2) = (fingerprint(&0_u8), fingerprint(&2_u8));
+        for line in [
+            format!(
+                "    <node id=\"s{}\">\n\
+                 \x20     <data key=\"label\">0</data>\n\
+                 \x20     <data key=\"init\">true</data>\n\
+                 \x20   </node>",
+                s0
+            ),
+            format!(
+                "    <edge source=\"s{}\" target=\"s{}\">\n\
+                 \x20     <data key=\"action\">&quot;jump&quot;</data>\n\
+                 \x20     <data key=\"violations\">steps by 1</data>\n\
+                 \x20   </edge>",
+                s0, s2
+            ),
+        ] {
+            assert!(graphml.contains(&line), "{} not in {}", line, graphml);
+        }
+    }
+}
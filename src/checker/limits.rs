@@ -0,0 +1,114 @@
+//! Private module for selective re-export.
+
+use std::fmt::Display;
+use std::num::NonZeroUsize;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// A limit that ended checking before the state space was exhausted. See
+/// [`Checker::limit_reached`](crate::Checker::limit_reached).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Limit {
+    /// See [`CheckerBuilder::target_state_count`](crate::CheckerBuilder::target_state_count).
+    StateCount,
+    /// See [`CheckerBuilder::target_duration`](crate::CheckerBuilder::target_duration).
+    Duration,
+    /// See [`CheckerBuilder::target_memory`](crate::CheckerBuilder::target_memory).
+    Memory,
+}
+
+impl Display for Limit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Limit::StateCount => write!(f, "target_state_count"),
+            Limit::Duration => write!(f, "target_duration"),
+            Limit::Memory => write!(f, "target_memory"),
+        }
+    }
+}
+
+/// The limits of a checker run, which are shared by the checker's threads so that they stop
+/// together.
+pub(crate) struct Limits {
+    state_count: Option<NonZeroUsize>,
+    deadline: Option<Instant>,
+    memory: Option<usize>,
+    reached: OnceLock<Limit>,
+}
+
+impl Limits {
+    /// Starts the clock for the `duration` limit, if any.
+    pub(crate) fn new(
+        state_count: Option<NonZeroUsize>,
+        duration: Option<Duration>,
+        memory: Option<usize>,
+    ) -> Self {
+        Self {
+            state_count,
+            deadline: duration.map(|duration| Instant::now() + duration),
+            memory,
+            reached: OnceLock::new(),
+        }
+    }
+
+    /// Returns the limit that has been reached given the number of states generated so far. Once
+    /// a limit is reached, it is returned for all later calls.
+    pub(crate) fn check(&self, state_count: usize) -> Option<Limit> {
+        if let Some(reached) = self.reached.get() {
+            return Some(*reached);
+        }
+        let reached = if self.state_count.is_some_and(|c| c.get() <= state_count) {
+            Limit::StateCount
+        } else if self.deadline.is_some_and(|d| d <= Instant::now()) {
+            Limit::Duration
+        } else if self
+            .memory
+            .is_some_and(|m| resident_memory().is_some_and(|r| m <= r))
+        {
+            Limit::Memory
+        } else {
+            return None;
+        };
+        Some(*self.reached.get_or_init(|| reached))
+    }
+
+    /// Returns the limit that has been reached, if any.
+    pub(crate) fn reached(&self) -> Option<Limit> {
+        self.reached.get().copied()
+    }
+}
+
+/// Returns the resident set size of this process in bytes, which is only known on Linux.
+fn resident_memory() -> Option<usize> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find_map(|l| l.strip_prefix("VmRSS:"))?;
+    let kilobytes: usize = line.trim().strip_suffix("kB")?.trim().parse().ok()?;
+    Some(kilobytes * 1024)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn records_first_limit_reached() {
+        let limits = Limits::new(NonZeroUsize::new(10), None, None);
+        assert_eq!(limits.check(9), None);
+        assert_eq!(limits.reached(), None);
+        assert_eq!(limits.check(10), Some(Limit::StateCount));
+        assert_eq!(limits.check(0), Some(Limit::StateCount));
+
+        let limits = Limits::new(NonZeroUsize::new(10), Some(Duration::ZERO), None);
+        assert_eq!(limits.check(0), Some(Limit::Duration));
+        assert_eq!(limits.check(10), Some(Limit::Duration));
+        assert_eq!(limits.reached(), Some(Limit::Duration));
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn can_measure_resident_memory() {
+        assert!(resident_memory().unwrap() > 0);
+        let limits = Limits::new(None, None, Some(1));
+        assert_eq!(limits.check(0), Some(Limit::Memory));
+    }
+}
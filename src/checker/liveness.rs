@@ -0,0 +1,1099 @@
+//! Private module for selective re-export.
+
+use crate::checker::control::CheckerControl;
+use crate::checker::discoveries::Discoveries;
+use crate::checker::limits::{Limit, Limits};
+use crate::checker::ltl::{Automaton, LtlPredicate};
+use crate::checker::{
+    checked_properties, complete_discovery, copy_actions, violated_by, Checker, EventuallyBits,
+    Expectation, Path, DEADLOCK,
+};
+use crate::{fingerprint, CheckerBuilder, CheckerVisitor, Fairness, Fingerprint, Model, Property};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+/// The number of states that the checker explores between checking its limits and acting on
+/// [`CheckerControl`] requests.
+const BLOCK_SIZE: usize = 1_500;
+
+/// Fingerprints of a discovery path, along with the index at which its loop begins (if any).
+type Lasso = (Vec<Fingerprint>, Option<usize>);
+
+/// The fairness constraint of the action corresponding to an edge. See [`Model::fairness`].
+type Label = Option<(Fairness, u64)>;
+
+pub(crate) struct LivenessChecker<M: Model> {
+    // Immutable state.
+    model: Arc<M>,
+    handles: Vec<std::thread::JoinHandle<()>>,
+    deadlock_detection: bool,
+
+    // Mutable state.
+    state_count: Arc<AtomicUsize>,
+    unique_state_count: Arc<AtomicUsize>,
+    max_depth: Arc<AtomicUsize>,
+    is_done: Arc<AtomicBool>,
+    discoveries: Arc<Discoveries<Lasso>>,
+    limits: Arc<Limits>,
+    control: CheckerControl,
+}
+
+impl<M> LivenessChecker<M>
+where
+    M: Model + Send + Sync + 'static,
+    M::State: Hash + Send + 'static,
+{
+    pub(crate) fn spawn(options: CheckerBuilder<M>) -> Self {
+        let model = Arc::new(options.model);
+        let deadlock_detection = options.deadlock_detection;
+        let control = CheckerControl::new();
+        let limits = Arc::new(Limits::new(
+            options.target_state_count,
+            options.target_duration,
+            options.target_memory,
+        ));
+        let target_max_depth = options.target_max_depth;
+        let visitor = options.visitor;
+
+        let state_count = Arc::new(AtomicUsize::new(0));
+        let unique_state_count = Arc::new(AtomicUsize::new(0));
+        let max_depth = Arc::new(AtomicUsize::new(0));
+        let is_done = Arc::new(AtomicBool::new(false));
+        let discoveries = Arc::new(Discoveries::new(options.max_discoveries_per_property));
+
+        let handle = {
+            let model = Arc::clone(&model);
+            let state_count = Arc::clone(&state_count);
+            let unique_state_count = Arc::clone(&unique_state_count);
+            let max_depth = Arc::clone(&max_depth);
+            let is_done = Arc::clone(&is_done);
+            let discoveries = Arc::clone(&discoveries);
+            let limits = Arc::clone(&limits);
+            let control = control.clone();
+            std::thread::Builder::new()
+                .name("checker-0".to_string())
+                .spawn(move || {
+                    log::debug!("Thread started.");
+                    let properties = model.properties();
+                    let mut predicates = Vec::new();
+                    let automata: Vec<_> = properties
+                        .iter()
+                        .map(|p| {
+                            p.formula
+                                .as_ref()
+                                .map(|f| Automaton::for_violations_of(f, &mut predicates))
+                        })
+                        .collect();
+                    let graph = Graph::explore(
+                        &*model,
+                        &predicates,
+                        &state_count,
+                        &unique_state_count,
+                        &max_depth,
+                        &discoveries,
+                        &visitor,
+                        deadlock_detection,
+                        &limits,
+                        &control,
+                        target_max_depth,
+                    );
+                    log::debug!("Exploration complete. gen={}", graph.nodes.len());
+                    for (i, property) in properties.iter().enumerate() {
+                        if !control.wait_while_paused() {
+                            log::debug!("Cancelled. Shutting down...");
+                            break;
+                        }
+                        // Only a single behavior is sought for liveness properties.
+                        if discoveries.first(&property.name).is_some() {
+                            continue;
+                        }
+                        let lasso = match (&property.expectation, &automata[i]) {
+                            (Expectation::Eventually, _) => graph.find_lasso(&|n: usize| {
+                                let node = &graph.nodes[n];
+                                node.is_expanded && node.unsatisfied.contains(i)
+                            }),
+                            (Expectation::Ltl, Some(automaton)) => graph.find_violation(automaton),
+                            _ => None,
+                        };
+                        if let Some((path, loop_start)) = lasso {
+                            let fingerprints: Vec<_> =
+                                path.iter().map(|&n| graph.nodes[n].fp).collect();
+                            let state_fp = *fingerprints.last().unwrap();
+                            discoveries.insert(
+                                &property.name,
+                                state_fp,
+                                (fingerprints, loop_start),
+                            );
+                        }
+                    }
+                    is_done.store(true, Ordering::Relaxed);
+                    log::debug!("Liveness analysis complete. Shutting down...");
+                })
+                .expect("Failed to spawn a thread")
+        };
+        LivenessChecker {
+            model,
+            handles: vec![handle],
+            deadlock_detection,
+            state_count,
+            unique_state_count,
+            max_depth,
+            is_done,
+            discoveries,
+            limits,
+            control,
+        }
+    }
+}
+
+/// A state within the explicitly stored state graph.
+struct Node {
+    fp: Fingerprint,
+    parent: Option<usize>,
+    /// Whether the state's successors have been generated. A state might not be expanded if it is
+    /// past the target max depth, for example, in which case its behaviors are unknown.
+    is_expanded: bool,
+    /// Indexes of the `eventually` properties that do not hold for this state.
+    unsatisfied: EventuallyBits,
+    /// Indexes of the [`Ltl`](crate::Ltl) predicates that hold for this state.
+    predicates: id_set::IdSet,
+}
+
+/// The reachable state graph of a model, which unlike the graph implied by other checkers retains
+/// every edge so that cycles can be identified.
+#[derive(Default)]
+struct Graph {
+    nodes: Vec<Node>,
+    successors: Vec<Vec<usize>>,
+    /// The label of each edge in `successors`.
+    labels: Vec<Vec<Label>>,
+    inits: Vec<usize>,
+    index: HashMap<Fingerprint, usize>,
+}
+
+impl Graph {
+    /// Explores the model in breadth-first order, checking `always` and `sometimes` properties
+    /// (and for deadlocks if `deadlock_detection` is set) along the way.
+    #[allow(clippy::too_many_arguments)]
+    fn explore<M>(
+        model: &M,
+        predicates: &[LtlPredicate<M>],
+        state_count: &AtomicUsize,
+        unique_state_count: &AtomicUsize,
+        global_max_depth: &AtomicUsize,
+        discoveries: &Discoveries<Lasso>,
+        visitor: &Option<Box<dyn CheckerVisitor<M> + Send + Sync>>,
+        deadlock_detection: bool,
+        limits: &Limits,
+        control: &CheckerControl,
+        target_max_depth: Option<NonZeroUsize>,
+    ) -> Self
+    where
+        M: Model,
+        M::State: Hash,
+    {
+        let properties = model.properties();
+        let property_count = properties.len() + usize::from(deadlock_detection);
+        let mut graph = Graph::default();
+        let mut pending = VecDeque::new();
+        for init_state in model.init_states() {
+            if !model.within_boundary(&init_state) {
+                continue;
+            }
+            state_count.fetch_add(1, Ordering::Relaxed);
+            let (n, is_new) = graph.insert(model, &properties, predicates, &init_state, None);
+            if is_new {
+                graph.inits.push(n);
+                pending.push_back((init_state, n, NonZeroUsize::new(1).unwrap()));
+            }
+        }
+
+        let mut actions = Vec::new();
+        let mut explored_count = 0;
+        while let Some((state, n, depth)) = pending.pop_front() {
+            unique_state_count.store(graph.nodes.len(), Ordering::Relaxed);
+            global_max_depth.fetch_max(depth.get(), Ordering::Relaxed);
+            if discoveries.complete_count() == property_count {
+                break;
+            }
+            if explored_count % BLOCK_SIZE == 0 {
+                if !control.wait_while_paused() {
+                    log::debug!("Cancelled. Stopping exploration.");
+                    break;
+                }
+                if let Some(limit) = limits.check(state_count.load(Ordering::Relaxed)) {
+                    log::debug!("Reached {} limit. Stopping exploration.", limit);
+                    break;
+                }
+            }
+            explored_count += 1;
+            if let Some(target_max_depth) = target_max_depth {
+                if depth >= target_max_depth {
+                    log::trace!("Skipping state as past max depth {}", depth);
+                    continue;
+                }
+            }
+            if let Some(visitor) = visitor {
+                let fingerprints = graph.path_to(n).map(|n| graph.nodes[n].fp).collect();
+                visitor.visit(model, Path::from_fingerprints(model, fingerprints));
+            }
+
+            let mut step_properties = Vec::new();
+            for property in &properties {
+                if discoveries.is_complete(&property.name) {
+                    continue;
+                }
+                let is_discovery = match property.expectation {
+                    Expectation::Always => !property.holds(model, &state),
+                    Expectation::AlwaysStep => {
+                        // Judged on the steps taken below.
+                        step_properties.push(property);
+                        false
+                    }
+                    Expectation::Sometimes => property.holds(model, &state),
+                    Expectation::Eventually | Expectation::Ltl => false,
+                };
+                if is_discovery {
+                    let fingerprints = graph.path_to(n).map(|n| graph.nodes[n].fp).collect();
+                    discoveries.insert(&property.name, graph.nodes[n].fp, (fingerprints, None));
+                }
+            }
+
+            graph.nodes[n].is_expanded = true;
+            let mut is_deadlocked = true;
+            model.actions(&state, &mut actions);
+            let mut copies = copy_actions(model, &state, &step_properties).into_iter();
+            for action in actions.drain(..) {
+                let label = model.fairness(&action);
+                let copy = copies.next();
+                let next_state = match model.next_state(&state, action) {
+                    None => continue,
+                    Some(next_state) => next_state,
+                };
+                is_deadlocked = false;
+                if !model.within_boundary(&next_state) {
+                    continue;
+                }
+                for property in
+                    violated_by(model, &step_properties, &state, copy.as_ref(), &next_state)
+                {
+                    let fingerprints = graph.path_to(n).map(|n| graph.nodes[n].fp).collect();
+                    discoveries.insert(&property.name, graph.nodes[n].fp, (fingerprints, None));
+                }
+                state_count.fetch_add(1, Ordering::Relaxed);
+                let (next_n, is_new) =
+                    graph.insert(model, &properties, predicates, &next_state, Some(n));
+                graph.successors[n].push(next_n);
+                graph.labels[n].push(label);
+                if is_new {
+                    pending.push_back((
+                        next_state,
+                        next_n,
+                        NonZeroUsize::new(depth.get() + 1).unwrap(),
+                    ));
+                }
+            }
+            // A deadlocked state has no next states at all, even outside the boundary.
+            if deadlock_detection && is_deadlocked && !model.valid_end_state(&state) {
+                let fingerprints = graph.path_to(n).map(|n| graph.nodes[n].fp).collect();
+                discoveries.insert(DEADLOCK, graph.nodes[n].fp, (fingerprints, None));
+            }
+        }
+        unique_state_count.store(graph.nodes.len(), Ordering::Relaxed);
+        graph
+    }
+
+    /// Returns the index of the node for a state, inserting one if necessary.
+    fn insert<M>(
+        &mut self,
+        model: &M,
+        properties: &[Property<M>],
+        predicates: &[LtlPredicate<M>],
+        state: &M::State,
+        parent: Option<usize>,
+    ) -> (usize, bool)
+    where
+        M: Model,
+        M::State: Hash,
+    {
+        let fp = fingerprint(state);
+        if let Some(&n) = self.index.get(&fp) {
+            return (n, false);
+        }
+        let mut unsatisfied = EventuallyBits::new();
+        for (i, property) in properties.iter().enumerate() {
+            if property.expectation == Expectation::Eventually && !property.holds(model, state) {
+                unsatisfied.insert(i);
+            }
+        }
+        let mut holding = id_set::IdSet::new();
+        for (i, (_name, predicate)) in predicates.iter().enumerate() {
+            if predicate(model, state) {
+                holding.insert(i);
+            }
+        }
+        let n = self.nodes.len();
+        self.nodes.push(Node {
+            fp,
+            parent,
+            is_expanded: false,
+            unsatisfied,
+            predicates: holding,
+        });
+        self.successors.push(Vec::new());
+        self.labels.push(Vec::new());
+        self.index.insert(fp, n);
+        (n, true)
+    }
+
+    /// The nodes along the breadth-first search tree from an init node to the specified node.
+    fn path_to(&self, n: usize) -> impl Iterator<Item = usize> {
+        let mut path = VecDeque::new();
+        let mut next = Some(n);
+        while let Some(n) = next {
+            path.push_front(n);
+            next = self.nodes[n].parent;
+        }
+        path.into_iter()
+    }
+
+    /// Whether a node was expanded without generating any successors.
+    fn is_terminal(&self, n: usize) -> bool {
+        self.nodes[n].is_expanded && self.successors[n].is_empty()
+    }
+
+    /// Finds a shortest path from an init node through included nodes that either ends at a
+    /// terminal node or reaches a fair cycle of included nodes. Returns the path along with the
+    /// index at which the loop begins (if any), in which case the path ends by returning to that
+    /// node.
+    fn find_lasso(&self, include: &dyn Fn(usize) -> bool) -> Option<(Vec<usize>, Option<usize>)> {
+        let fair_graph = FairGraph {
+            successors: &self.successors,
+            labels: &self.labels,
+            enabled: &|n| &self.labels[n],
+        };
+        let components = fair_graph.fair_components(include);
+        let mut component_ids = vec![None; self.nodes.len()];
+        for (id, component) in components.iter().enumerate() {
+            for &n in component {
+                component_ids[n] = Some(id);
+            }
+        }
+
+        let inits = self.inits.iter().copied().filter(|&n| include(n));
+        let mut path = shortest_path(&self.successors, inits, include, &|n| {
+            self.is_terminal(n) || component_ids[n].is_some()
+        })?;
+        let n = *path.last().unwrap();
+        if self.is_terminal(n) {
+            return Some((path, None));
+        }
+        let loop_start = path.len() - 1;
+        let component = &components[component_ids[n].unwrap()];
+        path.extend(fair_graph.fair_cycle(component, n, &[]));
+        Some((path, Some(loop_start)))
+    }
+
+    /// Finds a behavior accepted by an automaton for the violations of an [`Ltl`](crate::Ltl)
+    /// formula by searching the product of the state graph and automaton for a fair accepting
+    /// cycle.
+    /// Terminal states are treated as repeating forever. Returns the path of the behavior along
+    /// with the index at which its loop begins, which is absent if the behavior ends at a terminal
+    /// state.
+    fn find_violation(&self, automaton: &Automaton) -> Option<(Vec<usize>, Option<usize>)> {
+        // Build the reachable portion of the product, with each product node a pair of a graph
+        // node and an automaton state.
+        let matches = |n: usize, q: usize| {
+            self.nodes[n].is_expanded
+                && automaton.states[q].matches(|p| self.nodes[n].predicates.contains(p))
+        };
+        #[derive(Default)]
+        struct Product {
+            pairs: Vec<(usize, usize)>,
+            index: HashMap<(usize, usize), usize>,
+            successors: Vec<Vec<usize>>,
+            labels: Vec<Vec<Label>>,
+            pending: VecDeque<usize>,
+        }
+        impl Product {
+            fn intern(&mut self, pair: (usize, usize)) -> usize {
+                if let Some(&p) = self.index.get(&pair) {
+                    return p;
+                }
+                let p = self.pairs.len();
+                self.pairs.push(pair);
+                self.successors.push(Vec::new());
+                self.labels.push(Vec::new());
+                self.index.insert(pair, p);
+                self.pending.push_back(p);
+                p
+            }
+        }
+        let mut product = Product::default();
+        let mut inits = Vec::new();
+        for &n in &self.inits {
+            for (q, state) in automaton.states.iter().enumerate() {
+                if state.is_init && matches(n, q) {
+                    inits.push(product.intern((n, q)));
+                }
+            }
+        }
+        while let Some(p) = product.pending.pop_front() {
+            let (n, q) = product.pairs[p];
+            let edges: Vec<(usize, Label)> = if self.successors[n].is_empty() {
+                vec![(n, None)] // stutter
+            } else {
+                let labels = self.labels[n].iter().copied();
+                self.successors[n].iter().copied().zip(labels).collect()
+            };
+            for (next_n, label) in edges {
+                for &next_q in &automaton.states[q].successors {
+                    if matches(next_n, next_q) {
+                        let next_p = product.intern((next_n, next_q));
+                        product.successors[p].push(next_p);
+                        product.labels[p].push(label);
+                    }
+                }
+            }
+        }
+
+        // Then find a reachable fair cycle that visits every acceptance set.
+        let Product {
+            pairs,
+            successors,
+            labels,
+            ..
+        } = product;
+        let is_accepting =
+            |p: usize, set: usize| automaton.states[pairs[p].1].acceptance.contains(&set);
+        let fair_graph = FairGraph {
+            successors: &successors,
+            labels: &labels,
+            enabled: &|p| &self.labels[pairs[p].0],
+        };
+        let components: Vec<_> = fair_graph
+            .fair_components(&|_| true)
+            .into_iter()
+            .filter(|component| {
+                (0..automaton.acceptance_set_count)
+                    .all(|set| component.iter().any(|&p| is_accepting(p, set)))
+            })
+            .collect();
+        let mut component_ids = vec![None; pairs.len()];
+        for (id, component) in components.iter().enumerate() {
+            for &p in component {
+                component_ids[p] = Some(id);
+            }
+        }
+        let mut path = shortest_path(&successors, inits, &|_| true, &|p| {
+            component_ids[p].is_some()
+        })?;
+        let start = *path.last().unwrap();
+        let loop_start = path.len() - 1;
+        let accepting: Vec<_> = (0..automaton.acceptance_set_count)
+            .map(|set| move |p| is_accepting(p, set))
+            .collect();
+        let targets: Vec<&dyn Fn(usize) -> bool> = accepting
+            .iter()
+            .map(|is_target| is_target as &dyn Fn(usize) -> bool)
+            .collect();
+        let component = &components[component_ids[start].unwrap()];
+        path.extend(fair_graph.fair_cycle(component, start, &targets));
+
+        // Finally project onto the state graph, truncating any stuttering at a terminal state.
+        let mut path: Vec<_> = path.into_iter().map(|p| pairs[p].0).collect();
+        if let Some(terminal) = path.iter().position(|&n| self.successors[n].is_empty()) {
+            path.truncate(terminal + 1);
+            return Some((path, None));
+        }
+        Some((path, Some(loop_start)))
+    }
+}
+
+/// A graph whose edges are labeled with the fairness constraints of the corresponding actions.
+struct FairGraph<'a> {
+    successors: &'a [Vec<usize>],
+    labels: &'a [Vec<Label>],
+    /// The labels of the actions enabled at a node, which for a product graph are those of the
+    /// corresponding state.
+    enabled: &'a dyn Fn(usize) -> &'a [Label],
+}
+
+impl FairGraph<'_> {
+    /// The fairness constraints of the actions enabled at a node.
+    fn enabled(&self, n: usize) -> impl Iterator<Item = (Fairness, u64)> + '_ {
+        (self.enabled)(n).iter().flatten().copied()
+    }
+
+    /// Computes the strongly connected components of the subgraph induced by the included nodes
+    /// that contain fair cycles. A component starving a weakly fair action, which is enabled at
+    /// every node of the component but never taken within it, is discarded. A component starving
+    /// a strongly fair action, which is enabled at some node but never taken, is refined by
+    /// removing the nodes at which the action is enabled.
+    fn fair_components(&self, include: &dyn Fn(usize) -> bool) -> Vec<Vec<usize>> {
+        let mut pending = cyclic_components(self.successors, include);
+        let mut fair = Vec::new();
+        while let Some(component) = pending.pop() {
+            let members: HashSet<usize> = component.iter().copied().collect();
+            let mut taken = HashSet::new();
+            let mut enabled_counts = HashMap::new();
+            for &n in &component {
+                for (next_n, label) in self.successors[n].iter().zip(&self.labels[n]) {
+                    if let (true, Some(label)) = (members.contains(next_n), label) {
+                        taken.insert(*label);
+                    }
+                }
+                for label in self.enabled(n).collect::<HashSet<_>>() {
+                    *enabled_counts.entry(label).or_insert(0) += 1;
+                }
+            }
+            let mut starved = enabled_counts
+                .iter()
+                .filter(|(label, _)| !taken.contains(*label));
+            if starved.any(|(label, &count)| label.0 == Fairness::Weak && count == members.len()) {
+                continue;
+            }
+            let starved: HashSet<_> = enabled_counts
+                .into_keys()
+                .filter(|label| label.0 == Fairness::Strong && !taken.contains(label))
+                .collect();
+            if starved.is_empty() {
+                fair.push(component);
+                continue;
+            }
+            pending.extend(cyclic_components(self.successors, &|n| {
+                members.contains(&n) && !self.enabled(n).any(|label| starved.contains(&label))
+            }));
+        }
+        fair
+    }
+
+    /// Finds a cycle through a fair component from the specified node back to itself, excluding
+    /// the starting node from the front of the returned path. The cycle visits a node satisfying
+    /// each of the targets and takes every action that the component requires it to take to be
+    /// fair.
+    fn fair_cycle(
+        &self,
+        component: &[usize],
+        start: usize,
+        targets: &[&dyn Fn(usize) -> bool],
+    ) -> Vec<usize> {
+        let members: HashSet<usize> = component.iter().copied().collect();
+        let include = |n: usize| members.contains(&n);
+        let extend = |cycle: &mut Vec<usize>, is_target: &dyn Fn(usize) -> bool| {
+            let current = *cycle.last().unwrap();
+            let sources = self.successors[current]
+                .iter()
+                .copied()
+                .filter(|&n| include(n));
+            cycle.extend(
+                shortest_path(self.successors, sources, &include, is_target)
+                    .expect("fair component must contain a cycle"),
+            );
+        };
+        let is_labeled = |n: usize, next_n: usize, label: (Fairness, u64)| {
+            let mut edges = self.successors[n].iter().zip(&self.labels[n]);
+            edges.any(|(&m, &l)| m == next_n && l == Some(label))
+        };
+
+        let mut cycle = vec![start];
+        for is_target in targets {
+            if !cycle.iter().any(|&n| is_target(n)) {
+                extend(&mut cycle, is_target);
+            }
+        }
+        let enabled: BTreeSet<_> = component.iter().flat_map(|&n| self.enabled(n)).collect();
+        for label in enabled {
+            if cycle.windows(2).any(|w| is_labeled(w[0], w[1], label)) {
+                continue;
+            }
+            let edge = component.iter().find_map(|&n| {
+                let mut edges = self.successors[n].iter().zip(&self.labels[n]);
+                edges
+                    .find(|&(&m, &l)| include(m) && l == Some(label))
+                    .map(|(&m, _)| (n, m))
+            });
+            match edge {
+                Some((n, next_n)) => {
+                    if *cycle.last().unwrap() != n {
+                        extend(&mut cycle, &|m| m == n);
+                    }
+                    cycle.push(next_n);
+                }
+                None => {
+                    // A weakly fair action that is not continuously enabled.
+                    let is_disabled = |n: usize| !self.enabled(n).any(|l| l == label);
+                    if !cycle.iter().any(|&n| is_disabled(n)) {
+                        extend(&mut cycle, &is_disabled);
+                    }
+                }
+            }
+        }
+        if cycle.len() == 1 || *cycle.last().unwrap() != start {
+            extend(&mut cycle, &|n| n == start);
+        }
+        cycle.remove(0);
+        cycle
+    }
+}
+
+/// Computes the nontrivial strongly connected components of the subgraph induced by the included
+/// nodes using Tarjan's algorithm. A component is nontrivial if it contains a cycle, meaning it
+/// has more than one node or a node with an edge to itself.
+fn cyclic_components(
+    successors: &[Vec<usize>],
+    include: &dyn Fn(usize) -> bool,
+) -> Vec<Vec<usize>> {
+    const UNVISITED: usize = usize::MAX;
+    let mut index = vec![UNVISITED; successors.len()];
+    let mut lowlink = vec![0; successors.len()];
+    let mut on_stack = vec![false; successors.len()];
+    let mut stack = Vec::new();
+    let mut next_index = 0;
+    let mut components = Vec::new();
+    for root in 0..successors.len() {
+        if index[root] != UNVISITED || !include(root) {
+            continue;
+        }
+        // Each frame tracks a node and the position of the next successor to visit.
+        let mut frames = vec![(root, 0)];
+        index[root] = next_index;
+        lowlink[root] = next_index;
+        next_index += 1;
+        stack.push(root);
+        on_stack[root] = true;
+        while let Some(&(v, i)) = frames.last() {
+            if let Some(&w) = successors[v].get(i) {
+                frames.last_mut().unwrap().1 += 1;
+                if !include(w) {
+                    continue;
+                }
+                if index[w] == UNVISITED {
+                    index[w] = next_index;
+                    lowlink[w] = next_index;
+                    next_index += 1;
+                    stack.push(w);
+                    on_stack[w] = true;
+                    frames.push((w, 0));
+                } else if on_stack[w] {
+                    lowlink[v] = lowlink[v].min(index[w]);
+                }
+                continue;
+            }
+            frames.pop();
+            if let Some(&(u, _)) = frames.last() {
+                lowlink[u] = lowlink[u].min(lowlink[v]);
+            }
+            if lowlink[v] == index[v] {
+                let mut component = Vec::new();
+                loop {
+                    let w = stack.pop().unwrap();
+                    on_stack[w] = false;
+                    component.push(w);
+                    if w == v {
+                        break;
+                    }
+                }
+                if component.len() > 1 || successors[v].contains(&v) {
+                    components.push(component);
+                }
+            }
+        }
+    }
+    components
+}
+
+/// Finds a shortest path through included nodes from one of the sources to a target node,
+/// returning the nodes of the path including both endpoints.
+fn shortest_path(
+    successors: &[Vec<usize>],
+    sources: impl IntoIterator<Item = usize>,
+    include: &dyn Fn(usize) -> bool,
+    is_target: &dyn Fn(usize) -> bool,
+) -> Option<Vec<usize>> {
+    let mut parents = HashMap::new();
+    let mut pending = VecDeque::new();
+    for n in sources {
+        if let std::collections::hash_map::Entry::Vacant(entry) = parents.entry(n) {
+            entry.insert(None);
+            pending.push_back(n);
+        }
+    }
+    while let Some(n) = pending.pop_front() {
+        if is_target(n) {
+            let mut path = vec![n];
+            let mut next = parents[&n];
+            while let Some(n) = next {
+                path.push(n);
+                next = parents[&n];
+            }
+            path.reverse();
+            return Some(path);
+        }
+        for &next_n in &successors[n] {
+            if include(next_n) && !parents.contains_key(&next_n) {
+                parents.insert(next_n, Some(n));
+                pending.push_back(next_n);
+            }
+        }
+    }
+    None
+}
+
+impl<M> Checker<M> for LivenessChecker<M>
+where
+    M: Model,
+    M::State: Hash,
+{
+    fn model(&self) -> &M {
+        &self.model
+    }
+
+    fn state_count(&self) -> usize {
+        self.state_count.load(Ordering::Relaxed)
+    }
+
+    fn unique_state_count(&self) -> usize {
+        self.unique_state_count.load(Ordering::Relaxed)
+    }
+
+    fn max_depth(&self) -> usize {
+        self.max_depth.load(Ordering::Relaxed)
+    }
+
+    fn control(&self) -> Option<CheckerControl> {
+        Some(self.control.clone())
+    }
+
+    fn limit_reached(&self) -> Option<Limit> {
+        self.limits.reached()
+    }
+
+    fn properties(&self) -> Vec<Property<M>> {
+        checked_properties(&*self.model, self.deadlock_detection)
+    }
+
+    fn discoveries(&self) -> HashMap<String, Path<M::State, M::Action>> {
+        self.discoveries_all()
+            .into_iter()
+            .map(|(name, mut paths)| (name, paths.swap_remove(0)))
+            .collect()
+    }
+
+    fn discoveries_all(&self) -> HashMap<String, Vec<Path<M::State, M::Action>>> {
+        let properties = self.properties();
+        self.discoveries
+            .all()
+            .into_iter()
+            .map(|(name, discoveries)| {
+                let paths = discoveries
+                    .into_iter()
+                    .map(|(fingerprints, loop_start)| {
+                        let path =
+                            Path::from_fingerprints(self.model(), VecDeque::from(fingerprints));
+                        let path = match loop_start {
+                            Some(loop_start) => path.with_loop_start(loop_start),
+                            None => path,
+                        };
+                        complete_discovery(self.model(), &properties, &name, path)
+                    })
+                    .collect();
+                (name, paths)
+            })
+            .collect()
+    }
+
+    fn handles(&mut self) -> Vec<JoinHandle<()>> {
+        std::mem::take(&mut self.handles)
+    }
+
+    fn is_done(&self) -> bool {
+        self.is_done.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::binary_clock::*;
+    use crate::test_util::counter::Counter;
+    use crate::test_util::dgraph::DGraph;
+    use crate::test_util::linear_equation_solver::*;
+    use crate::Ltl;
+
+    fn eventually_odd() -> Property<DGraph> {
+        Property::eventually("odd", |_, s| s % 2 == 1)
+    }
+
+    #[test]
+    fn can_discover_lasso_counterexample() {
+        let checker = DGraph::with_property(eventually_odd())
+            .with_path(vec![0, 2, 4, 2]) // cycle
+            .checker()
+            .spawn_liveness()
+            .join();
+        let path = checker.discovery("odd").unwrap();
+        assert_eq!(path.loop_start(), Some(1));
+        assert_eq!(path.into_states(), vec![0, 2, 4, 2]);
+        checker.assert_discovery("odd", vec![2, 4, 2]);
+    }
+
+    #[test]
+    fn can_discover_self_loop_counterexample() {
+        let path = DGraph::with_property(eventually_odd())
+            .with_path(vec![0, 1])
+            .with_path(vec![0, 2, 2])
+            .checker()
+            .spawn_liveness()
+            .join()
+            .discovery("odd")
+            .unwrap();
+        assert_eq!(path.loop_start(), Some(1));
+        assert_eq!(path.into_states(), vec![0, 2, 2]);
+    }
+
+    #[test]
+    fn can_discover_terminal_counterexample() {
+        let path = DGraph::with_property(eventually_odd())
+            .with_path(vec![0, 2, 4])
+            .with_path(vec![1, 4, 6]) // revisiting 4
+            .checker()
+            .spawn_liveness()
+            .join()
+            .discovery("odd")
+            .unwrap();
+        assert_eq!(path.loop_start(), None);
+        assert_eq!(path.into_states(), vec![0, 2, 4, 6]);
+    }
+
+    #[test]
+    fn can_validate_cycles_that_satisfy_eventually() {
+        DGraph::with_property(eventually_odd())
+            .with_path(vec![0, 2, 3, 4, 2]) // cycle passes through 3
+            .with_path(vec![1, 1]) // satisfied self loop
+            .checker()
+            .spawn_liveness()
+            .join()
+            .assert_properties();
+    }
+
+    #[test]
+    fn can_collect_multiple_discoveries_per_property() {
+        let checker = LinearEquation { a: 2, b: 10, c: 14 }
+            .checker()
+            .max_discoveries_per_property(3)
+            .spawn_liveness()
+            .join();
+        let paths = checker.discoveries_all().remove("solvable").unwrap();
+        assert_eq!(paths.len(), 3);
+        assert_eq!(
+            paths[0].clone().into_actions(),
+            vec![Guess::IncreaseX, Guess::IncreaseX, Guess::IncreaseY]
+        );
+
+        let checker = DGraph::with_property(eventually_odd())
+            .with_path(vec![0, 2, 4, 2])
+            .with_path(vec![0, 6])
+            .checker()
+            .max_discoveries_per_property(3)
+            .spawn_liveness()
+            .join();
+        assert_eq!(checker.discoveries_all()["odd"].len(), 1);
+    }
+
+    #[test]
+    fn can_check_always_and_sometimes_properties() {
+        let checker = LinearEquation { a: 2, b: 10, c: 14 }
+            .checker()
+            .spawn_liveness()
+            .join();
+        checker.assert_properties();
+        assert_eq!(
+            checker.discovery("solvable").unwrap().into_actions(),
+            vec![Guess::IncreaseX, Guess::IncreaseX, Guess::IncreaseY]
+        );
+
+        let checker = BinaryClock.checker().spawn_liveness().join();
+        checker.assert_properties();
+        assert_eq!(checker.unique_state_count(), 2);
+    }
+
+    fn globally_request_leads_to_response() -> Property<DGraph> {
+        let request = Ltl::predicate("request", |_, s| *s == 1);
+        let response = Ltl::predicate("response", |_, s| *s == 2);
+        Property::ltl(
+            "leads to",
+            Ltl::globally(request.implies(Ltl::finally(response))),
+        )
+    }
+
+    #[test]
+    fn can_discover_ltl_lasso_counterexample() {
+        let checker = DGraph::with_property(globally_request_leads_to_response())
+            .with_path(vec![0, 1, 2, 0]) // answered
+            .with_path(vec![0, 1, 3, 4, 3]) // never answered
+            .checker()
+            .spawn_liveness()
+            .join();
+        let path = checker.discovery("leads to").unwrap();
+        assert_eq!(path.loop_start(), Some(2));
+        assert_eq!(path.into_states(), vec![0, 1, 3, 4, 3]);
+        checker.assert_discovery("leads to", vec![1, 3, 4, 3]);
+    }
+
+    #[test]
+    fn can_discover_ltl_terminal_counterexample() {
+        let small = Ltl::predicate("small", |_, s| *s < 10);
+        let big = Ltl::predicate("big", |_, s| *s >= 10);
+        let checker = DGraph::with_property(Property::ltl("small until big", small.until(big)))
+            .with_path(vec![0, 1, 10])
+            .with_path(vec![0, 2, 3])
+            .checker()
+            .spawn_liveness()
+            .join();
+        let path = checker.discovery("small until big").unwrap();
+        assert_eq!(path.loop_start(), None);
+        assert_eq!(path.into_states(), vec![0, 2, 3]);
+        checker.assert_discovery("small until big", vec![2, 3]);
+    }
+
+    #[test]
+    fn distinguishes_ltl_predicates_that_share_a_name() {
+        let checker = Counter::new(3)
+            .with_property(Property::ltl(
+                "below 4",
+                Ltl::globally(Ltl::predicate("bounded", |_, s| *s < 4)),
+            ))
+            .with_property(Property::ltl(
+                "below 2",
+                Ltl::globally(Ltl::predicate("bounded", |_, s| *s < 2)),
+            ))
+            .checker()
+            .spawn_liveness()
+            .join();
+        checker.assert_no_discovery("below 4");
+        checker.assert_discovery("below 2", vec!["increment"; 3]);
+    }
+
+    #[test]
+    fn can_validate_ltl() {
+        DGraph::with_property(globally_request_leads_to_response())
+            .with_path(vec![0, 1, 2, 0])
+            .with_path(vec![0, 1, 3, 2, 4, 4]) // answered before stuttering
+            .checker()
+            .spawn_liveness()
+            .join()
+            .assert_properties();
+    }
+
+    #[test]
+    fn weak_fairness_discards_cycles_that_starve_continuously_enabled_actions() {
+        let graph = DGraph::with_property(eventually_odd())
+            .with_path(vec![0, 0])
+            .with_path(vec![0, 1]);
+        let path = graph
+            .clone()
+            .checker()
+            .spawn_liveness()
+            .join()
+            .discovery("odd");
+        assert_eq!(path.unwrap().into_states(), vec![0, 0]);
+
+        graph
+            .with_fairness(1, Fairness::Weak)
+            .checker()
+            .spawn_liveness()
+            .join()
+            .assert_properties();
+    }
+
+    #[test]
+    fn strong_fairness_discards_cycles_that_starve_intermittently_enabled_actions() {
+        let graph = DGraph::with_property(eventually_odd())
+            .with_path(vec![0, 2, 0])
+            .with_path(vec![0, 1]);
+        let path = graph
+            .clone()
+            .with_fairness(1, Fairness::Weak)
+            .checker()
+            .spawn_liveness()
+            .join()
+            .discovery("odd");
+        assert_eq!(path.unwrap().into_states(), vec![0, 2, 0]);
+
+        graph
+            .with_fairness(1, Fairness::Strong)
+            .checker()
+            .spawn_liveness()
+            .join()
+            .assert_properties();
+    }
+
+    #[test]
+    fn strong_fairness_refines_components() {
+        let checker = DGraph::with_property(eventually_odd())
+            .with_path(vec![0, 2, 0])
+            .with_path(vec![0, 1])
+            .with_path(vec![2, 4, 2]) // avoids the state enabling the fair action
+            .with_fairness(1, Fairness::Strong)
+            .checker()
+            .spawn_liveness()
+            .join();
+        let path = checker.discovery("odd").unwrap();
+        assert_eq!(path.loop_start(), Some(0));
+        assert_eq!(path.into_states(), vec![2, 4, 2]);
+    }
+
+    #[test]
+    fn fair_lasso_takes_fair_actions() {
+        // A fair cycle must visit 4, as the action leading there is otherwise continuously enabled.
+        let checker = DGraph::with_property(eventually_odd())
+            .with_path(vec![0, 2, 0])
+            .with_path(vec![0, 4, 2])
+            .with_path(vec![2, 4])
+            .with_fairness(4, Fairness::Weak)
+            .checker()
+            .spawn_liveness()
+            .join();
+        let path = checker.discovery("odd").unwrap();
+        assert_eq!(path.loop_start(), Some(0));
+        assert_eq!(path.into_states(), vec![0, 2, 4, 2, 0]);
+    }
+
+    #[test]
+    fn fairness_applies_to_ltl() {
+        let graph = DGraph::with_property(globally_request_leads_to_response())
+            .with_path(vec![0, 1, 3, 3])
+            .with_path(vec![3, 2]);
+        let path = graph
+            .clone()
+            .checker()
+            .spawn_liveness()
+            .join()
+            .discovery("leads to");
+        assert_eq!(path.unwrap().into_states(), vec![0, 1, 3, 3]);
+
+        graph
+            .with_fairness(2, Fairness::Weak)
+            .checker()
+            .spawn_liveness()
+            .join()
+            .assert_properties();
+    }
+
+    #[test]
+    fn displays_loop_separately() {
+        let path = DGraph::with_property(eventually_odd())
+            .with_path(vec![0, 2, 4, 2])
+            .checker()
+            .spawn_liveness()
+            .join()
+            .discovery("odd")
+            .unwrap();
+        assert_eq!(format!("{}", path), "Path[1]:\n- 2\nLoop[2]:\n- 4\n- 2\n");
+    }
+}
@@ -0,0 +1,678 @@
+//! Private module for selective re-export.
+
+use crate::Model;
+use std::collections::BTreeSet;
+use std::fmt::{Debug, Display, Formatter};
+use std::sync::Arc;
+
+/// A [linear temporal logic](https://en.wikipedia.org/wiki/Linear_temporal_logic) formula over
+/// named state predicates, for use with [`Property::ltl`].
+///
+/// Formulas are judged against every behavior of a model, where a behavior that reaches a terminal
+/// state is viewed as remaining in that state forever. Predicates are named for display, and a
+/// predicate that a formula reuses (by cloning it) is only evaluated once per state.
+///
+/// # Example
+///
+/// ```
+/// # use stateright::*;
+/// # struct M;
+/// # impl Model for M {
+/// #     type State = (bool, bool);
+/// #     type Action = ();
+/// #     fn init_states(&self) -> Vec<Self::State> { vec![(false, false)] }
+/// #     fn actions(&self, _: &Self::State, _: &mut Vec<Self::Action>) {}
+/// #     fn next_state(&self, _: &Self::State, _: Self::Action) -> Option<Self::State> { None }
+/// # }
+/// let request = Ltl::<M>::predicate("request", |_, s| s.0);
+/// let response = Ltl::<M>::predicate("response", |_, s| s.1);
+/// let leads_to = Ltl::globally(request.implies(Ltl::finally(response)));
+/// assert_eq!(format!("{}", leads_to), "G (request -> F response)");
+/// let property = Property::<M>::ltl("requests are answered", leads_to);
+/// ```
+///
+/// [`Property::ltl`]: crate::Property::ltl
+pub enum Ltl<M: Model> {
+    /// Holds if the named predicate holds for the current state.
+    #[allow(clippy::type_complexity)]
+    Predicate(String, Arc<dyn Fn(&M, &M::State) -> bool + Send + Sync>),
+    /// Always holds.
+    True,
+    /// Never holds.
+    False,
+    /// Holds if the inner formula does not.
+    Not(Box<Ltl<M>>),
+    /// Holds if both formulas hold.
+    And(Box<Ltl<M>>, Box<Ltl<M>>),
+    /// Holds if either formula holds.
+    Or(Box<Ltl<M>>, Box<Ltl<M>>),
+    /// Holds if the second formula holds whenever the first does.
+    Implies(Box<Ltl<M>>, Box<Ltl<M>>),
+    /// Holds if the formula holds for the next state.
+    Next(Box<Ltl<M>>),
+    /// Holds if the formula holds for the current state and every subsequent state.
+    Globally(Box<Ltl<M>>),
+    /// Holds if the formula holds for the current state or some subsequent state.
+    Finally(Box<Ltl<M>>),
+    /// Holds if the first formula holds at least until the second does, which must happen.
+    Until(Box<Ltl<M>>, Box<Ltl<M>>),
+    /// Holds if the second formula holds up to and including the point at which the first does,
+    /// which need not happen.
+    Release(Box<Ltl<M>>, Box<Ltl<M>>),
+}
+
+impl<M: Model> Ltl<M> {
+    /// A named state predicate.
+    pub fn predicate(
+        name: impl Into<String>,
+        condition: impl Fn(&M, &M::State) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        Ltl::Predicate(name.into(), Arc::new(condition))
+    }
+
+    /// `X self`: holds if this formula holds for the next state.
+    pub fn next(formula: Self) -> Self {
+        Ltl::Next(Box::new(formula))
+    }
+
+    /// `G self`: holds if this formula holds for the current state and every subsequent state.
+    pub fn globally(formula: Self) -> Self {
+        Ltl::Globally(Box::new(formula))
+    }
+
+    /// `F self`: holds if this formula holds for the current state or some subsequent state.
+    pub fn finally(formula: Self) -> Self {
+        Ltl::Finally(Box::new(formula))
+    }
+
+    /// `self && other`
+    pub fn and(self, other: Self) -> Self {
+        Ltl::And(Box::new(self), Box::new(other))
+    }
+
+    /// `self || other`
+    pub fn or(self, other: Self) -> Self {
+        Ltl::Or(Box::new(self), Box::new(other))
+    }
+
+    /// `self -> other`
+    pub fn implies(self, other: Self) -> Self {
+        Ltl::Implies(Box::new(self), Box::new(other))
+    }
+
+    /// `self U other`
+    pub fn until(self, other: Self) -> Self {
+        Ltl::Until(Box::new(self), Box::new(other))
+    }
+
+    /// `self R other`
+    pub fn release(self, other: Self) -> Self {
+        Ltl::Release(Box::new(self), Box::new(other))
+    }
+
+    /// Converts to a formula over model `W`, such as a model built from this one, by converting
+    /// the condition of each predicate. A condition that the formula reuses is converted once so
+    /// that it remains shared.
+    pub(crate) fn map_conditions<W: Model>(
+        &self,
+        convert: &impl Fn(Arc<Condition<M>>) -> Arc<Condition<W>>,
+    ) -> Ltl<W> {
+        self.map_conditions_cached(convert, &mut Vec::new())
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn map_conditions_cached<W: Model>(
+        &self,
+        convert: &impl Fn(Arc<Condition<M>>) -> Arc<Condition<W>>,
+        converted: &mut Vec<(Arc<Condition<M>>, Arc<Condition<W>>)>,
+    ) -> Ltl<W> {
+        let mut map = |f: &Self| Box::new(f.map_conditions_cached(convert, converted));
+        match self {
+            Ltl::Predicate(name, condition) => {
+                let condition = match converted.iter().find(|(c, _)| Arc::ptr_eq(c, condition)) {
+                    Some((_, c)) => Arc::clone(c),
+                    None => {
+                        let c = convert(Arc::clone(condition));
+                        converted.push((Arc::clone(condition), Arc::clone(&c)));
+                        c
+                    }
+                };
+                Ltl::Predicate(name.clone(), condition)
+            }
+            Ltl::True => Ltl::True,
+            Ltl::False => Ltl::False,
+            Ltl::Not(f) => Ltl::Not(map(f)),
+            Ltl::And(l, r) => Ltl::And(map(l), map(r)),
+            Ltl::Or(l, r) => Ltl::Or(map(l), map(r)),
+            Ltl::Implies(l, r) => Ltl::Implies(map(l), map(r)),
+            Ltl::Next(f) => Ltl::Next(map(f)),
+            Ltl::Globally(f) => Ltl::Globally(map(f)),
+            Ltl::Finally(f) => Ltl::Finally(map(f)),
+            Ltl::Until(l, r) => Ltl::Until(map(l), map(r)),
+            Ltl::Release(l, r) => Ltl::Release(map(l), map(r)),
+        }
+    }
+
+    /// Converts to negation normal form, interning predicates by both name and condition so that
+    /// distinct predicates that happen to share a name are evaluated separately.
+    fn to_nnf(&self, is_negated: bool, predicates: &mut Vec<LtlPredicate<M>>) -> Nnf {
+        let binary = |l: &Self, r: &Self, predicates: &mut Vec<_>| {
+            (
+                Box::new(l.to_nnf(is_negated, predicates)),
+                Box::new(r.to_nnf(is_negated, predicates)),
+            )
+        };
+        match self {
+            Ltl::Predicate(name, condition) => {
+                let i = match predicates
+                    .iter()
+                    .position(|(n, c)| n == name && Arc::ptr_eq(c, condition))
+                {
+                    Some(i) => i,
+                    None => {
+                        predicates.push((name.clone(), Arc::clone(condition)));
+                        predicates.len() - 1
+                    }
+                };
+                if is_negated {
+                    Nnf::NotPredicate(i)
+                } else {
+                    Nnf::Predicate(i)
+                }
+            }
+            Ltl::True if is_negated => Nnf::False,
+            Ltl::True => Nnf::True,
+            Ltl::False if is_negated => Nnf::True,
+            Ltl::False => Nnf::False,
+            Ltl::Not(f) => f.to_nnf(!is_negated, predicates),
+            Ltl::And(l, r) => {
+                let (l, r) = binary(l, r, predicates);
+                if is_negated {
+                    Nnf::Or(l, r)
+                } else {
+                    Nnf::And(l, r)
+                }
+            }
+            Ltl::Or(l, r) => {
+                let (l, r) = binary(l, r, predicates);
+                if is_negated {
+                    Nnf::And(l, r)
+                } else {
+                    Nnf::Or(l, r)
+                }
+            }
+            Ltl::Implies(l, r) => {
+                // l -> r == !l || r
+                let l = Box::new(l.to_nnf(!is_negated, predicates));
+                let r = Box::new(r.to_nnf(is_negated, predicates));
+                if is_negated {
+                    Nnf::And(l, r)
+                } else {
+                    Nnf::Or(l, r)
+                }
+            }
+            Ltl::Next(f) => Nnf::Next(Box::new(f.to_nnf(is_negated, predicates))),
+            Ltl::Globally(f) => {
+                // G f == false R f
+                let f = Box::new(f.to_nnf(is_negated, predicates));
+                if is_negated {
+                    Nnf::Until(Box::new(Nnf::True), f)
+                } else {
+                    Nnf::Release(Box::new(Nnf::False), f)
+                }
+            }
+            Ltl::Finally(f) => {
+                // F f == true U f
+                let f = Box::new(f.to_nnf(is_negated, predicates));
+                if is_negated {
+                    Nnf::Release(Box::new(Nnf::False), f)
+                } else {
+                    Nnf::Until(Box::new(Nnf::True), f)
+                }
+            }
+            Ltl::Until(l, r) => {
+                let (l, r) = binary(l, r, predicates);
+                if is_negated {
+                    Nnf::Release(l, r)
+                } else {
+                    Nnf::Until(l, r)
+                }
+            }
+            Ltl::Release(l, r) => {
+                let (l, r) = binary(l, r, predicates);
+                if is_negated {
+                    Nnf::Until(l, r)
+                } else {
+                    Nnf::Release(l, r)
+                }
+            }
+        }
+    }
+}
+
+impl<M: Model> Clone for Ltl<M> {
+    fn clone(&self) -> Self {
+        match self {
+            Ltl::Predicate(name, condition) => Ltl::Predicate(name.clone(), Arc::clone(condition)),
+            Ltl::True => Ltl::True,
+            Ltl::False => Ltl::False,
+            Ltl::Not(f) => Ltl::Not(f.clone()),
+            Ltl::And(l, r) => Ltl::And(l.clone(), r.clone()),
+            Ltl::Or(l, r) => Ltl::Or(l.clone(), r.clone()),
+            Ltl::Implies(l, r) => Ltl::Implies(l.clone(), r.clone()),
+            Ltl::Next(f) => Ltl::Next(f.clone()),
+            Ltl::Globally(f) => Ltl::Globally(f.clone()),
+            Ltl::Finally(f) => Ltl::Finally(f.clone()),
+            Ltl::Until(l, r) => Ltl::Until(l.clone(), r.clone()),
+            Ltl::Release(l, r) => Ltl::Release(l.clone(), r.clone()),
+        }
+    }
+}
+
+impl<M: Model> std::ops::Not for Ltl<M> {
+    type Output = Self;
+    fn not(self) -> Self {
+        Ltl::Not(Box::new(self))
+    }
+}
+
+impl<M: Model> Display for Ltl<M> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        // Parenthesizes binary operands so that the output is unambiguous.
+        struct Operand<'a, M: Model>(&'a Ltl<M>);
+        impl<M: Model> Display for Operand<'_, M> {
+            fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                match self.0 {
+                    Ltl::Predicate(..)
+                    | Ltl::True
+                    | Ltl::False
+                    | Ltl::Not(_)
+                    | Ltl::Next(_)
+                    | Ltl::Globally(_)
+                    | Ltl::Finally(_) => {
+                        write!(f, "{}", self.0)
+                    }
+                    _ => write!(f, "({})", self.0),
+                }
+            }
+        }
+        match self {
+            Ltl::Predicate(name, _) => write!(f, "{}", name),
+            Ltl::True => write!(f, "true"),
+            Ltl::False => write!(f, "false"),
+            Ltl::Not(x) => write!(f, "!{}", Operand(x)),
+            Ltl::And(l, r) => write!(f, "{} && {}", Operand(l), Operand(r)),
+            Ltl::Or(l, r) => write!(f, "{} || {}", Operand(l), Operand(r)),
+            Ltl::Implies(l, r) => write!(f, "{} -> {}", Operand(l), Operand(r)),
+            Ltl::Next(x) => write!(f, "X {}", Operand(x)),
+            Ltl::Globally(x) => write!(f, "G {}", Operand(x)),
+            Ltl::Finally(x) => write!(f, "F {}", Operand(x)),
+            Ltl::Until(l, r) => write!(f, "{} U {}", Operand(l), Operand(r)),
+            Ltl::Release(l, r) => write!(f, "{} R {}", Operand(l), Operand(r)),
+        }
+    }
+}
+
+impl<M: Model> Debug for Ltl<M> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Ltl({})", self)
+    }
+}
+
+/// The condition of a predicate.
+pub(crate) type Condition<M> = dyn Fn(&M, &<M as Model>::State) -> bool + Send + Sync;
+
+/// A named predicate referenced by a formula.
+pub(crate) type LtlPredicate<M> = (String, Arc<Condition<M>>);
+
+/// A formula in negation normal form, with predicates referenced by index.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+enum Nnf {
+    True,
+    False,
+    Predicate(usize),
+    NotPredicate(usize),
+    And(Box<Nnf>, Box<Nnf>),
+    Or(Box<Nnf>, Box<Nnf>),
+    Next(Box<Nnf>),
+    Until(Box<Nnf>, Box<Nnf>),
+    Release(Box<Nnf>, Box<Nnf>),
+}
+
+impl Nnf {
+    fn untils(&self, output: &mut BTreeSet<Nnf>) {
+        match self {
+            Nnf::True | Nnf::False | Nnf::Predicate(_) | Nnf::NotPredicate(_) => {}
+            Nnf::Next(f) => f.untils(output),
+            Nnf::And(l, r) | Nnf::Or(l, r) | Nnf::Release(l, r) => {
+                l.untils(output);
+                r.untils(output);
+            }
+            Nnf::Until(l, r) => {
+                output.insert(self.clone());
+                l.untils(output);
+                r.untils(output);
+            }
+        }
+    }
+
+    /// Evaluates the formula for each position of an infinite word, where `successors[i]` is the
+    /// position following position `i` and `holds(p, i)` indicates whether predicate `p` holds at
+    /// position `i`.
+    fn evaluate(&self, successors: &[usize], holds: &dyn Fn(usize, usize) -> bool) -> Vec<bool> {
+        let len = successors.len();
+        match self {
+            Nnf::True => vec![true; len],
+            Nnf::False => vec![false; len],
+            Nnf::Predicate(p) => (0..len).map(|i| holds(*p, i)).collect(),
+            Nnf::NotPredicate(p) => (0..len).map(|i| !holds(*p, i)).collect(),
+            Nnf::And(l, r) | Nnf::Or(l, r) => {
+                let l = l.evaluate(successors, holds);
+                let r = r.evaluate(successors, holds);
+                let is_and = matches!(self, Nnf::And(..));
+                (0..len)
+                    .map(|i| if is_and { l[i] && r[i] } else { l[i] || r[i] })
+                    .collect()
+            }
+            Nnf::Next(f) => {
+                let f = f.evaluate(successors, holds);
+                successors.iter().map(|&j| f[j]).collect()
+            }
+            Nnf::Until(l, r) | Nnf::Release(l, r) => {
+                // Least fixpoint for until and greatest fixpoint for release. Each iteration
+                // propagates values back by at least one position, so `len` iterations suffice.
+                let is_until = matches!(self, Nnf::Until(..));
+                let l = l.evaluate(successors, holds);
+                let r = r.evaluate(successors, holds);
+                let mut output = vec![!is_until; len];
+                for _ in 0..=len {
+                    for i in (0..len).rev() {
+                        let later = output[successors[i]];
+                        output[i] = if is_until {
+                            r[i] || (l[i] && later)
+                        } else {
+                            r[i] && (l[i] || later)
+                        };
+                    }
+                }
+                output
+            }
+        }
+    }
+}
+
+/// A state of an [`Automaton`].
+pub(crate) struct AutomatonState {
+    pub(crate) is_init: bool,
+    pub(crate) successors: Vec<usize>,
+    /// Indexes of the acceptance sets that contain this state.
+    pub(crate) acceptance: Vec<usize>,
+    positive: Vec<usize>,
+    negative: Vec<usize>,
+}
+
+impl AutomatonState {
+    /// Indicates whether the automaton can enter this state upon reading a model state for which
+    /// `holds(p)` indicates whether predicate `p` holds.
+    pub(crate) fn matches(&self, holds: impl Fn(usize) -> bool) -> bool {
+        self.positive.iter().all(|&p| holds(p)) && !self.negative.iter().any(|&p| holds(p))
+    }
+}
+
+/// A generalized Büchi automaton accepting the infinite behaviors that violate a formula. An
+/// accepting run visits a state from each acceptance set infinitely often.
+pub(crate) struct Automaton {
+    pub(crate) states: Vec<AutomatonState>,
+    pub(crate) acceptance_set_count: usize,
+}
+
+impl Automaton {
+    /// Builds an automaton for the negation of a formula using the tableau construction from
+    /// "Simple On-the-fly Automatic Verification of Linear Temporal Logic" by Gerth, Peled, Vardi,
+    /// and Wolper. Predicates are interned into `predicates`, whose indexes the automaton
+    /// references, and which may be shared by the automata of several formulas.
+    pub(crate) fn for_violations_of<M: Model>(
+        formula: &Ltl<M>,
+        predicates: &mut Vec<LtlPredicate<M>>,
+    ) -> Self {
+        const INIT: usize = usize::MAX;
+        struct Node {
+            incoming: BTreeSet<usize>,
+            new: BTreeSet<Nnf>,
+            old: BTreeSet<Nnf>,
+            next: BTreeSet<Nnf>,
+        }
+
+        let negation = formula.to_nnf(true, predicates);
+        let mut untils = BTreeSet::new();
+        negation.untils(&mut untils);
+
+        let mut expanded: Vec<Node> = Vec::new();
+        let mut pending = vec![Node {
+            incoming: BTreeSet::from([INIT]),
+            new: BTreeSet::from([negation]),
+            old: BTreeSet::new(),
+            next: BTreeSet::new(),
+        }];
+        while let Some(mut node) = pending.pop() {
+            let eta = match node.new.pop_first() {
+                Some(eta) => eta,
+                None => {
+                    if let Some(existing) = expanded
+                        .iter_mut()
+                        .find(|n| n.old == node.old && n.next == node.next)
+                    {
+                        existing.incoming.extend(node.incoming);
+                    } else {
+                        let next = node.next.clone();
+                        expanded.push(node);
+                        pending.push(Node {
+                            incoming: BTreeSet::from([expanded.len() - 1]),
+                            new: next,
+                            old: BTreeSet::new(),
+                            next: BTreeSet::new(),
+                        });
+                    }
+                    continue;
+                }
+            };
+            if node.old.contains(&eta) {
+                pending.push(node);
+                continue;
+            }
+            let add_new = |node: &mut Node, f: &Nnf| {
+                if !node.old.contains(f) {
+                    node.new.insert(f.clone());
+                }
+            };
+            match &eta {
+                Nnf::False => continue, // contradiction
+                Nnf::Predicate(p) if node.old.contains(&Nnf::NotPredicate(*p)) => continue,
+                Nnf::NotPredicate(p) if node.old.contains(&Nnf::Predicate(*p)) => continue,
+                Nnf::True | Nnf::Predicate(_) | Nnf::NotPredicate(_) => {}
+                Nnf::And(l, r) => {
+                    add_new(&mut node, l);
+                    add_new(&mut node, r);
+                }
+                Nnf::Next(f) => {
+                    node.next.insert((**f).clone());
+                }
+                Nnf::Or(l, r) | Nnf::Until(l, r) | Nnf::Release(l, r) => {
+                    let mut split = Node {
+                        incoming: node.incoming.clone(),
+                        new: node.new.clone(),
+                        old: node.old.clone(),
+                        next: node.next.clone(),
+                    };
+                    split.old.insert(eta.clone());
+                    match &eta {
+                        Nnf::Or(..) => {
+                            add_new(&mut node, l);
+                            add_new(&mut split, r);
+                        }
+                        Nnf::Until(..) => {
+                            add_new(&mut node, l);
+                            node.next.insert(eta.clone());
+                            add_new(&mut split, r);
+                        }
+                        _ => {
+                            add_new(&mut node, r);
+                            node.next.insert(eta.clone());
+                            add_new(&mut split, l);
+                            add_new(&mut split, r);
+                        }
+                    }
+                    pending.push(split);
+                }
+            }
+            node.old.insert(eta);
+            pending.push(node);
+        }
+
+        let mut states: Vec<_> = expanded
+            .iter()
+            .map(|node| AutomatonState {
+                is_init: node.incoming.contains(&INIT),
+                successors: Vec::new(),
+                acceptance: untils
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, until)| match until {
+                        Nnf::Until(_, r) => !node.old.contains(until) || node.old.contains(r),
+                        _ => unreachable!(),
+                    })
+                    .map(|(i, _)| i)
+                    .collect(),
+                positive: node
+                    .old
+                    .iter()
+                    .filter_map(|f| match f {
+                        Nnf::Predicate(p) => Some(*p),
+                        _ => None,
+                    })
+                    .collect(),
+                negative: node
+                    .old
+                    .iter()
+                    .filter_map(|f| match f {
+                        Nnf::NotPredicate(p) => Some(*p),
+                        _ => None,
+                    })
+                    .collect(),
+            })
+            .collect();
+        for (dst, node) in expanded.iter().enumerate() {
+            for &src in &node.incoming {
+                if src != INIT {
+                    states[src].successors.push(dst);
+                }
+            }
+        }
+        Automaton {
+            states,
+            acceptance_set_count: untils.len(),
+        }
+    }
+}
+
+/// Indicates whether a formula holds for an infinite behavior of a model, represented by its
+/// states along with `successors[i]`, the index of the state following state `i`.
+pub(crate) fn holds<M: Model>(
+    formula: &Ltl<M>,
+    model: &M,
+    states: &[M::State],
+    successors: &[usize],
+) -> bool {
+    let mut predicates = Vec::new();
+    let nnf = formula.to_nnf(false, &mut predicates);
+    let holds = |p: usize, i: usize| (predicates[p].1)(model, &states[i]);
+    nnf.evaluate(successors, &holds)[0]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::dgraph::DGraph;
+
+    fn p(name: &'static str, value: u8) -> Ltl<DGraph> {
+        match value {
+            0 => Ltl::predicate(name, |_, s| *s == 0),
+            1 => Ltl::predicate(name, |_, s| *s == 1),
+            _ => Ltl::predicate(name, |_, s| *s == 2),
+        }
+    }
+
+    #[test]
+    fn can_display() {
+        let formula = Ltl::globally(p("a", 0).implies(Ltl::finally((!p("b", 1)).and(p("c", 2)))));
+        assert_eq!(format!("{}", formula), "G (a -> F (!b && c))");
+        assert_eq!(format!("{}", p("a", 0).until(p("b", 1))), "a U b");
+    }
+
+    #[test]
+    fn can_evaluate_on_lasso() {
+        let model = DGraph::with_property(crate::Property::always("unused", |_, _| true));
+        // 0 -> 1 -> 2 -> 1 -> ...
+        let states = vec![0, 1, 2];
+        let successors = vec![1, 2, 1];
+        let eval = |f: Ltl<DGraph>| holds(&f, &model, &states, &successors);
+        assert!(eval(p("zero", 0)));
+        assert!(!eval(p("one", 1)));
+        assert!(eval(Ltl::next(p("one", 1))));
+        assert!(eval(Ltl::globally(Ltl::finally(p("two", 2)))));
+        assert!(!eval(Ltl::finally(Ltl::globally(p("two", 2)))));
+        assert!(eval(p("zero", 0).until(p("one", 1))));
+        assert!(!eval(p("zero", 0).until(p("two", 2))));
+        assert!(eval(Ltl::globally(
+            p("one", 1).implies(Ltl::next(p("two", 2)))
+        )));
+        assert!(!eval(Ltl::globally(!p("zero", 0))));
+        assert!(eval(p("two", 2).release((!p("zero", 0)).or(p("zero", 0)))));
+    }
+
+    #[test]
+    fn can_capture_state_in_predicates() {
+        let model = DGraph::with_property(crate::Property::always("unused", |_, _| true));
+        // 0 -> 1 -> 2 -> 2 -> ...
+        let states = vec![0, 1, 2];
+        let successors = vec![1, 2, 2];
+        let at_least = |threshold: u8| {
+            Ltl::predicate(format!("at least {}", threshold), move |_, s: &u8| {
+                *s >= threshold
+            })
+        };
+        assert_eq!(format!("{}", Ltl::finally(at_least(2))), "F at least 2");
+        assert!(holds(
+            &Ltl::finally(Ltl::globally(at_least(2))),
+            &model,
+            &states,
+            &successors
+        ));
+        assert!(!holds(
+            &Ltl::globally(at_least(1)),
+            &model,
+            &states,
+            &successors
+        ));
+    }
+
+    #[test]
+    fn builds_automaton_for_violations() {
+        let mut predicates = Vec::new();
+        let zero = p("zero", 0);
+        // Violations of `F zero` never satisfy `zero`.
+        let automaton = Automaton::for_violations_of(&Ltl::finally(zero.clone()), &mut predicates);
+        assert_eq!(predicates.len(), 1);
+        assert_eq!(automaton.acceptance_set_count, 0);
+        assert!(automaton.states.iter().any(|s| s.is_init));
+        for state in &automaton.states {
+            assert!(!state.matches(|_| true));
+            assert!(state.matches(|_| false));
+        }
+
+        // Violations of `G zero` must eventually satisfy `!zero`.
+        let automaton = Automaton::for_violations_of(&Ltl::globally(zero), &mut predicates);
+        assert_eq!(predicates.len(), 1);
+        assert_eq!(automaton.acceptance_set_count, 1);
+    }
+}
@@ -1,6 +1,12 @@
 //! Private module for selective re-export.
 
-use crate::checker::{Checker, EventuallyBits, Expectation, Path};
+use crate::checker::control::CheckerControl;
+use crate::checker::discoveries::Discoveries;
+use crate::checker::limits::{Limit, Limits};
+use crate::checker::{
+    checked_properties, complete_discovery, copy_actions, take_steps, violated_by, visit_key,
+    Checker, EventuallyBits, Expectation, Path, DEADLOCK,
+};
 use crate::job_market::JobBroker;
 use crate::{
     fingerprint, CheckerBuilder, CheckerVisitor, ControlFlow, Fingerprint, Model, Property,
@@ -12,8 +18,10 @@ use std::collections::{HashMap, VecDeque};
 use std::hash::{BuildHasherDefault, Hash};
 use std::num::NonZeroUsize;
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::RecvTimeoutError;
 use std::sync::Arc;
 use std::thread::JoinHandle;
+use std::time::Duration;
 
 // While this file is currently quite similar to dfs.rs, a refactoring to lift shared
 // behavior is being postponed until DPOR is implemented.
@@ -22,6 +30,7 @@ pub(crate) struct OnDemandChecker<M: Model> {
     // Immutable state.
     model: Arc<M>,
     handles: Vec<std::thread::JoinHandle<()>>,
+    deadlock_detection: bool,
 
     // Mutable state.
     job_broker: JobBroker<Job<M::State>>,
@@ -29,9 +38,16 @@ pub(crate) struct OnDemandChecker<M: Model> {
     max_depth: Arc<AtomicUsize>,
     generated:
         Arc<DashMap<Fingerprint, Option<Fingerprint>, BuildHasherDefault<NoHashHasher<u64>>>>,
-    discoveries: Arc<DashMap<&'static str, Fingerprint>>,
+    // Maps visit keys back to state fingerprints. Only populated when applying symmetry reduction,
+    // as visit keys are otherwise the state fingerprints themselves.
+    state_fingerprints: Option<StateFingerprints>,
+    discoveries: Arc<Discoveries<Fingerprint>>,
+    limits: Arc<Limits>,
+    control: CheckerControl,
     control_flow: std::sync::mpsc::SyncSender<ControlFlow>,
 }
+type StateFingerprints =
+    Arc<DashMap<Fingerprint, Fingerprint, BuildHasherDefault<NoHashHasher<u64>>>>;
 type Job<State> = (State, Fingerprint, EventuallyBits, NonZeroUsize);
 
 impl<M> OnDemandChecker<M>
@@ -41,10 +57,17 @@ where
 {
     pub(crate) fn spawn(options: CheckerBuilder<M>) -> Self {
         let model = Arc::new(options.model);
-        let target_state_count = options.target_state_count;
+        let deadlock_detection = options.deadlock_detection;
+        let symmetry = options.symmetry;
+        let control = CheckerControl::new();
+        let limits = Arc::new(Limits::new(
+            options.target_state_count,
+            options.target_duration,
+            options.target_memory,
+        ));
         let thread_count = options.thread_count;
         let visitor = Arc::new(options.visitor);
-        let property_count = model.properties().len();
+        let property_count = checked_properties(&*model, deadlock_detection).len();
 
         let mut controlflow_channels = Vec::new();
         let (controlflow_to_check_sender, controlflow_to_check_receiver) =
@@ -57,10 +80,19 @@ where
             .collect();
         let state_count = Arc::new(AtomicUsize::new(init_states.len()));
         let max_depth = Arc::new(AtomicUsize::new(0));
+        let state_fingerprints: Option<StateFingerprints> = if symmetry.is_some() {
+            Some(Arc::new(DashMap::default()))
+        } else {
+            None
+        };
         let generated = Arc::new({
             let generated = DashMap::default();
             for s in &init_states {
-                generated.insert(fingerprint(s), None);
+                let key = visit_key(s, symmetry, None);
+                if let Some(state_fingerprints) = &state_fingerprints {
+                    state_fingerprints.insert(key, fingerprint(s));
+                }
+                generated.insert(key, None);
             }
             generated
         });
@@ -80,11 +112,11 @@ where
         let pending: VecDeque<_> = init_states
             .into_iter()
             .map(|s| {
-                let fp = fingerprint(&s);
-                (s, fp, ebits.clone(), NonZeroUsize::new(1).unwrap())
+                let key = visit_key(&s, symmetry, None);
+                (s, key, ebits.clone(), NonZeroUsize::new(1).unwrap())
             })
             .collect();
-        let discoveries = Arc::new(DashMap::default());
+        let discoveries = Arc::new(Discoveries::new(options.max_discoveries_per_property));
         let mut handles = Vec::new();
 
         let mut job_broker = JobBroker::new(thread_count);
@@ -96,7 +128,10 @@ where
             let state_count = Arc::clone(&state_count);
             let max_depth = Arc::clone(&max_depth);
             let generated = Arc::clone(&generated);
+            let state_fingerprints = state_fingerprints.clone();
             let discoveries = Arc::clone(&discoveries);
+            let limits = Arc::clone(&limits);
+            let control = control.clone();
 
             let (controlflow_sender, controlflow_receiver) = std::sync::mpsc::channel();
             controlflow_channels.push(controlflow_sender);
@@ -133,7 +168,16 @@ where
                             if wait_for_fingerprints {
                                 // Step 0: wait for someone to ask us to do work
                                 loop {
-                                    let control_flow = controlflow_receiver.recv();
+                                    // Wakes periodically to honor cancellation.
+                                    let control_flow = controlflow_receiver
+                                        .recv_timeout(Duration::from_millis(100));
+                                    if let Err(RecvTimeoutError::Timeout) = control_flow {
+                                        if control.is_cancelled() {
+                                            log::debug!("{}: Cancelled. Shutting down...", t);
+                                            return;
+                                        }
+                                        continue;
+                                    }
                                     if let Ok(control_flow) = control_flow {
                                         match control_flow {
                                             ControlFlow::CheckFingerprint(fingerprint) => {
@@ -145,9 +189,13 @@ where
                                                 if pending.is_empty() {
                                                     break;
                                                 }
-                                                if let Some(index) = pending
-                                                    .iter()
-                                                    .position(|(_, f, _, _)| *f == fingerprint)
+                                                if let Some(index) =
+                                                    pending.iter().position(|(_, f, _, _)| {
+                                                        state_fingerprint(
+                                                            state_fingerprints.as_deref(),
+                                                            *f,
+                                                        ) == fingerprint
+                                                    })
                                                 {
                                                     targetted_pending
                                                         .push_back(pending.remove(index).unwrap());
@@ -173,19 +221,31 @@ where
                                 targetted_pending.append(&mut pending);
                             }
 
+                            if !control.wait_while_paused() {
+                                log::debug!(
+                                    "{}: Cancelled. Shutting down... gen={}",
+                                    t,
+                                    generated.len()
+                                );
+                                return;
+                            }
+
                             // Step 1: Do work.
                             Self::check_block(
                                 &model,
                                 &state_count,
                                 &generated,
+                                state_fingerprints.as_deref(),
                                 &mut targetted_pending,
                                 &discoveries,
                                 &visitor,
                                 1500,
                                 &max_depth,
+                                symmetry,
+                                deadlock_detection,
                             );
                             pending.append(&mut targetted_pending);
-                            if discoveries.len() == property_count {
+                            if discoveries.complete_count() == property_count {
                                 log::debug!(
                                     "{}: Discovery complete. Shutting down... gen={}",
                                     t,
@@ -193,15 +253,14 @@ where
                                 );
                                 return;
                             }
-                            if let Some(target_state_count) = target_state_count {
-                                if target_state_count.get() <= state_count.load(Ordering::Relaxed) {
-                                    log::debug!(
-                                        "{}: Reached target state count. Shutting down... gen={}",
-                                        t,
-                                        generated.len()
-                                    );
-                                    return;
-                                }
+                            if let Some(limit) = limits.check(state_count.load(Ordering::Relaxed)) {
+                                log::debug!(
+                                    "{}: Reached {} limit. Shutting down... gen={}",
+                                    t,
+                                    limit,
+                                    generated.len()
+                                );
+                                return;
                             }
 
                             // Step 2: Share work.
@@ -226,16 +285,21 @@ where
         OnDemandChecker {
             model,
             handles,
+            deadlock_detection,
             job_broker,
             state_count,
             max_depth,
             generated,
+            state_fingerprints,
             discoveries,
+            limits,
+            control,
             control_flow: controlflow_to_check_sender,
         }
     }
 
     #[allow(clippy::too_many_arguments)]
+    #[allow(clippy::type_complexity)]
     fn check_block(
         model: &M,
         state_count: &AtomicUsize,
@@ -244,12 +308,18 @@ where
             Option<Fingerprint>,
             BuildHasherDefault<NoHashHasher<u64>>,
         >,
+        state_fingerprints: Option<
+            &DashMap<Fingerprint, Fingerprint, BuildHasherDefault<NoHashHasher<u64>>>,
+        >,
         pending: &mut VecDeque<Job<M::State>>,
-        discoveries: &DashMap<&'static str, Fingerprint>,
+        discoveries: &Discoveries<Fingerprint>,
         visitor: &Option<Box<dyn CheckerVisitor<M> + Send + Sync>>,
         max_count: usize,
         global_max_depth: &AtomicUsize,
+        symmetry: Option<fn(&M::State) -> M::State>,
+        deadlock_detection: bool,
     ) {
+        // Deadlocks are detected via the steps taken rather than via a property.
         let properties = model.properties();
 
         let mut current_max_depth = global_max_depth.load(Ordering::Relaxed);
@@ -275,43 +345,51 @@ where
             }
 
             if let Some(visitor) = visitor {
-                visitor.visit(model, reconstruct_path(model, generated, state_fp));
+                visitor.visit(
+                    model,
+                    reconstruct_path(model, generated, state_fingerprints, state_fp),
+                );
             }
 
             // Done if discoveries found for all properties.
-            let mut is_awaiting_discoveries = false;
+            let mut is_awaiting_discoveries =
+                deadlock_detection && !discoveries.is_complete(DEADLOCK);
+            let mut step_properties = Vec::new();
             for (i, property) in properties.iter().enumerate() {
-                if discoveries.contains_key(property.name) {
+                if discoveries.is_complete(&property.name) {
                     continue;
                 }
                 match property {
                     Property {
                         expectation: Expectation::Always,
-                        condition: always,
                         ..
                     } => {
-                        if !always(model, &state) {
+                        if !property.holds(model, &state) {
                             // Races other threads, but that's fine.
-                            discoveries.insert(property.name, state_fp);
-                        } else {
-                            is_awaiting_discoveries = true;
+                            discoveries.insert(&property.name, state_fp, state_fp);
                         }
+                        is_awaiting_discoveries |= !discoveries.is_complete(&property.name);
+                    }
+                    Property {
+                        expectation: Expectation::AlwaysStep,
+                        ..
+                    } => {
+                        // Judged on the steps taken below.
+                        step_properties.push(property);
+                        is_awaiting_discoveries = true;
                     }
                     Property {
                         expectation: Expectation::Sometimes,
-                        condition: sometimes,
                         ..
                     } => {
-                        if sometimes(model, &state) {
+                        if property.holds(model, &state) {
                             // Races other threads, but that's fine.
-                            discoveries.insert(property.name, state_fp);
-                        } else {
-                            is_awaiting_discoveries = true;
+                            discoveries.insert(&property.name, state_fp, state_fp);
                         }
+                        is_awaiting_discoveries |= !discoveries.is_complete(&property.name);
                     }
                     Property {
                         expectation: Expectation::Eventually,
-                        condition: eventually,
                         ..
                     } => {
                         // The checker early exits after finding discoveries for every property,
@@ -320,10 +398,16 @@ where
                         // discovery regardless of whether the eventually property is now satisfied
                         // (i.e. it might be falsifiable via a different path).
                         is_awaiting_discoveries = true;
-                        if eventually(model, &state) {
+                        if property.holds(model, &state) {
                             ebits.remove(i);
                         }
                     }
+                    Property {
+                        expectation: Expectation::Ltl,
+                        ..
+                    } => {
+                        // Only checked by the liveness checker.
+                    }
                 }
             }
             if !is_awaiting_discoveries {
@@ -332,10 +416,14 @@ where
 
             // Otherwise enqueue newly generated states (with related metadata).
             let mut is_terminal = true;
+            let mut is_deadlocked = true;
             model.actions(&state, &mut actions);
-            let next_states = actions.drain(..).flat_map(|a| model.next_state(&state, a));
-            for next_state in next_states {
-                let next_fp = fingerprint(&next_state);
+            let copies = copy_actions(model, &state, &step_properties);
+            for (action, next_state) in take_steps(model, &state, actions.drain(..), copies) {
+                is_deadlocked = false;
+                // With symmetry reduction, the search continues from the state itself rather than
+                // its representative so that the path to it remains valid.
+                let next_fp = visit_key(&next_state, symmetry, None);
                 log::debug!(
                     "checker generated state transition: {} -> {}",
                     state_fp,
@@ -347,6 +435,18 @@ where
                 }
                 state_count.fetch_add(1, Ordering::Relaxed);
 
+                // Judge the step even if the next state was already generated.
+                for property in violated_by(
+                    model,
+                    &step_properties,
+                    &state,
+                    action.as_ref(),
+                    &next_state,
+                ) {
+                    // Races other threads, but that's fine.
+                    discoveries.insert(&property.name, state_fp, state_fp);
+                }
+
                 // Skip if already generated.
                 //
                 // FIXME: we should really include ebits in the fingerprint here --
@@ -356,6 +456,9 @@ where
                 // that it holds in the path leading to the second visit -- another
                 // possible false-negative.
                 if let Entry::Vacant(next_entry) = generated.entry(next_fp) {
+                    if let Some(state_fingerprints) = state_fingerprints {
+                        state_fingerprints.insert(next_fp, fingerprint(&next_state));
+                    }
                     next_entry.insert(Some(state_fp));
                 } else {
                     // FIXME: arriving at an already-known state may be a loop (in which case it
@@ -383,9 +486,14 @@ where
                 for (i, property) in properties.iter().enumerate() {
                     if ebits.contains(i) {
                         // Races other threads, but that's fine.
-                        discoveries.insert(property.name, state_fp);
+                        discoveries.insert(&property.name, state_fp, state_fp);
                     }
                 }
+                // A deadlocked state has no next states at all, even outside the boundary.
+                if deadlock_detection && is_deadlocked && !model.valid_end_state(&state) {
+                    // Races other threads, but that's fine.
+                    discoveries.insert(DEADLOCK, state_fp, state_fp);
+                }
             }
         }
     }
@@ -423,14 +531,44 @@ where
         self.max_depth.load(Ordering::Relaxed)
     }
 
-    fn discoveries(&self) -> HashMap<&'static str, Path<M::State, M::Action>> {
+    fn control(&self) -> Option<CheckerControl> {
+        Some(self.control.clone())
+    }
+
+    fn limit_reached(&self) -> Option<Limit> {
+        self.limits.reached()
+    }
+
+    fn properties(&self) -> Vec<Property<M>> {
+        checked_properties(&*self.model, self.deadlock_detection)
+    }
+
+    fn discoveries(&self) -> HashMap<String, Path<M::State, M::Action>> {
+        self.discoveries_all()
+            .into_iter()
+            .map(|(name, mut paths)| (name, paths.swap_remove(0)))
+            .collect()
+    }
+
+    fn discoveries_all(&self) -> HashMap<String, Vec<Path<M::State, M::Action>>> {
+        let properties = self.properties();
         self.discoveries
-            .iter()
-            .map(|mapref| {
-                (
-                    <&'static str>::clone(mapref.key()),
-                    reconstruct_path(self.model(), &self.generated, *mapref.value()),
-                )
+            .all()
+            .into_iter()
+            .map(|(name, discoveries)| {
+                let paths = discoveries
+                    .into_iter()
+                    .map(|fp| {
+                        let path = reconstruct_path(
+                            self.model(),
+                            &self.generated,
+                            self.state_fingerprints.as_deref(),
+                            fp,
+                        );
+                        complete_discovery(self.model(), &properties, &name, path)
+                    })
+                    .collect();
+                (name, paths)
             })
             .collect()
     }
@@ -440,13 +578,16 @@ where
     }
 
     fn is_done(&self) -> bool {
-        self.job_broker.is_closed() || self.discoveries.len() == self.model.properties().len()
+        self.job_broker.is_closed() || self.discoveries.complete_count() == self.properties().len()
     }
 }
 
 fn reconstruct_path<M>(
     model: &M,
     generated: &DashMap<Fingerprint, Option<Fingerprint>, BuildHasherDefault<NoHashHasher<u64>>>,
+    state_fingerprints: Option<
+        &DashMap<Fingerprint, Fingerprint, BuildHasherDefault<NoHashHasher<u64>>>,
+    >,
     fp: Fingerprint,
 ) -> Path<M::State, M::Action>
 where
@@ -472,9 +613,25 @@ where
             }
         }
     }
+    for fp in fingerprints.iter_mut() {
+        *fp = state_fingerprint(state_fingerprints, *fp);
+    }
     Path::from_fingerprints(model, fingerprints)
 }
 
+/// Maps a visit key back to the fingerprint of the state that was visited.
+fn state_fingerprint(
+    state_fingerprints: Option<
+        &DashMap<Fingerprint, Fingerprint, BuildHasherDefault<NoHashHasher<u64>>>,
+    >,
+    key: Fingerprint,
+) -> Fingerprint {
+    match state_fingerprints {
+        Some(state_fingerprints) => *state_fingerprints.get(&key).unwrap(),
+        None => key,
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -541,4 +698,18 @@ mod test {
             vec![Guess::IncreaseY; 27],
         );
     }
+
+    #[test]
+    fn can_apply_symmetry_reduction() {
+        use crate::test_util::symmetric_processes::*;
+
+        let (visitor, _) = PathRecorder::new_with_accessor();
+        let checker = Sys.checker().symmetry().visitor(visitor).spawn_on_demand();
+        checker.run_to_completion();
+        while !checker.is_done() {
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        assert_eq!(checker.unique_state_count(), 6);
+        assert!(checker.discovery("a process pauses").is_some());
+    }
 }
@@ -10,10 +10,15 @@ use std::hash::Hash;
 /// You can convert to a `Vec<_>` with [`path.into_vec()`]. If you only need the actions, then use
 /// [`path.into_actions()`].
 ///
+/// A path can also be "lasso" shaped, indicating an infinite behavior that repeats a loop forever.
+/// In that case [`path.loop_start()`] indicates the index of the state at which the loop begins,
+/// and the last state of the path is that same state, reached again by the loop's final action.
+///
 /// [`path.into_vec()`]: Path::into_vec
 /// [`path.into_actions()`]: Path::into_actions
+/// [`path.loop_start()`]: Path::loop_start
 #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
-pub struct Path<State, Action>(Vec<(State, Option<Action>)>);
+pub struct Path<State, Action>(Vec<(State, Option<Action>)>, Option<usize>);
 
 impl<State, Action> Path<State, Action> {
     /// Constructs a path from a model and a sequence of fingerprints.
@@ -93,7 +98,15 @@ Available next fingerprints (none of which match): {:?}"#,
             last_state = next_state;
         }
         output.push((last_state, None));
-        Path(output)
+        Path(output, None)
+    }
+
+    /// Extends the path by a step from its last state.
+    pub(crate) fn push(&mut self, action: Action, state: State) {
+        if let Some((_, last_action)) = self.0.last_mut() {
+            *last_action = Some(action);
+        }
+        self.0.push((state, None));
     }
 
     /// Constructs a path from a model, initial state, and a sequence of actions. Panics for inputs
@@ -127,7 +140,7 @@ Available next fingerprints (none of which match): {:?}"#,
         }
         output.push((prev_state, None));
 
-        Some(Path(output))
+        Some(Path(output, None))
     }
 
     /// Determines the final state associated with a particular fingerprint path.
@@ -164,6 +177,31 @@ Available next fingerprints (none of which match): {:?}"#,
         Some(matching_state)
     }
 
+    /// Marks the path as a lasso whose loop begins at the state with the specified index. The last
+    /// state of the path must repeat that state.
+    pub(crate) fn with_loop_start(self, loop_start: usize) -> Self {
+        debug_assert!(loop_start < self.0.len() - 1);
+        Path(self.0, Some(loop_start))
+    }
+
+    /// Converts each action of the path, such as for a model that relabels the actions of another.
+    pub(crate) fn map_actions<A>(self, mut f: impl FnMut(Action) -> A) -> Path<State, A> {
+        Path(
+            self.0
+                .into_iter()
+                .map(|(s, a)| (s, a.map(&mut f)))
+                .collect(),
+            self.1,
+        )
+    }
+
+    /// Indicates the index of the state at which this path's loop begins, if the path is a lasso
+    /// describing an infinite behavior. The last state of such a path repeats the state at this
+    /// index.
+    pub fn loop_start(&self) -> Option<usize> {
+        self.1
+    }
+
     /// Extracts the last state.
     pub fn last_state(&self) -> &State {
         &self.0.last().unwrap().0
@@ -210,8 +248,12 @@ where
     State: Debug,
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
-        writeln!(f, "Path[{}]:", self.0.len() - 1)?;
-        for (_state, action) in &self.0 {
+        let prefix_len = self.1.unwrap_or(self.0.len() - 1);
+        writeln!(f, "Path[{}]:", prefix_len)?;
+        for (i, (_state, action)) in self.0.iter().enumerate() {
+            if Some(i) == self.1 {
+                writeln!(f, "Loop[{}]:", self.0.len() - 1 - prefix_len)?;
+            }
             if let Some(action) = action {
                 writeln!(f, "- {:?}", action)?;
             }
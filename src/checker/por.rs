@@ -0,0 +1,118 @@
+//! Private module for selective re-export.
+
+use crate::{Expectation, Model, Property};
+
+/// Applies partial order reduction to the `actions` enabled in a `state` by looking for a single
+/// "ample" step whose exploration suffices. Such a step's action must be [`Model::independent`]
+/// of every other action, it must not change the outcome of any property's condition, and it must
+/// lead to a state for which `is_fresh` holds, which ensures that the other actions cannot be
+/// postponed indefinitely by a cycle. Every step is observed by an [`Expectation::AlwaysStep`]
+/// property, so nothing is reduced if there are any, and `actions` is left intact.
+///
+/// If an ample step is found, then `actions` is cleared and only its next state is returned.
+/// Otherwise the returned next states belong to actions that were removed from `actions` while
+/// searching, so they still need to be explored along with the remaining `actions`.
+pub(crate) fn reduce<M: Model>(
+    model: &M,
+    properties: &[Property<M>],
+    state: &M::State,
+    actions: &mut Vec<M::Action>,
+    is_fresh: impl Fn(&M::State) -> bool,
+) -> Vec<M::State> {
+    let mut next_states = Vec::new();
+    if actions.len() < 2
+        || properties
+            .iter()
+            .any(|p| p.expectation == Expectation::AlwaysStep)
+    {
+        return next_states;
+    }
+    let candidates: Vec<usize> = (0..actions.len())
+        .filter(|&i| {
+            (0..actions.len()).all(|j| i == j || model.independent(state, &actions[i], &actions[j]))
+        })
+        .collect();
+    // Later candidates are considered first so that removing them leaves earlier indexes intact.
+    for i in candidates.into_iter().rev() {
+        let next_state = match model.next_state(state, actions.remove(i)) {
+            None => continue,
+            Some(next_state) => next_state,
+        };
+        let is_visible = properties.iter().any(|p| {
+            p.expectation != Expectation::Ltl
+                && p.holds(model, state) != p.holds(model, &next_state)
+        });
+        if !is_visible && model.within_boundary(&next_state) && is_fresh(&next_state) {
+            actions.clear();
+            return vec![next_state];
+        }
+        next_states.push(next_state);
+    }
+    next_states
+}
+
+#[cfg(test)]
+mod test {
+    use crate::actor::actor_test_util::broadcast::{broadcast_model, Broadcaster};
+    use crate::actor::ActorModel;
+    use crate::{Checker, Expectation, Model};
+
+    /// Deliveries to actors 1 and 2 are observed together, while actor 3 is unobserved.
+    fn model() -> ActorModel<Broadcaster> {
+        broadcast_model()
+            .property(Expectation::Always, "2 never leads 1", |_, s| {
+                s.actor_states[2] <= s.actor_states[1]
+            })
+            .property(Expectation::Sometimes, "all received", |_, s| {
+                s.actor_states.iter().skip(1).all(|s| **s == 1)
+            })
+            .property(Expectation::Eventually, "1 receives", |_, s| {
+                *s.actor_states[1] == 1
+            })
+    }
+
+    #[test]
+    fn reduces_bfs_without_losing_discoveries() {
+        let checker = model().checker().spawn_bfs().join();
+        assert!(checker.discovery("2 never leads 1").is_some());
+        assert_eq!(checker.unique_state_count(), 8);
+
+        let checker = model()
+            .checker()
+            .partial_order_reduction()
+            .spawn_bfs()
+            .join();
+        assert!(checker.discovery("2 never leads 1").is_some());
+        assert!(checker.discovery("all received").is_some());
+        checker.assert_no_discovery("1 receives");
+        assert!(checker.unique_state_count() < 8);
+    }
+
+    #[test]
+    fn reduces_dfs_without_losing_discoveries() {
+        let checker = model().checker().spawn_dfs().join();
+        assert_eq!(checker.unique_state_count(), 8);
+
+        let checker = model()
+            .checker()
+            .partial_order_reduction()
+            .spawn_dfs()
+            .join();
+        assert!(checker.discovery("2 never leads 1").is_some());
+        assert!(checker.discovery("all received").is_some());
+        checker.assert_no_discovery("1 receives");
+        // Actor 3 receives first, then deliveries to 1 and 2 are interleaved.
+        assert_eq!(checker.unique_state_count(), 1 + 2 * 2);
+    }
+
+    #[test]
+    fn does_not_reduce_without_independence() {
+        let checker = crate::test_util::linear_equation_solver::LinearEquation { a: 2, b: 4, c: 7 }
+            .checker()
+            .partial_order_reduction()
+            .target_max_depth(4)
+            .spawn_bfs()
+            .join();
+        assert_eq!(checker.unique_state_count(), 10);
+    }
+}
@@ -0,0 +1,278 @@
+//! Private module for selective re-export.
+
+use crate::{Model, Path, Property};
+use std::fmt::Debug;
+use std::sync::Arc;
+
+/// A [`Model`] for checking that an implementation model refines a specification model, meaning
+/// that every behavior of the implementation is also a behavior of the specification once its
+/// states are mapped by an abstraction function.
+///
+/// The model has the states and actions of the implementation, and its properties require that
+/// the abstraction of each initial state is an initial state of the specification and that each
+/// step of the implementation maps to either a step of the specification or a stutter (a step
+/// that leaves the abstract state unchanged). A discovery is therefore a [`Path`] of the
+/// implementation: for [`Refinement::INIT_PROPERTY`] it ends in the offending initial state, and
+/// for [`Refinement::STEP_PROPERTY`] it ends with the offending step.
+///
+/// # Example
+///
+/// ```
+/// use stateright::*;
+///
+/// /// A clock that ticks from 0 to 3 and wraps around.
+/// struct Spec;
+/// impl Model for Spec {
+///     type State = u8;
+///     type Action = ();
+///     fn init_states(&self) -> Vec<Self::State> { vec![0] }
+///     fn actions(&self, _: &Self::State, actions: &mut Vec<Self::Action>) { actions.push(()); }
+///     fn next_state(&self, s: &Self::State, _: Self::Action) -> Option<Self::State> {
+///         Some((s + 1) % 4)
+///     }
+/// }
+///
+/// /// Implements the clock with minutes and seconds, where the minute is the spec's tick.
+/// struct Impl;
+/// impl Model for Impl {
+///     type State = (u8, u8);
+///     type Action = ();
+///     fn init_states(&self) -> Vec<Self::State> { vec![(0, 0)] }
+///     fn actions(&self, _: &Self::State, actions: &mut Vec<Self::Action>) { actions.push(()); }
+///     fn next_state(&self, &(m, s): &Self::State, _: Self::Action) -> Option<Self::State> {
+///         if s < 59 { Some((m, s + 1)) } else { Some(((m + 1) % 4, 0)) }
+///     }
+/// }
+///
+/// Refinement::new(Impl, Spec, |&(m, _)| m)
+///     .checker().spawn_bfs().join()
+///     .assert_properties();
+/// ```
+pub struct Refinement<I: Model, S: Model> {
+    implementation: I,
+    specification: S,
+    #[allow(clippy::type_complexity)]
+    abstraction: Arc<dyn Fn(&I::State) -> S::State + Send + Sync>,
+    /// Cached so that [`Refinement::INIT_PROPERTY`] need not recompute them for every state.
+    implementation_inits: Vec<I::State>,
+    specification_inits: Vec<S::State>,
+}
+
+impl<I: Model, S: Model> Refinement<I, S> {
+    /// The name of the property requiring that initial states map to initial states of the
+    /// specification.
+    pub const INIT_PROPERTY: &'static str = "initial states refine specification";
+
+    /// The name of the property requiring that steps map to steps of the specification or
+    /// stutters.
+    pub const STEP_PROPERTY: &'static str = "steps refine specification";
+
+    /// Pairs an `implementation` with the `specification` it should refine, given an
+    /// `abstraction` function that maps implementation states to specification states.
+    ///
+    /// The initial states of both models are computed once here, so they should not vary.
+    pub fn new(
+        implementation: I,
+        specification: S,
+        abstraction: impl Fn(&I::State) -> S::State + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            implementation_inits: implementation.init_states(),
+            specification_inits: specification.init_states(),
+            implementation,
+            specification,
+            abstraction: Arc::new(abstraction),
+        }
+    }
+
+    /// Returns the implementation model.
+    pub fn implementation(&self) -> &I {
+        &self.implementation
+    }
+
+    /// Returns the specification model.
+    pub fn specification(&self) -> &S {
+        &self.specification
+    }
+
+    /// Maps an implementation state to the corresponding specification state.
+    pub fn abstraction(&self, state: &I::State) -> S::State {
+        (self.abstraction)(state)
+    }
+
+    /// Maps each state along an implementation path, such as a discovery, to the corresponding
+    /// specification state.
+    pub fn abstract_states(&self, path: Path<I::State, I::Action>) -> Vec<S::State> {
+        path.into_states()
+            .iter()
+            .map(|state| self.abstraction(state))
+            .collect()
+    }
+}
+
+impl<I, S> Model for Refinement<I, S>
+where
+    I: Model,
+    I::State: PartialEq,
+    S: Model,
+    S::State: PartialEq,
+{
+    type State = I::State;
+    type Action = I::Action;
+
+    fn init_states(&self) -> Vec<Self::State> {
+        self.implementation.init_states()
+    }
+
+    fn actions(&self, state: &Self::State, actions: &mut Vec<Self::Action>) {
+        self.implementation.actions(state, actions)
+    }
+
+    fn next_state(&self, last_state: &Self::State, action: Self::Action) -> Option<Self::State> {
+        self.implementation.next_state(last_state, action)
+    }
+
+    fn format_action(&self, action: &Self::Action) -> String
+    where
+        Self::Action: Debug,
+    {
+        self.implementation.format_action(action)
+    }
+
+    fn format_step(&self, last_state: &Self::State, action: Self::Action) -> Option<String>
+    where
+        Self::State: Debug,
+    {
+        self.implementation.format_step(last_state, action)
+    }
+
+    fn as_svg(&self, path: Path<Self::State, Self::Action>) -> Option<String> {
+        self.implementation.as_svg(path)
+    }
+
+    fn properties(&self) -> Vec<Property<Self>> {
+        vec![
+            Property::always(Self::INIT_PROPERTY, |refinement: &Self, state| {
+                !refinement.implementation_inits.contains(state)
+                    || refinement
+                        .specification_inits
+                        .contains(&refinement.abstraction(state))
+            }),
+            Property::always_step(
+                Self::STEP_PROPERTY,
+                |refinement: &Self, last_state, _, next_state| {
+                    let last_state = refinement.abstraction(last_state);
+                    let next_state = refinement.abstraction(next_state);
+                    next_state == last_state
+                        || refinement
+                            .specification
+                            .next_states(&last_state)
+                            .contains(&next_state)
+                },
+            ),
+        ]
+    }
+
+    fn within_boundary(&self, state: &Self::State) -> bool {
+        self.implementation.within_boundary(state)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::linear_equation_solver::{Guess, LinearEquation};
+    use crate::Checker;
+
+    /// Increments `x` and `y` in lockstep, so `x - y` never changes.
+    struct Diagonal;
+    impl Model for Diagonal {
+        type State = (u8, u8);
+        type Action = Guess;
+        fn init_states(&self) -> Vec<Self::State> {
+            vec![(0, 0)]
+        }
+        fn actions(&self, _: &Self::State, actions: &mut Vec<Self::Action>) {
+            actions.push(Guess::IncreaseX);
+        }
+        fn next_state(&self, &(x, y): &Self::State, _: Self::Action) -> Option<Self::State> {
+            Some((x.wrapping_add(1), y.wrapping_add(1)))
+        }
+    }
+
+    #[test]
+    fn accepts_refining_implementation() {
+        // With `y` abstracted away, each step of the diagonal increments `x` as the spec does.
+        Refinement::new(Diagonal, LinearEquation { a: 2, b: 4, c: 7 }, |&(x, _)| {
+            (x, 0)
+        })
+        .checker()
+        .spawn_bfs()
+        .join()
+        .assert_properties();
+
+        // Incrementing both is a stutter when only their difference is observed.
+        let checker = Refinement::new(Diagonal, Diagonal, |&(x, y)| (x.wrapping_sub(y), 0))
+            .checker()
+            .spawn_dfs()
+            .join();
+        checker.assert_properties();
+    }
+
+    #[test]
+    fn reports_implementation_path_for_violating_step() {
+        let refinement = Refinement::new(Diagonal, LinearEquation { a: 2, b: 4, c: 7 }, |&s| s);
+        let checker = refinement.checker().spawn_bfs().join();
+        checker.assert_no_discovery(Refinement::<Diagonal, LinearEquation>::INIT_PROPERTY);
+        checker.assert_discovery(
+            Refinement::<Diagonal, LinearEquation>::STEP_PROPERTY,
+            vec![Guess::IncreaseX],
+        );
+        let path = checker
+            .discovery(Refinement::<Diagonal, LinearEquation>::STEP_PROPERTY)
+            .unwrap();
+        assert_eq!(path.last_state(), &(1, 1));
+        assert_eq!(checker.model().abstract_states(path), vec![(0, 0), (1, 1)]);
+    }
+
+    #[test]
+    fn reports_violating_initial_state() {
+        let refinement = Refinement::new(Diagonal, Diagonal, |&(x, y)| (x.wrapping_add(1), y));
+        let checker = refinement.checker().spawn_bfs().join();
+        assert_eq!(
+            checker
+                .discovery(Refinement::<Diagonal, Diagonal>::INIT_PROPERTY)
+                .unwrap()
+                .into_states(),
+            vec![(0, 0)]
+        );
+    }
+
+    #[test]
+    fn computes_initial_states_once() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        /// Counts calls to `init_states` of the diagonal.
+        struct Counted(AtomicUsize);
+        impl Model for Counted {
+            type State = (u8, u8);
+            type Action = Guess;
+            fn init_states(&self) -> Vec<Self::State> {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                Diagonal.init_states()
+            }
+            fn actions(&self, state: &Self::State, actions: &mut Vec<Self::Action>) {
+                Diagonal.actions(state, actions)
+            }
+            fn next_state(&self, state: &Self::State, action: Self::Action) -> Option<Self::State> {
+                Diagonal.next_state(state, action)
+            }
+        }
+
+        let refinement = Refinement::new(Diagonal, Counted(AtomicUsize::new(0)), |&s| s);
+        let checker = refinement.checker().spawn_bfs().join();
+        checker.assert_properties();
+        assert!(checker.unique_state_count() > 1);
+        assert_eq!(checker.model().specification().0.load(Ordering::SeqCst), 1);
+    }
+}
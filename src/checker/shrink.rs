@@ -0,0 +1,256 @@
+//! Private module for selective re-export.
+
+use crate::checker::Path;
+use crate::{Expectation, Model, Property};
+
+/// Shrinks the discovery for a property. See
+/// [`CheckerBuilder::shrink_discoveries`](crate::CheckerBuilder::shrink_discoveries).
+pub(crate) type Shrink<M> = fn(
+    &M,
+    &[Property<M>],
+    &str,
+    Path<<M as Model>::State, <M as Model>::Action>,
+) -> Path<<M as Model>::State, <M as Model>::Action>;
+
+/// Minimizes the `path` discovered for the property named `name` by delta debugging its actions:
+/// progressively smaller runs of actions are removed, and adjacent actions are swapped, whenever
+/// replaying the remaining actions via the model still leads to a discovery for the property. A
+/// replay stops at the first state that is a discovery, so the result is never longer than the
+/// original. Paths for [`Expectation::Ltl`] properties are returned as is, as are paths that
+/// cannot be replayed.
+pub(crate) fn shrink<M>(
+    model: &M,
+    properties: &[Property<M>],
+    name: &str,
+    path: Path<M::State, M::Action>,
+) -> Path<M::State, M::Action>
+where
+    M: Model,
+    M::State: PartialEq,
+    M::Action: PartialEq,
+{
+    let property = match properties.iter().find(|p| p.name == name) {
+        Some(property) if property.expectation != Expectation::Ltl => property,
+        _ => return path,
+    };
+    if path.loop_start().is_some() {
+        return path;
+    }
+    let mut steps = path.into_vec();
+    let actions: Vec<M::Action> = steps
+        .iter_mut()
+        .filter_map(|(_, action)| action.take())
+        .collect();
+    let (init_state, _) = steps.swap_remove(0);
+
+    // Candidates are indexes into the original actions, which need not be cloneable.
+    let discovery_len = |indexes: &[usize]| replay(model, property, &init_state, &actions, indexes);
+    let mut current: Vec<usize> = (0..actions.len()).collect();
+    match discovery_len(&current) {
+        Some(len) => current.truncate(len),
+        None => {
+            return Path::from_actions(model, init_state, &actions)
+                .expect("Discovery should be replayable")
+        }
+    }
+    loop {
+        // Remove runs of actions, halving the length of the runs once none can be removed.
+        let mut run_len = (current.len() / 2).max(1);
+        while !current.is_empty() {
+            let mut is_reduced = false;
+            let mut start = 0;
+            while start < current.len() {
+                let end = (start + run_len).min(current.len());
+                let candidate: Vec<usize> = current[..start]
+                    .iter()
+                    .chain(&current[end..])
+                    .copied()
+                    .collect();
+                match discovery_len(&candidate) {
+                    Some(len) => {
+                        current = candidate;
+                        current.truncate(len);
+                        is_reduced = true;
+                    }
+                    None => start += run_len,
+                }
+            }
+            if !is_reduced {
+                if run_len == 1 {
+                    break;
+                }
+                run_len /= 2;
+            }
+            run_len = run_len.min((current.len() / 2).max(1));
+        }
+
+        // Reordering can reach a discovery sooner or enable further removals.
+        let reordered = (1..current.len()).find_map(|i| {
+            let mut candidate = current.clone();
+            candidate.swap(i - 1, i);
+            discovery_len(&candidate)
+                .filter(|len| *len < current.len())
+                .map(|len| {
+                    candidate.truncate(len);
+                    candidate
+                })
+        });
+        match reordered {
+            Some(reordered) => current = reordered,
+            None => break,
+        }
+    }
+    Path::from_actions(model, init_state, current.iter().map(|&i| &actions[i]))
+        .expect("Shrunk discovery should be replayable")
+}
+
+/// Replays the `actions` at `indexes` from `init_state`, returning how many of them lead to a
+/// discovery for the `property`, or `None` if the actions do not.
+fn replay<M>(
+    model: &M,
+    property: &Property<M>,
+    init_state: &M::State,
+    actions: &[M::Action],
+    indexes: &[usize],
+) -> Option<usize>
+where
+    M: Model,
+    M::Action: PartialEq,
+{
+    let is_discovery = |state: &M::State| match property.expectation {
+        Expectation::Always => !property.holds(model, state),
+        Expectation::Sometimes => property.holds(model, state),
+        Expectation::AlwaysStep | Expectation::Eventually | Expectation::Ltl => false,
+    };
+    let mut next_state = None;
+    for (len, &i) in indexes.iter().enumerate() {
+        let state = next_state.as_ref().unwrap_or(init_state);
+        if is_discovery(state) {
+            return Some(len);
+        }
+        if property.expectation == Expectation::Eventually && property.holds(model, state) {
+            return None;
+        }
+        let (action, next) = model
+            .next_steps(state)
+            .into_iter()
+            .find(|(action, _)| action == &actions[i])?;
+        if !model.within_boundary(&next) {
+            return None;
+        }
+        if let Some(step_condition) = &property.step_condition {
+            if !step_condition(model, state, &action, &next) {
+                return Some(len + 1);
+            }
+        }
+        next_state = Some(next);
+    }
+    let state = next_state.as_ref().unwrap_or(init_state);
+    match property.expectation {
+        Expectation::Eventually => {
+            // A counterexample ends in a terminal state without satisfying the property.
+            let is_terminal = model
+                .next_steps(state)
+                .into_iter()
+                .all(|(_, next_state)| !model.within_boundary(&next_state));
+            (is_terminal && !property.holds(model, state)).then_some(indexes.len())
+        }
+        _ => is_discovery(state).then_some(indexes.len()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::counter::Counter;
+    use crate::test_util::linear_equation_solver::*;
+    use crate::*;
+
+    /// Counts to 5, but can reset or skip ahead from 3.
+    fn counter() -> Counter {
+        Counter::new(5)
+            .with_jump("reset", 3, 0)
+            .with_jump("skip", 3, 5)
+            .with_property(Property::always("below 5", |_, s| *s < 5))
+            .with_property(Property::eventually("reaches 4", |_, s| *s == 4))
+    }
+
+    #[test]
+    fn can_shrink_discoveries() {
+        // DFS tries the last action first, so it increases y until it wraps around.
+        let checker = LinearEquation { a: 1, b: 0, c: 2 }
+            .checker()
+            .spawn_dfs()
+            .join();
+        assert!(checker.discovery("solvable").unwrap().into_actions().len() > 256);
+        assert_eq!(
+            checker.shrunk_discovery("solvable").unwrap().into_actions(),
+            vec![Guess::IncreaseX, Guess::IncreaseX]
+        );
+
+        let path = Path::from_actions(
+            &counter(),
+            0,
+            &[vec!["increment"; 3], vec!["reset"], vec!["increment"; 5]].concat(),
+        );
+        assert_eq!(
+            super::shrink(
+                &counter(),
+                &counter().properties(),
+                "below 5",
+                path.unwrap()
+            )
+            .into_actions(),
+            vec!["increment"; 5]
+        );
+
+        // An "eventually" counterexample must still end in a terminal state.
+        let path = Path::from_actions(
+            &counter(),
+            0,
+            &[
+                vec!["increment"; 3],
+                vec!["reset"],
+                vec!["increment"; 3],
+                vec!["skip"],
+            ]
+            .concat(),
+        );
+        assert_eq!(
+            super::shrink(
+                &counter(),
+                &counter().properties(),
+                "reaches 4",
+                path.unwrap()
+            )
+            .into_actions(),
+            vec!["increment", "increment", "increment", "skip"]
+        );
+    }
+
+    #[test]
+    fn can_shrink_deadlock_discoveries() {
+        let properties = crate::checker::checked_properties(&counter(), true);
+        let path = Path::from_actions(
+            &counter(),
+            0,
+            &[vec!["increment"; 3], vec!["reset"], vec!["increment"; 5]].concat(),
+        );
+        assert_eq!(
+            super::shrink(&counter(), &properties, "deadlock", path.unwrap()).into_actions(),
+            vec!["increment"; 5]
+        );
+    }
+
+    #[test]
+    fn can_shrink_discoveries_automatically() {
+        let checker = LinearEquation { a: 1, b: 0, c: 2 }
+            .checker()
+            .shrink_discoveries()
+            .spawn_dfs()
+            .join();
+        assert_eq!(
+            checker.discovery("solvable").unwrap().into_actions(),
+            vec![Guess::IncreaseX, Guess::IncreaseX]
+        );
+    }
+}
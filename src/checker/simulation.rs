@@ -1,11 +1,14 @@
 //! Private module for selective re-export.
 
-use crate::checker::{Checker, Expectation, Path};
+use crate::checker::control::CheckerControl;
+use crate::checker::discoveries::Discoveries;
+use crate::checker::limits::{Limit, Limits};
+use crate::checker::shrink::Shrink;
+use crate::checker::{
+    checked_properties, complete_discovery, copy_actions, violated_by, Checker, Expectation, Path,
+    DEADLOCK,
+};
 use crate::{fingerprint, CheckerBuilder, CheckerVisitor, Fingerprint, Model, Property};
-use dashmap::DashMap;
-use rand::rngs::StdRng;
-use rand::Rng;
-use rand::SeedableRng;
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::hash::Hash;
 use std::num::NonZeroUsize;
@@ -43,8 +46,7 @@ pub struct UniformChooser;
 
 /// A chooser that makes uniform choices.
 pub struct UniformChooserState {
-    // FIXME: use a reproducible rng, one that will not change over versions.
-    rng: StdRng,
+    rng: StableRng,
 }
 
 impl<M> Chooser<M> for UniformChooser
@@ -55,7 +57,7 @@ where
 
     fn new_state(&self, seed: u64) -> Self::State {
         UniformChooserState {
-            rng: StdRng::seed_from_u64(seed),
+            rng: StableRng::new(seed),
         }
     }
 
@@ -64,7 +66,7 @@ where
         state: &mut Self::State,
         initial_states: &[<M as Model>::State],
     ) -> usize {
-        state.rng.gen_range(0..initial_states.len())
+        state.rng.below(initial_states.len())
     }
 
     fn choose_action(
@@ -73,7 +75,43 @@ where
         _current_state: &<M as Model>::State,
         actions: &[<M as Model>::Action],
     ) -> usize {
-        state.rng.gen_range(0..actions.len())
+        state.rng.below(actions.len())
+    }
+}
+
+/// A pseudorandom number generator
+/// ([SplitMix64](https://prng.di.unimi.it/splitmix64.c)) whose output is fully determined by its
+/// seed, unlike those of the `rand` crate, which may change between versions. Simulation traces
+/// can therefore be reproduced from their seeds by any version of this library.
+#[derive(Clone)]
+pub(crate) struct StableRng {
+    state: u64,
+}
+
+impl StableRng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number in `0..n` with equal probability. Panics if `n` is zero.
+    pub(crate) fn below(&mut self, n: usize) -> usize {
+        let n = n as u64;
+        // Rejects the values beyond the largest multiple of `n` to avoid modulo bias.
+        let zone = u64::MAX - u64::MAX % n;
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return (value % n) as usize;
+            }
+        }
     }
 }
 
@@ -81,11 +119,15 @@ pub(crate) struct SimulationChecker<M: Model> {
     // Immutable state.
     model: Arc<M>,
     handles: Vec<std::thread::JoinHandle<()>>,
+    deadlock_detection: bool,
 
     // Mutable state.
     state_count: Arc<AtomicUsize>,
     max_depth: Arc<AtomicUsize>,
-    discoveries: Arc<DashMap<&'static str, Vec<Fingerprint>>>,
+    discoveries: Arc<Discoveries<(u64, Vec<Fingerprint>)>>,
+    limits: Arc<Limits>,
+    control: CheckerControl,
+    shrink: Option<Shrink<M>>,
 }
 
 impl<M> SimulationChecker<M>
@@ -97,28 +139,44 @@ where
     ///
     /// `seed` is the seed for the random selection of actions between states.
     /// It is passed straight through to the first trace on the first thread to allow for
-    /// reproducibility. For other threads and traces it is regenerated using a [`StdRng`].
-    pub(crate) fn spawn<C: Chooser<M>>(options: CheckerBuilder<M>, seed: u64, chooser: C) -> Self {
+    /// reproducibility. For other threads and traces it is regenerated using a [`StableRng`].
+    /// If `replay` is set then only that first trace is checked.
+    pub(crate) fn spawn<C: Chooser<M>>(
+        options: CheckerBuilder<M>,
+        seed: u64,
+        chooser: C,
+        replay: bool,
+    ) -> Self {
         let model = Arc::new(options.model);
+        let deadlock_detection = options.deadlock_detection;
+        let shrink = options.shrink;
         let symmetry = options.symmetry;
-        let target_state_count = options.target_state_count;
+        let control = CheckerControl::new();
+        let limits = Arc::new(Limits::new(
+            options.target_state_count,
+            options.target_duration,
+            options.target_memory,
+        ));
         let target_max_depth = options.target_max_depth;
         let visitor = Arc::new(options.visitor);
-        let property_count = model.properties().len();
+        let property_count = checked_properties(&*model, deadlock_detection).len();
 
         let state_count = Arc::new(AtomicUsize::new(0));
         let max_depth = Arc::new(AtomicUsize::new(0));
-        let discoveries = Arc::new(DashMap::default());
+        let discoveries = Arc::new(Discoveries::new(options.max_discoveries_per_property));
         let mut handles = Vec::new();
 
         let mut thread_seed = seed;
+        let thread_count = if replay { 1 } else { options.thread_count };
 
-        for t in 0..options.thread_count {
+        for t in 0..thread_count {
             let model = Arc::clone(&model);
             let visitor = Arc::clone(&visitor);
             let state_count = Arc::clone(&state_count);
             let max_depth = Arc::clone(&max_depth);
             let discoveries = Arc::clone(&discoveries);
+            let limits = Arc::clone(&limits);
+            let control = control.clone();
             let chooser = chooser.clone();
             handles.push(
                 std::thread::Builder::new()
@@ -126,9 +184,12 @@ where
                     .spawn(move || {
                         let mut seed = thread_seed;
                         log::debug!("{}: Thread started with seed={}.", t, seed);
-                        // FIXME: use a reproducible rng, one that will not change over versions.
-                        let mut rng = StdRng::seed_from_u64(seed);
+                        let mut rng = StableRng::new(seed);
                         loop {
+                            if !control.wait_while_paused() {
+                                log::debug!("{}: Cancelled. Shutting down...", t);
+                                return;
+                            }
                             Self::check_trace_from_initial::<C>(
                                 &model,
                                 seed,
@@ -139,39 +200,43 @@ where
                                 target_max_depth,
                                 &max_depth,
                                 symmetry,
+                                deadlock_detection,
                             );
+                            if replay {
+                                log::debug!("{}: Replayed seed={}. Shutting down...", t, seed);
+                                return;
+                            }
 
                             // Check whether we have found everything.
                             // All threads should reach this check and have the same result,
                             // leading them all to shut down together.
-                            if discoveries.len() == property_count {
+                            if discoveries.complete_count() == property_count {
                                 log::debug!("{}: Discovery complete. Shutting down...", t,);
                                 return;
                             }
-                            if let Some(target_state_count) = target_state_count {
-                                if target_state_count.get() <= state_count.load(Ordering::Relaxed) {
-                                    log::debug!(
-                                        "{}: Reached target state count. Shutting down...",
-                                        t,
-                                    );
-                                    return;
-                                }
+                            if let Some(limit) = limits.check(state_count.load(Ordering::Relaxed)) {
+                                log::debug!("{}: Reached {} limit. Shutting down...", t, limit);
+                                return;
                             }
 
-                            seed = rng.gen();
+                            seed = rng.next_u64();
                             log::trace!("{}: Generated new thread seed={}", t, seed);
                         }
                     })
                     .expect("Failed to spawn a thread"),
             );
-            thread_seed += 1;
+            thread_seed = thread_seed.wrapping_add(1);
         }
         SimulationChecker {
             model,
             handles,
+            deadlock_detection,
             state_count,
             max_depth,
             discoveries,
+            limits,
+            control,
+            shrink,
         }
     }
 
@@ -182,12 +247,14 @@ where
         seed: u64,
         chooser: &C,
         state_count: &AtomicUsize,
-        discoveries: &DashMap<&'static str, Vec<Fingerprint>>,
+        discoveries: &Discoveries<(u64, Vec<Fingerprint>)>,
         visitor: &Option<Box<dyn CheckerVisitor<M> + Send + Sync>>,
         target_max_depth: Option<NonZeroUsize>,
         global_max_depth: &AtomicUsize,
         symmetry: Option<fn(&M::State) -> M::State>,
+        deadlock_detection: bool,
     ) {
+        // Deadlocks are detected via the steps taken rather than via a property.
         let properties = model.properties();
 
         let mut chooser_state = chooser.new_state(seed);
@@ -270,39 +337,52 @@ where
             }
 
             // Done if discoveries found for all properties.
-            let mut is_awaiting_discoveries = false;
+            let mut is_awaiting_discoveries =
+                deadlock_detection && !discoveries.is_complete(DEADLOCK);
+            let mut step_properties = Vec::new();
             for (i, property) in properties.iter().enumerate() {
-                if discoveries.contains_key(property.name) {
+                if discoveries.is_complete(&property.name) {
                     continue;
                 }
                 match property {
                     Property {
                         expectation: Expectation::Always,
-                        condition: always,
                         ..
                     } => {
-                        if !always(model, &state) {
+                        if !property.holds(model, &state) {
                             // Races other threads, but that's fine.
-                            discoveries.insert(property.name, fingerprint_path.clone());
-                        } else {
-                            is_awaiting_discoveries = true;
+                            discoveries.insert(
+                                &property.name,
+                                *fingerprint_path.last().unwrap(),
+                                (seed, fingerprint_path.clone()),
+                            );
                         }
+                        is_awaiting_discoveries |= !discoveries.is_complete(&property.name);
+                    }
+                    Property {
+                        expectation: Expectation::AlwaysStep,
+                        ..
+                    } => {
+                        // Judged on the steps taken below.
+                        step_properties.push(property);
+                        is_awaiting_discoveries = true;
                     }
                     Property {
                         expectation: Expectation::Sometimes,
-                        condition: sometimes,
                         ..
                     } => {
-                        if sometimes(model, &state) {
+                        if property.holds(model, &state) {
                             // Races other threads, but that's fine.
-                            discoveries.insert(property.name, fingerprint_path.clone());
-                        } else {
-                            is_awaiting_discoveries = true;
+                            discoveries.insert(
+                                &property.name,
+                                *fingerprint_path.last().unwrap(),
+                                (seed, fingerprint_path.clone()),
+                            );
                         }
+                        is_awaiting_discoveries |= !discoveries.is_complete(&property.name);
                     }
                     Property {
                         expectation: Expectation::Eventually,
-                        condition: eventually,
                         ..
                     } => {
                         // The checker early exits after finding discoveries for every property,
@@ -311,10 +391,16 @@ where
                         // discovery regardless of whether the eventually property is now satisfied
                         // (i.e. it might be falsifiable via a different path).
                         is_awaiting_discoveries = true;
-                        if eventually(model, &state) {
+                        if property.holds(model, &state) {
                             ebits.remove(i);
                         }
                     }
+                    Property {
+                        expectation: Expectation::Ltl,
+                        ..
+                    } => {
+                        // Only checked by the liveness checker.
+                    }
                 }
             }
             if !is_awaiting_discoveries {
@@ -324,12 +410,21 @@ where
 
             // generate the possible next actions
             model.actions(&state, &mut actions);
+            let mut copies = copy_actions(model, &state, &step_properties);
 
             // generate the next state, repeatedly choosing an action until we get one or there are
             // no actions left to choose.
             loop {
                 if actions.is_empty() {
-                    // no actions to choose from
+                    // no actions to choose from, so the state is deadlocked unless it may end
+                    if deadlock_detection && !model.valid_end_state(&state) {
+                        // Races other threads, but that's fine.
+                        discoveries.insert(
+                            DEADLOCK,
+                            *fingerprint_path.last().unwrap(),
+                            (seed, fingerprint_path.clone()),
+                        );
+                    }
                     // break from the outer loop so that we still check eventually properties
                     log::trace!("No actions to choose from");
                     break 'outer;
@@ -338,6 +433,7 @@ where
                 // now pick one
                 let index = chooser.choose_action(&mut chooser_state, &state, &actions);
                 let action = actions.swap_remove(index);
+                let copy = (!copies.is_empty()).then(|| copies.swap_remove(index));
 
                 // take the chosen action
                 match model.next_state(&state, action) {
@@ -346,6 +442,22 @@ where
                         log::trace!("No next state");
                     }
                     Some(next_state) => {
+                        if model.within_boundary(&next_state) {
+                            for property in violated_by(
+                                model,
+                                &step_properties,
+                                &state,
+                                copy.as_ref(),
+                                &next_state,
+                            ) {
+                                // Races other threads, but that's fine.
+                                discoveries.insert(
+                                    &property.name,
+                                    *fingerprint_path.last().unwrap(),
+                                    (seed, fingerprint_path.clone()),
+                                );
+                            }
+                        }
                         // now clear the actions for the next round
                         actions.clear();
                         state = next_state;
@@ -358,7 +470,11 @@ where
         for (i, property) in properties.iter().enumerate() {
             if ebits.contains(i) {
                 // Races other threads, but that's fine.
-                discoveries.insert(property.name, fingerprint_path.clone());
+                discoveries.insert(
+                    &property.name,
+                    *fingerprint_path.last().unwrap(),
+                    (seed, fingerprint_path.clone()),
+                );
             }
         }
     }
@@ -387,14 +503,48 @@ where
         self.max_depth.load(Ordering::Relaxed)
     }
 
-    fn discoveries(&self) -> HashMap<&'static str, Path<M::State, M::Action>> {
+    fn control(&self) -> Option<CheckerControl> {
+        Some(self.control.clone())
+    }
+
+    fn limit_reached(&self) -> Option<Limit> {
+        self.limits.reached()
+    }
+
+    fn properties(&self) -> Vec<Property<M>> {
+        checked_properties(&*self.model, self.deadlock_detection)
+    }
+
+    fn discovery_seed(&self, name: &str) -> Option<u64> {
+        self.discoveries.first(name).map(|(seed, _)| seed)
+    }
+
+    fn discoveries(&self) -> HashMap<String, Path<M::State, M::Action>> {
+        self.discoveries_all()
+            .into_iter()
+            .map(|(name, mut paths)| (name, paths.swap_remove(0)))
+            .collect()
+    }
+
+    fn discoveries_all(&self) -> HashMap<String, Vec<Path<M::State, M::Action>>> {
+        let properties = self.properties();
         self.discoveries
-            .iter()
-            .map(|mapref| {
-                (
-                    <&'static str>::clone(mapref.key()),
-                    Path::from_fingerprints(self.model(), VecDeque::from(mapref.value().clone())),
-                )
+            .all()
+            .into_iter()
+            .map(|(name, discoveries)| {
+                let paths = discoveries
+                    .into_iter()
+                    .map(|(_, fingerprints)| {
+                        let path =
+                            Path::from_fingerprints(self.model(), VecDeque::from(fingerprints));
+                        let path = complete_discovery(self.model(), &properties, &name, path);
+                        match self.shrink {
+                            Some(shrink) => shrink(self.model(), &properties, &name, path),
+                            None => path,
+                        }
+                    })
+                    .collect();
+                (name, paths)
             })
             .collect()
     }
@@ -426,4 +576,46 @@ mod test {
             vec![Guess::IncreaseX, Guess::IncreaseY, Guess::IncreaseX],
         );
     }
+
+    #[test]
+    fn stable_rng_is_stable() {
+        // Reference outputs of SplitMix64.
+        let mut rng = StableRng::new(0);
+        assert_eq!(rng.next_u64(), 0xe220_a839_7b1d_cdaf);
+        assert_eq!(rng.next_u64(), 0x6e78_9e6a_a1b9_65f4);
+
+        let mut rng = StableRng::new(1);
+        let mut counts = [0; 3];
+        for _ in 0..300 {
+            counts[rng.below(3)] += 1;
+        }
+        assert!(counts.iter().all(|&count| count > 50), "{:?}", counts);
+    }
+
+    #[test]
+    fn can_replay_discovery_from_seed() {
+        let model = || LinearEquation { a: 2, b: 10, c: 14 };
+        let checker = model()
+            .checker()
+            .threads(4)
+            .spawn_simulation(7, UniformChooser)
+            .join();
+        let seed = checker.discovery_seed("solvable").unwrap();
+
+        let replayed = model().checker().replay(seed, UniformChooser).join();
+        assert_eq!(replayed.discovery_seed("solvable"), Some(seed));
+        assert_eq!(
+            replayed.discovery("solvable"),
+            checker.discovery("solvable")
+        );
+
+        // A trace that does not reach a discovery is checked once.
+        let replayed = LinearEquation { a: 2, b: 4, c: 7 }
+            .checker()
+            .target_max_depth(10)
+            .replay(seed, UniformChooser)
+            .join();
+        assert_eq!(replayed.state_count(), 10);
+        assert_eq!(replayed.discovery_seed("solvable"), None);
+    }
 }
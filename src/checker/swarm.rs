@@ -0,0 +1,400 @@
+//! Private module for selective re-export.
+
+use crate::checker::bitstate::Bitstate;
+use crate::checker::dfs::{DfsChecker, Generated, Job};
+use crate::checker::discoveries::Discoveries;
+use crate::checker::shrink::Shrink;
+use crate::checker::simulation::StableRng;
+use crate::checker::{
+    checked_properties, complete_discovery, visit_key, Checker, EventuallyBits, Expectation, Path,
+};
+use crate::{fingerprint, CheckerBuilder, Chooser, Fingerprint, Model, Property};
+use dashmap::DashSet;
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+/// Runs many independent depth-first searches, as with the "swarm verification" of the SPIN model
+/// checker. Each search is diversified by its own seed, which determines the order in which it
+/// tries actions, its depth bound, and its hash function, so that together the searches cover
+/// more of a large state space than any one of them would. The searches share their discoveries
+/// and counters.
+pub(crate) struct SwarmChecker<M: Model> {
+    // Immutable state.
+    model: Arc<M>,
+    handles: Vec<std::thread::JoinHandle<()>>,
+    deadlock_detection: bool,
+
+    // Mutable state.
+    state_count: Arc<AtomicUsize>,
+    unique_state_count: Arc<AtomicUsize>,
+    max_depth: Arc<AtomicUsize>,
+    omission_probability: Arc<Mutex<Option<f64>>>,
+    discoveries: Arc<Discoveries<(u64, Vec<Fingerprint>)>>,
+    shrink: Option<Shrink<M>>,
+}
+
+/// How a search of the swarm differs from the others.
+struct Instance {
+    seed: u64,
+    target_max_depth: Option<NonZeroUsize>,
+    hash_seed: u64,
+}
+
+impl Instance {
+    /// Derives the parameters of a search from its `seed`. A search's depth bound falls between
+    /// half and all of the `target_max_depth`.
+    fn new(seed: u64, target_max_depth: Option<NonZeroUsize>) -> Self {
+        let mut rng = StableRng::new(seed);
+        let target_max_depth = target_max_depth.map(|depth| {
+            let min = (depth.get() / 2).max(1);
+            let depth = min + rng.below(depth.get() - min + 1);
+            NonZeroUsize::new(depth).unwrap()
+        });
+        Instance {
+            seed,
+            target_max_depth,
+            hash_seed: rng.next_u64(),
+        }
+    }
+}
+
+impl<M> SwarmChecker<M>
+where
+    M: Model + Send + Sync + 'static,
+    M::State: Hash + Send + 'static,
+{
+    pub(crate) fn spawn<C: Chooser<M>>(
+        options: CheckerBuilder<M>,
+        seed: u64,
+        instance_count: usize,
+        chooser: C,
+    ) -> Self {
+        let model = Arc::new(options.model);
+        let deadlock_detection = options.deadlock_detection;
+        let shrink = options.shrink;
+        let bitstate = options.bitstate;
+        let symmetry = options.symmetry;
+        let target_state_count = options.target_state_count;
+        let target_max_depth = options.target_max_depth;
+        let partial_order_reduction = options.partial_order_reduction;
+        let track_eventually_bits = options.track_eventually_bits;
+        let visitor = Arc::new(options.visitor);
+        let property_count = checked_properties(&*model, deadlock_detection).len();
+
+        let state_count = Arc::new(AtomicUsize::new(0));
+        let unique_state_count = Arc::new(AtomicUsize::new(0));
+        let max_depth = Arc::new(AtomicUsize::new(0));
+        let omission_probability = Arc::new(Mutex::new(None));
+        let discoveries = Arc::new(Discoveries::new(options.max_discoveries_per_property));
+        let next_instance = Arc::new(AtomicUsize::new(0));
+        let mut handles = Vec::new();
+
+        for t in 0..options.thread_count.min(instance_count) {
+            let model = Arc::clone(&model);
+            let visitor = Arc::clone(&visitor);
+            let state_count = Arc::clone(&state_count);
+            let unique_state_count = Arc::clone(&unique_state_count);
+            let max_depth = Arc::clone(&max_depth);
+            let omission_probability = Arc::clone(&omission_probability);
+            let discoveries = Arc::clone(&discoveries);
+            let next_instance = Arc::clone(&next_instance);
+            let chooser = chooser.clone();
+            handles.push(
+                std::thread::Builder::new()
+                    .name(format!("checker-{}", t))
+                    .spawn(move || loop {
+                        let is_done = |discoveries: &Discoveries<_>| {
+                            discoveries.complete_count() == property_count
+                                || target_state_count
+                                    .is_some_and(|c| c.get() <= state_count.load(Ordering::Relaxed))
+                        };
+                        let i = next_instance.fetch_add(1, Ordering::Relaxed);
+                        if i >= instance_count || is_done(&discoveries) {
+                            log::debug!("{}: No more instances. Shutting down...", t);
+                            return;
+                        }
+                        let instance = Instance::new(seed.wrapping_add(i as u64), target_max_depth);
+                        log::debug!(
+                            "{}: Instance {} started with seed={}, target_max_depth={:?}.",
+                            t,
+                            i,
+                            instance.seed,
+                            instance.target_max_depth
+                        );
+
+                        let generated = match bitstate {
+                            Some((bits, hash_count)) => Generated::Bitstate(Bitstate::new(
+                                bits,
+                                hash_count,
+                                instance.hash_seed,
+                            )),
+                            None => Generated::Exact(DashSet::default()),
+                        };
+                        let mut pending = Self::init_jobs(
+                            &model,
+                            &state_count,
+                            &generated,
+                            symmetry,
+                            track_eventually_bits,
+                        );
+                        let mut chooser_state = chooser.new_state(instance.seed);
+                        let mut order_actions =
+                            |state: &M::State,
+                             actions: &mut Vec<M::Action>,
+                             copies: &mut Vec<M::Action>| {
+                                let mut ordered = Vec::with_capacity(actions.len());
+                                let mut ordered_copies = Vec::with_capacity(copies.len());
+                                while !actions.is_empty() {
+                                    let index =
+                                        chooser.choose_action(&mut chooser_state, state, actions);
+                                    ordered.push(actions.swap_remove(index));
+                                    if !copies.is_empty() {
+                                        ordered_copies.push(copies.swap_remove(index));
+                                    }
+                                }
+                                *actions = ordered;
+                                *copies = ordered_copies;
+                            };
+                        let mut reported_len = 0;
+                        while !pending.is_empty() && !is_done(&discoveries) {
+                            DfsChecker::<M>::check_block(
+                                &model,
+                                &state_count,
+                                &generated,
+                                &mut pending,
+                                &discoveries,
+                                |fingerprints| (instance.seed, fingerprints),
+                                &visitor,
+                                1500,
+                                instance.target_max_depth,
+                                &max_depth,
+                                partial_order_reduction,
+                                symmetry,
+                                track_eventually_bits,
+                                deadlock_detection,
+                                Some(&mut order_actions),
+                            );
+                            let len = generated.len();
+                            unique_state_count.fetch_add(len - reported_len, Ordering::Relaxed);
+                            reported_len = len;
+                        }
+                        if let Generated::Bitstate(bitstate) = &generated {
+                            let mut omission_probability = omission_probability.lock().unwrap();
+                            let p = bitstate.omission_probability();
+                            if omission_probability.is_none_or(|max| max < p) {
+                                *omission_probability = Some(p);
+                            }
+                        }
+                        log::debug!("{}: Instance {} done. gen={}", t, i, reported_len);
+                    })
+                    .expect("Failed to spawn a thread"),
+            );
+        }
+        SwarmChecker {
+            model,
+            handles,
+            deadlock_detection,
+            state_count,
+            unique_state_count,
+            max_depth,
+            omission_probability,
+            discoveries,
+            shrink,
+        }
+    }
+
+    /// Generates the initial states for a search, recording them as generated.
+    #[allow(clippy::type_complexity)]
+    fn init_jobs(
+        model: &M,
+        state_count: &AtomicUsize,
+        generated: &Generated,
+        symmetry: Option<fn(&M::State) -> M::State>,
+        track_eventually_bits: bool,
+    ) -> VecDeque<Job<M::State>> {
+        let ebits = {
+            let mut ebits = EventuallyBits::new();
+            for (i, p) in model.properties().iter().enumerate() {
+                if let Property {
+                    expectation: Expectation::Eventually,
+                    ..
+                } = p
+                {
+                    ebits.insert(i);
+                }
+            }
+            ebits
+        };
+        let mut pending = VecDeque::new();
+        for s in model.init_states() {
+            if !model.within_boundary(&s) {
+                continue;
+            }
+            state_count.fetch_add(1, Ordering::Relaxed);
+            let key = visit_key(&s, symmetry, track_eventually_bits.then_some(&ebits));
+            if generated.insert(key) {
+                let fp = fingerprint(&s);
+                pending.push_back((s, vec![fp], ebits.clone(), NonZeroUsize::new(1).unwrap()));
+            }
+        }
+        pending
+    }
+}
+
+impl<M> Checker<M> for SwarmChecker<M>
+where
+    M: Model,
+    M::State: Hash,
+{
+    fn model(&self) -> &M {
+        &self.model
+    }
+
+    fn state_count(&self) -> usize {
+        self.state_count.load(Ordering::Relaxed)
+    }
+
+    fn unique_state_count(&self) -> usize {
+        // Searches do not share their generated states, so a state visited by several of them is
+        // counted once per search.
+        self.unique_state_count.load(Ordering::Relaxed)
+    }
+
+    fn max_depth(&self) -> usize {
+        self.max_depth.load(Ordering::Relaxed)
+    }
+
+    fn omission_probability(&self) -> Option<f64> {
+        *self.omission_probability.lock().unwrap()
+    }
+
+    fn properties(&self) -> Vec<Property<M>> {
+        checked_properties(&*self.model, self.deadlock_detection)
+    }
+
+    fn discovery_seed(&self, name: &str) -> Option<u64> {
+        self.discoveries.first(name).map(|(seed, _)| seed)
+    }
+
+    fn discoveries(&self) -> HashMap<String, Path<M::State, M::Action>> {
+        self.discoveries_all()
+            .into_iter()
+            .map(|(name, mut paths)| (name, paths.swap_remove(0)))
+            .collect()
+    }
+
+    fn discoveries_all(&self) -> HashMap<String, Vec<Path<M::State, M::Action>>> {
+        let properties = self.properties();
+        self.discoveries
+            .all()
+            .into_iter()
+            .map(|(name, discoveries)| {
+                let paths = discoveries
+                    .into_iter()
+                    .map(|(_, fingerprints)| {
+                        let path =
+                            Path::from_fingerprints(self.model(), VecDeque::from(fingerprints));
+                        let path = complete_discovery(self.model(), &properties, &name, path);
+                        match self.shrink {
+                            Some(shrink) => shrink(self.model(), &properties, &name, path),
+                            None => path,
+                        }
+                    })
+                    .collect();
+                (name, paths)
+            })
+            .collect()
+    }
+
+    fn handles(&mut self) -> Vec<JoinHandle<()>> {
+        std::mem::take(&mut self.handles)
+    }
+
+    fn is_done(&self) -> bool {
+        self.handles.iter().all(|h| h.is_finished())
+            || self.discoveries.complete_count() == self.properties().len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::linear_equation_solver::*;
+
+    #[test]
+    fn can_complete_by_eliminating_properties() {
+        let checker = LinearEquation { a: 2, b: 10, c: 14 }
+            .checker()
+            .threads(4)
+            .spawn_swarm(0, 8)
+            .join();
+        checker.assert_properties();
+        assert!(checker.discovery("solvable").is_some());
+    }
+
+    #[test]
+    fn derives_instance_seeds_from_base_seed() {
+        let checker = LinearEquation { a: 2, b: 10, c: 14 }
+            .checker()
+            .spawn_swarm(42, 1)
+            .join();
+        assert_eq!(checker.discovery_seed("solvable"), Some(42));
+
+        // Runs with different seeds try actions in different orders.
+        let discoveries: std::collections::HashSet<_> = (0..4)
+            .map(|seed| {
+                LinearEquation { a: 2, b: 10, c: 14 }
+                    .checker()
+                    .spawn_swarm(seed, 1)
+                    .join()
+                    .discovery("solvable")
+                    .unwrap()
+                    .into_states()
+            })
+            .collect();
+        assert!(discoveries.len() > 1);
+    }
+
+    #[test]
+    fn can_diversify_searches() {
+        // Each search stops at a depth bound between 5 and 10, trying actions in its own order.
+        let checker = LinearEquation { a: 2, b: 4, c: 7 }
+            .checker()
+            .target_max_depth(10)
+            .spawn_swarm(0, 4)
+            .join();
+        checker.assert_no_discovery("solvable");
+        assert!(checker.max_depth() <= 10);
+        let single = LinearEquation { a: 2, b: 4, c: 7 }
+            .checker()
+            .target_max_depth(10)
+            .spawn_swarm(0, 1)
+            .join();
+        assert!(checker.unique_state_count() > single.unique_state_count());
+        assert!(checker.state_count() >= checker.unique_state_count());
+
+        // Bitstate hashing bounds the memory of each search.
+        let checker = LinearEquation { a: 2, b: 4, c: 7 }
+            .checker()
+            .bitstate(1 << 10, 3)
+            .spawn_swarm(0, 2)
+            .join();
+        assert!(checker.omission_probability().unwrap() > 0.5);
+    }
+
+    #[test]
+    fn can_stop_at_target_state_count() {
+        let checker = LinearEquation { a: 2, b: 4, c: 7 }
+            .checker()
+            .target_max_depth(50)
+            .target_state_count(1_000)
+            .spawn_swarm(0, 1_000)
+            .join();
+        assert!(checker.state_count() >= 1_000);
+        assert!(checker.state_count() < 10_000);
+    }
+}
@@ -0,0 +1,344 @@
+//! Private module for selective re-export.
+
+use crate::{fingerprint, Fingerprint, Model, Path};
+use std::collections::{HashSet, VecDeque};
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+
+/// An event observed in a log of a running system, for validation by a [`TraceValidator`].
+pub enum Observation<State, Action> {
+    /// An action that the system took, such as an
+    /// [`ActorModelAction::Deliver`](crate::actor::ActorModelAction::Deliver) recorded by the
+    /// recipient of a message.
+    Action(Action),
+    /// A partial observation of the system's state, such as the state of one actor, which the
+    /// state at that point of the behavior must satisfy.
+    #[allow(clippy::type_complexity)]
+    State(Box<dyn Fn(&State) -> bool>),
+}
+
+impl<State, Action> Observation<State, Action> {
+    /// Observes that the state at this point of the behavior satisfies a `predicate`.
+    pub fn state(predicate: impl Fn(&State) -> bool + 'static) -> Self {
+        Observation::State(Box::new(predicate))
+    }
+}
+
+/// Indicates the first observation of a trace that no behavior of the model explains.
+#[derive(Debug)]
+pub struct TraceMismatch<State, Action> {
+    /// The index of the unexplained observation.
+    pub index: usize,
+    /// A behavior of the model that explains the preceding observations.
+    pub path: Path<State, Action>,
+}
+
+impl<State, Action> Display for TraceMismatch<State, Action> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "No behavior of the model explains observation {} of the trace",
+            self.index
+        )
+    }
+}
+
+/// Validates traces of a running system, such as logs from an actor system launched with
+/// [`spawn`](crate::actor::spawn()), against a [`Model`] of that system, in the style of TLA+
+/// trace validation. The model is searched for a behavior that takes the observed actions in
+/// order and passes through states that satisfy the observed state predicates. Every behavior
+/// consistent with the observations so far is tracked, so nondeterminism in the model is
+/// resolved by later observations.
+///
+/// Actions that the system takes without them being logged, such as messages dropped by the
+/// network, can be declared via [`TraceValidator::unobserved`], in which case any number of them
+/// may occur between observations.
+///
+/// # Example
+///
+/// ```
+/// use stateright::*;
+///
+/// /// A counter that can be incremented or reset.
+/// struct Counter;
+/// impl Model for Counter {
+///     type State = u8;
+///     type Action = &'static str;
+///     fn init_states(&self) -> Vec<Self::State> { vec![0] }
+///     fn actions(&self, _: &Self::State, actions: &mut Vec<Self::Action>) {
+///         actions.push("increment");
+///         actions.push("reset");
+///     }
+///     fn next_state(&self, s: &Self::State, a: Self::Action) -> Option<Self::State> {
+///         match a { "increment" => Some(s + 1), _ => Some(0) }
+///     }
+///     fn within_boundary(&self, s: &Self::State) -> bool { *s < 10 }
+/// }
+///
+/// let validator = TraceValidator::new(&Counter);
+/// let path = validator
+///     .validate(vec![Observation::Action("increment"), Observation::state(|s| *s == 1)])
+///     .unwrap();
+/// assert_eq!(path.into_actions(), vec!["increment"]);
+///
+/// let mismatch = validator
+///     .validate(vec![Observation::Action("reset"), Observation::state(|s| *s == 1)])
+///     .unwrap_err();
+/// assert_eq!(mismatch.index, 1);
+/// ```
+pub struct TraceValidator<'a, M: Model> {
+    model: &'a M,
+    #[allow(clippy::type_complexity)]
+    unobserved: Option<Box<dyn Fn(&M::Action) -> bool + 'a>>,
+}
+
+impl<'a, M> TraceValidator<'a, M>
+where
+    M: Model,
+    M::Action: PartialEq,
+    M::State: Hash,
+{
+    /// Instantiates a validator for traces of the system described by a `model`.
+    pub fn new(model: &'a M) -> Self {
+        Self {
+            model,
+            unobserved: None,
+        }
+    }
+
+    /// Indicates actions that may occur without being observed.
+    pub fn unobserved(self, is_unobserved: impl Fn(&M::Action) -> bool + 'a) -> Self {
+        Self {
+            unobserved: Some(Box::new(is_unobserved)),
+            ..self
+        }
+    }
+
+    /// Searches for a behavior of the model that explains the `observations`, returning the
+    /// behavior if found, or otherwise the first observation that cannot be explained. Panics if
+    /// the model has no initial states within its boundary.
+    #[allow(clippy::type_complexity)]
+    pub fn validate(
+        &self,
+        observations: impl IntoIterator<Item = Observation<M::State, M::Action>>,
+    ) -> Result<Path<M::State, M::Action>, TraceMismatch<M::State, M::Action>> {
+        let model = self.model;
+        let mut behaviors = Behaviors::default();
+        for state in model.init_states() {
+            if model.within_boundary(&state) {
+                let fp = fingerprint(&state);
+                behaviors.insert(state, vec![fp]);
+            }
+        }
+        assert!(
+            !behaviors.entries.is_empty(),
+            "The model has no initial states within its boundary"
+        );
+
+        for (index, observation) in observations.into_iter().enumerate() {
+            behaviors = self.with_unobserved(behaviors);
+            let explained = match &observation {
+                Observation::Action(observed) => {
+                    let mut explained = Behaviors::default();
+                    for (state, fingerprints) in &behaviors.entries {
+                        for (action, next_state) in model.next_steps(state) {
+                            if action == *observed && model.within_boundary(&next_state) {
+                                let mut fingerprints = fingerprints.clone();
+                                fingerprints.push(fingerprint(&next_state));
+                                explained.insert(next_state, fingerprints);
+                            }
+                        }
+                    }
+                    explained
+                }
+                Observation::State(predicate) => {
+                    if !behaviors.entries.iter().any(|(state, _)| predicate(state)) {
+                        Behaviors::default()
+                    } else {
+                        behaviors.retain(|state| predicate(state));
+                        std::mem::take(&mut behaviors)
+                    }
+                }
+            };
+            if explained.entries.is_empty() {
+                return Err(TraceMismatch {
+                    index,
+                    path: behaviors.first_path(model),
+                });
+            }
+            behaviors = explained;
+        }
+        Ok(behaviors.first_path(model))
+    }
+
+    /// Extends the behaviors with any number of unobserved actions.
+    fn with_unobserved(&self, mut behaviors: Behaviors<M::State>) -> Behaviors<M::State> {
+        let Some(is_unobserved) = &self.unobserved else {
+            return behaviors;
+        };
+        let mut pending: VecDeque<_> = (0..behaviors.entries.len()).collect();
+        while let Some(i) = pending.pop_front() {
+            let (state, fingerprints) = &behaviors.entries[i];
+            let mut next = Vec::new();
+            for (action, next_state) in self.model.next_steps(state) {
+                if is_unobserved(&action) && self.model.within_boundary(&next_state) {
+                    let mut fingerprints = fingerprints.clone();
+                    fingerprints.push(fingerprint(&next_state));
+                    next.push((next_state, fingerprints));
+                }
+            }
+            for (next_state, fingerprints) in next {
+                if behaviors.insert(next_state, fingerprints) {
+                    pending.push_back(behaviors.entries.len() - 1);
+                }
+            }
+        }
+        behaviors
+    }
+}
+
+/// The states reached by the behaviors that explain a trace so far, each along with the
+/// fingerprints of one such behavior.
+struct Behaviors<State> {
+    entries: Vec<(State, Vec<Fingerprint>)>,
+    visited: HashSet<Fingerprint>,
+}
+
+impl<State> Default for Behaviors<State> {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+            visited: HashSet::new(),
+        }
+    }
+}
+
+impl<State> Behaviors<State> {
+    /// Records a behavior unless another one already reached its last state, returning whether
+    /// it was recorded.
+    fn insert(&mut self, state: State, fingerprints: Vec<Fingerprint>) -> bool {
+        if self.visited.insert(*fingerprints.last().unwrap()) {
+            self.entries.push((state, fingerprints));
+            true
+        } else {
+            false
+        }
+    }
+
+    fn retain(&mut self, mut predicate: impl FnMut(&State) -> bool) {
+        self.entries.retain(|(state, _)| predicate(state));
+        self.visited = self
+            .entries
+            .iter()
+            .map(|(_, fingerprints)| *fingerprints.last().unwrap())
+            .collect();
+    }
+
+    fn first_path<M>(&self, model: &M) -> Path<State, M::Action>
+    where
+        M: Model<State = State>,
+        State: Hash,
+    {
+        let fingerprints = self.entries[0].1.clone();
+        Path::from_fingerprints(model, fingerprints.into())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::actor::actor_test_util::ping_pong::{PingPongCfg, PingPongMsg::*};
+    use crate::actor::{ActorModelAction::*, ActorModelState, Envelope, Id, LossyNetwork};
+
+    #[test]
+    fn finds_behavior_explaining_actor_trace() {
+        let model = PingPongCfg {
+            maintains_history: false,
+            max_nat: 2,
+        }
+        .into_model();
+        let (a, b) = (Id::from(0), Id::from(1));
+        let path = TraceValidator::new(&model)
+            .validate(vec![
+                Observation::Action(Deliver {
+                    src: a,
+                    dst: b,
+                    msg: Ping(0),
+                }),
+                Observation::state(|s: &ActorModelState<_, _>| *s.actor_states[1] == 1),
+                Observation::Action(Deliver {
+                    src: b,
+                    dst: a,
+                    msg: Pong(0),
+                }),
+            ])
+            .unwrap();
+        assert_eq!(path.last_state().actor_states, vec![1.into(), 1.into()]);
+    }
+
+    #[test]
+    fn reports_first_unexplained_observation() {
+        let model = PingPongCfg {
+            maintains_history: false,
+            max_nat: 2,
+        }
+        .into_model();
+        let (a, b) = (Id::from(0), Id::from(1));
+        let mismatch = TraceValidator::new(&model)
+            .validate(vec![
+                Observation::Action(Deliver {
+                    src: a,
+                    dst: b,
+                    msg: Ping(0),
+                }),
+                Observation::Action(Deliver {
+                    src: b,
+                    dst: a,
+                    msg: Pong(1),
+                }),
+            ])
+            .unwrap_err();
+        assert_eq!(mismatch.index, 1);
+        assert_eq!(
+            mismatch.to_string(),
+            "No behavior of the model explains observation 1 of the trace"
+        );
+        assert_eq!(mismatch.path.into_actions().len(), 1);
+    }
+
+    #[test]
+    fn allows_unobserved_actions() {
+        let model = PingPongCfg {
+            maintains_history: false,
+            max_nat: 2,
+        }
+        .into_model()
+        .lossy_network(LossyNetwork::Yes);
+        let (a, b) = (Id::from(0), Id::from(1));
+        // The ping is dropped, after which no actor can do anything.
+        let observations = || {
+            vec![Observation::state(|s: &ActorModelState<_, _>| {
+                s.network.len() == 0
+            })]
+        };
+        assert_eq!(
+            TraceValidator::new(&model)
+                .validate(observations())
+                .unwrap_err()
+                .index,
+            0
+        );
+        let path = TraceValidator::new(&model)
+            .unobserved(|action| matches!(action, Drop(_)))
+            .validate(observations())
+            .unwrap();
+        assert_eq!(
+            path.into_actions(),
+            vec![Drop(Envelope {
+                src: a,
+                dst: b,
+                msg: Ping(0)
+            })]
+        );
+    }
+}
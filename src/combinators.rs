@@ -0,0 +1,750 @@
+//! Combinators that build a [`Model`] from other models, such as [`restrict`] for narrowing the
+//! state space of a model or [`interleaved_product`] for composing two models that evolve
+//! independently. The resulting models can be checked and explored like any other.
+//!
+//! The properties of the underlying models, including [`Property::ltl`] properties, carry over to
+//! the combined model. The products prefix the name of each property with `left.` or `right.` to
+//! indicate the model it came from, while the other combinators leave the names unchanged.
+//!
+//! # Example
+//!
+//! ```rust
+//! use stateright::*;
+//! use stateright::combinators::*;
+//!
+//! /// A counter that can only be incremented.
+//! struct Counter;
+//! impl Model for Counter {
+//!     type State = u8;
+//!     type Action = ();
+//!     fn init_states(&self) -> Vec<Self::State> { vec![0] }
+//!     fn actions(&self, _: &Self::State, actions: &mut Vec<Self::Action>) { actions.push(()); }
+//!     fn next_state(&self, s: &Self::State, _: Self::Action) -> Option<Self::State> {
+//!         s.checked_add(1)
+//!     }
+//! }
+//!
+//! let two_counters = interleaved_product(Counter, Counter);
+//! let model = with_properties(
+//!     restrict(two_counters, |&(a, b)| a <= 3 && b <= 3),
+//!     vec![Property::sometimes("both reach 3", |_, s| *s == (3, 3))],
+//! );
+//! let checker = model.checker().spawn_bfs().join();
+//! checker.assert_properties();
+//! assert_eq!(checker.unique_state_count(), 16);
+//! ```
+
+use crate::{Fairness, Model, Path, Property};
+use std::fmt::Debug;
+use std::sync::Arc;
+
+/// Restricts a model to the states satisfying a `predicate`, in addition to the model's own
+/// [`Model::within_boundary`].
+pub fn restrict<M: Model>(
+    model: M,
+    predicate: impl Fn(&M::State) -> bool + Send + Sync + 'static,
+) -> Restrict<M> {
+    Restrict {
+        model,
+        predicate: Box::new(predicate),
+    }
+}
+
+/// Attaches additional `properties` to a model without editing its [`Model::properties`].
+pub fn with_properties<M: Model>(model: M, properties: Vec<Property<M>>) -> WithProperties<M> {
+    WithProperties { model, properties }
+}
+
+/// Relabels the actions of a model, given a function converting each action `into` the new type
+/// and a function converting a relabeled action back `from` the new type.
+pub fn map_actions<M: Model, A>(
+    model: M,
+    into: impl Fn(M::Action) -> A + Send + Sync + 'static,
+    from: impl Fn(&A) -> M::Action + Send + Sync + 'static,
+) -> MapActions<M, A> {
+    MapActions {
+        model,
+        into: Box::new(into),
+        from: Box::new(from),
+    }
+}
+
+/// Composes two models that take every step together. See [`SynchronousProduct`].
+pub fn synchronous_product<L: Model, R: Model>(left: L, right: R) -> SynchronousProduct<L, R> {
+    SynchronousProduct { left, right }
+}
+
+/// Composes two models that take steps independently of one another. See
+/// [`InterleavedProduct`].
+pub fn interleaved_product<L: Model, R: Model>(left: L, right: R) -> InterleavedProduct<L, R> {
+    InterleavedProduct { left, right }
+}
+
+/// A model restricted to a subset of its states. See [`restrict`].
+pub struct Restrict<M: Model> {
+    model: M,
+    #[allow(clippy::type_complexity)]
+    predicate: Box<dyn Fn(&M::State) -> bool + Send + Sync>,
+}
+
+impl<M: Model> Restrict<M> {
+    /// Returns the restricted model.
+    pub fn model(&self) -> &M {
+        &self.model
+    }
+}
+
+impl<M: Model + 'static> Model for Restrict<M> {
+    type State = M::State;
+    type Action = M::Action;
+
+    fn init_states(&self) -> Vec<Self::State> {
+        self.model.init_states()
+    }
+
+    fn actions(&self, state: &Self::State, actions: &mut Vec<Self::Action>) {
+        self.model.actions(state, actions)
+    }
+
+    fn next_state(&self, last_state: &Self::State, action: Self::Action) -> Option<Self::State> {
+        self.model.next_state(last_state, action)
+    }
+
+    fn format_action(&self, action: &Self::Action) -> String
+    where
+        Self::Action: Debug,
+    {
+        self.model.format_action(action)
+    }
+
+    fn format_step(&self, last_state: &Self::State, action: Self::Action) -> Option<String>
+    where
+        Self::State: Debug,
+    {
+        self.model.format_step(last_state, action)
+    }
+
+    fn as_svg(&self, path: Path<Self::State, Self::Action>) -> Option<String> {
+        self.model.as_svg(path)
+    }
+
+    fn properties(&self) -> Vec<Property<Self>> {
+        self.model
+            .properties()
+            .into_iter()
+            .map(|p| {
+                lift(
+                    p,
+                    |r: &Self| &r.model,
+                    |s| s,
+                    |r, c, s, a, t| c(&r.model, s, a, t),
+                )
+            })
+            .collect()
+    }
+
+    fn within_boundary(&self, state: &Self::State) -> bool {
+        self.model.within_boundary(state) && (self.predicate)(state)
+    }
+
+    fn valid_end_state(&self, state: &Self::State) -> bool {
+        self.model.valid_end_state(state)
+    }
+
+    fn fairness(&self, action: &Self::Action) -> Option<(Fairness, u64)> {
+        self.model.fairness(action)
+    }
+
+    // `independent` is not delegated, as a step that the restriction excludes can disable an
+    // action that is independent in the unrestricted model.
+}
+
+/// A model with additional properties. See [`with_properties`].
+pub struct WithProperties<M: Model> {
+    model: M,
+    properties: Vec<Property<M>>,
+}
+
+impl<M: Model> WithProperties<M> {
+    /// Returns the model to which the properties are attached.
+    pub fn model(&self) -> &M {
+        &self.model
+    }
+}
+
+impl<M: Model + 'static> Model for WithProperties<M> {
+    type State = M::State;
+    type Action = M::Action;
+
+    fn init_states(&self) -> Vec<Self::State> {
+        self.model.init_states()
+    }
+
+    fn actions(&self, state: &Self::State, actions: &mut Vec<Self::Action>) {
+        self.model.actions(state, actions)
+    }
+
+    fn next_state(&self, last_state: &Self::State, action: Self::Action) -> Option<Self::State> {
+        self.model.next_state(last_state, action)
+    }
+
+    fn format_action(&self, action: &Self::Action) -> String
+    where
+        Self::Action: Debug,
+    {
+        self.model.format_action(action)
+    }
+
+    fn format_step(&self, last_state: &Self::State, action: Self::Action) -> Option<String>
+    where
+        Self::State: Debug,
+    {
+        self.model.format_step(last_state, action)
+    }
+
+    fn as_svg(&self, path: Path<Self::State, Self::Action>) -> Option<String> {
+        self.model.as_svg(path)
+    }
+
+    fn properties(&self) -> Vec<Property<Self>> {
+        self.model
+            .properties()
+            .into_iter()
+            .chain(self.properties.iter().cloned())
+            .map(|p| {
+                lift(
+                    p,
+                    |w: &Self| &w.model,
+                    |s| s,
+                    |w, c, s, a, t| c(&w.model, s, a, t),
+                )
+            })
+            .collect()
+    }
+
+    fn within_boundary(&self, state: &Self::State) -> bool {
+        self.model.within_boundary(state)
+    }
+
+    fn valid_end_state(&self, state: &Self::State) -> bool {
+        self.model.valid_end_state(state)
+    }
+
+    fn fairness(&self, action: &Self::Action) -> Option<(Fairness, u64)> {
+        self.model.fairness(action)
+    }
+
+    fn independent(&self, state: &Self::State, a1: &Self::Action, a2: &Self::Action) -> bool {
+        self.model.independent(state, a1, a2)
+    }
+}
+
+/// A model with relabeled actions. See [`map_actions`].
+pub struct MapActions<M: Model, A> {
+    model: M,
+    #[allow(clippy::type_complexity)]
+    into: Box<dyn Fn(M::Action) -> A + Send + Sync>,
+    #[allow(clippy::type_complexity)]
+    from: Box<dyn Fn(&A) -> M::Action + Send + Sync>,
+}
+
+impl<M: Model, A> MapActions<M, A> {
+    /// Returns the model whose actions are relabeled.
+    pub fn model(&self) -> &M {
+        &self.model
+    }
+}
+
+impl<M: Model + 'static, A: 'static> Model for MapActions<M, A> {
+    type State = M::State;
+    type Action = A;
+
+    fn init_states(&self) -> Vec<Self::State> {
+        self.model.init_states()
+    }
+
+    fn actions(&self, state: &Self::State, actions: &mut Vec<Self::Action>) {
+        let mut original = Vec::new();
+        self.model.actions(state, &mut original);
+        actions.extend(original.into_iter().map(&self.into));
+    }
+
+    fn next_state(&self, last_state: &Self::State, action: Self::Action) -> Option<Self::State> {
+        self.model.next_state(last_state, (self.from)(&action))
+    }
+
+    fn format_step(&self, last_state: &Self::State, action: Self::Action) -> Option<String>
+    where
+        Self::State: Debug,
+    {
+        self.model.format_step(last_state, (self.from)(&action))
+    }
+
+    fn as_svg(&self, path: Path<Self::State, Self::Action>) -> Option<String> {
+        self.model
+            .as_svg(path.map_actions(|action| (self.from)(&action)))
+    }
+
+    fn properties(&self) -> Vec<Property<Self>> {
+        self.model
+            .properties()
+            .into_iter()
+            .map(|p| {
+                lift(
+                    p,
+                    |m: &Self| &m.model,
+                    |s| s,
+                    |m, c, s, a, t| c(&m.model, s, &(m.from)(a), t),
+                )
+            })
+            .collect()
+    }
+
+    fn within_boundary(&self, state: &Self::State) -> bool {
+        self.model.within_boundary(state)
+    }
+
+    fn valid_end_state(&self, state: &Self::State) -> bool {
+        self.model.valid_end_state(state)
+    }
+
+    fn fairness(&self, action: &Self::Action) -> Option<(Fairness, u64)> {
+        self.model.fairness(&(self.from)(action))
+    }
+
+    fn independent(&self, state: &Self::State, a1: &Self::Action, a2: &Self::Action) -> bool {
+        self.model
+            .independent(state, &(self.from)(a1), &(self.from)(a2))
+    }
+}
+
+/// Two models that take every step together, such as a system and an observer that follows it.
+/// A state of the product pairs the states of the two models, and an action pairs an action of
+/// each, where a step is only possible if both models can take their half of it. The product
+/// is within its boundary when both models are.
+pub struct SynchronousProduct<L, R> {
+    left: L,
+    right: R,
+}
+
+impl<L, R> SynchronousProduct<L, R> {
+    /// Returns the left model.
+    pub fn left(&self) -> &L {
+        &self.left
+    }
+
+    /// Returns the right model.
+    pub fn right(&self) -> &R {
+        &self.right
+    }
+}
+
+impl<L, R> Model for SynchronousProduct<L, R>
+where
+    L: Model + 'static,
+    L::State: Clone,
+    L::Action: Clone,
+    R: Model + 'static,
+    R::State: Clone,
+    R::Action: Clone,
+{
+    type State = (L::State, R::State);
+    type Action = (L::Action, R::Action);
+
+    fn init_states(&self) -> Vec<Self::State> {
+        let right_states = self.right.init_states();
+        self.left
+            .init_states()
+            .into_iter()
+            .flat_map(|l| right_states.iter().map(move |r| (l.clone(), r.clone())))
+            .collect()
+    }
+
+    fn actions(&self, state: &Self::State, actions: &mut Vec<Self::Action>) {
+        let (mut left_actions, mut right_actions) = (Vec::new(), Vec::new());
+        self.left.actions(&state.0, &mut left_actions);
+        self.right.actions(&state.1, &mut right_actions);
+        for l in left_actions {
+            actions.extend(right_actions.iter().map(|r| (l.clone(), r.clone())));
+        }
+    }
+
+    fn next_state(&self, last_state: &Self::State, action: Self::Action) -> Option<Self::State> {
+        Some((
+            self.left.next_state(&last_state.0, action.0)?,
+            self.right.next_state(&last_state.1, action.1)?,
+        ))
+    }
+
+    fn properties(&self) -> Vec<Property<Self>> {
+        let left = self.left.properties().into_iter().map(|p| {
+            lift(
+                prefix("left.", p),
+                |p: &Self| &p.left,
+                |s| &s.0,
+                |p, c, s, a, t| c(&p.left, &s.0, &a.0, &t.0),
+            )
+        });
+        let right = self.right.properties().into_iter().map(|p| {
+            lift(
+                prefix("right.", p),
+                |p: &Self| &p.right,
+                |s| &s.1,
+                |p, c, s, a, t| c(&p.right, &s.1, &a.1, &t.1),
+            )
+        });
+        left.chain(right).collect()
+    }
+
+    fn within_boundary(&self, state: &Self::State) -> bool {
+        self.left.within_boundary(&state.0) && self.right.within_boundary(&state.1)
+    }
+
+    fn valid_end_state(&self, state: &Self::State) -> bool {
+        self.left.valid_end_state(&state.0) && self.right.valid_end_state(&state.1)
+    }
+}
+
+/// Two models that take steps independently of one another, such as two processes that share
+/// nothing. A state of the product pairs the states of the two models, and each step is a step
+/// of one of the models, which leaves the state of the other unchanged. The product is within
+/// its boundary when both models are.
+pub struct InterleavedProduct<L, R> {
+    left: L,
+    right: R,
+}
+
+impl<L, R> InterleavedProduct<L, R> {
+    /// Returns the left model.
+    pub fn left(&self) -> &L {
+        &self.left
+    }
+
+    /// Returns the right model.
+    pub fn right(&self) -> &R {
+        &self.right
+    }
+}
+
+/// An action of an [`InterleavedProduct`], which is taken by one of its models.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum InterleavedAction<L, R> {
+    /// An action of the left model.
+    Left(L),
+    /// An action of the right model.
+    Right(R),
+}
+
+impl<L, R> Model for InterleavedProduct<L, R>
+where
+    L: Model + 'static,
+    L::State: Clone,
+    R: Model + 'static,
+    R::State: Clone,
+{
+    type State = (L::State, R::State);
+    type Action = InterleavedAction<L::Action, R::Action>;
+
+    fn init_states(&self) -> Vec<Self::State> {
+        let right_states = self.right.init_states();
+        self.left
+            .init_states()
+            .into_iter()
+            .flat_map(|l| right_states.iter().map(move |r| (l.clone(), r.clone())))
+            .collect()
+    }
+
+    fn actions(&self, state: &Self::State, actions: &mut Vec<Self::Action>) {
+        let (mut left_actions, mut right_actions) = (Vec::new(), Vec::new());
+        self.left.actions(&state.0, &mut left_actions);
+        self.right.actions(&state.1, &mut right_actions);
+        actions.extend(left_actions.into_iter().map(InterleavedAction::Left));
+        actions.extend(right_actions.into_iter().map(InterleavedAction::Right));
+    }
+
+    fn next_state(&self, last_state: &Self::State, action: Self::Action) -> Option<Self::State> {
+        match action {
+            InterleavedAction::Left(a) => Some((
+                self.left.next_state(&last_state.0, a)?,
+                last_state.1.clone(),
+            )),
+            InterleavedAction::Right(a) => Some((
+                last_state.0.clone(),
+                self.right.next_state(&last_state.1, a)?,
+            )),
+        }
+    }
+
+    fn properties(&self) -> Vec<Property<Self>> {
+        // A step of one model is a stutter of the other, which satisfies its step conditions.
+        let left = self.left.properties().into_iter().map(|p| {
+            lift(
+                prefix("left.", p),
+                |p: &Self| &p.left,
+                |s| &s.0,
+                |p, c, s, a, t| match a {
+                    InterleavedAction::Left(a) => c(&p.left, &s.0, a, &t.0),
+                    InterleavedAction::Right(_) => true,
+                },
+            )
+        });
+        let right = self.right.properties().into_iter().map(|p| {
+            lift(
+                prefix("right.", p),
+                |p: &Self| &p.right,
+                |s| &s.1,
+                |p, c, s, a, t| match a {
+                    InterleavedAction::Left(_) => true,
+                    InterleavedAction::Right(a) => c(&p.right, &s.1, a, &t.1),
+                },
+            )
+        });
+        left.chain(right).collect()
+    }
+
+    fn within_boundary(&self, state: &Self::State) -> bool {
+        self.left.within_boundary(&state.0) && self.right.within_boundary(&state.1)
+    }
+
+    fn valid_end_state(&self, state: &Self::State) -> bool {
+        self.left.valid_end_state(&state.0) && self.right.valid_end_state(&state.1)
+    }
+
+    fn fairness(&self, action: &Self::Action) -> Option<(Fairness, u64)> {
+        // Identifiers are tagged with the model so that those of the two models cannot collide.
+        match action {
+            InterleavedAction::Left(a) => self
+                .left
+                .fairness(a)
+                .map(|(fairness, id)| (fairness, crate::fingerprint(&(0, id)).get())),
+            InterleavedAction::Right(a) => self
+                .right
+                .fairness(a)
+                .map(|(fairness, id)| (fairness, crate::fingerprint(&(1, id)).get())),
+        }
+    }
+
+    fn independent(&self, state: &Self::State, a1: &Self::Action, a2: &Self::Action) -> bool {
+        // Actions of different models commute, but a property may relate the two models, so only
+        // actions of the same model are judged.
+        match (a1, a2) {
+            (InterleavedAction::Left(a1), InterleavedAction::Left(a2)) => {
+                self.left.independent(&state.0, a1, a2)
+            }
+            (InterleavedAction::Right(a1), InterleavedAction::Right(a2)) => {
+                self.right.independent(&state.1, a1, a2)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// The condition of an [`Expectation::AlwaysStep`] property.
+type StepCondition<M> = dyn Fn(&M, &<M as Model>::State, &<M as Model>::Action, &<M as Model>::State) -> bool
+    + Send
+    + Sync;
+
+/// Converts a property of model `M` to a property of model `W` that is built from it, given how
+/// to find `M` and its state within `W` and its state, and how to judge a step of `W` with a step
+/// condition of `M`.
+#[allow(clippy::type_complexity)]
+fn lift<M: Model + 'static, W: Model + 'static>(
+    property: Property<M>,
+    model: fn(&W) -> &M,
+    state: fn(&W::State) -> &M::State,
+    step: fn(&W, &StepCondition<M>, &W::State, &W::Action, &W::State) -> bool,
+) -> Property<W> {
+    Property {
+        expectation: property.expectation,
+        name: property.name,
+        condition: property.condition.map(|c| {
+            Arc::new(move |w: &W, s: &W::State| c(model(w), state(s)))
+                as Arc<dyn Fn(&W, &W::State) -> bool + Send + Sync>
+        }),
+        formula: property.formula.map(|formula| {
+            formula.map_conditions(&|c| {
+                Arc::new(move |w: &W, s: &W::State| c(model(w), state(s)))
+                    as Arc<dyn Fn(&W, &W::State) -> bool + Send + Sync>
+            })
+        }),
+        step_condition: property.step_condition.map(|c| {
+            Arc::new(move |w: &W, s: &W::State, a: &W::Action, t: &W::State| step(w, &*c, s, a, t))
+                as Arc<StepCondition<W>>
+        }),
+    }
+}
+
+/// Prefixes the name of a property of one of the models of a product, so that properties of the
+/// two models cannot be confused.
+fn prefix<M: Model>(prefix: &str, property: Property<M>) -> Property<M> {
+    Property {
+        name: format!("{}{}", prefix, property.name),
+        ..property
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::binary_clock::{BinaryClock, BinaryClockAction};
+    use crate::test_util::counter::Counter;
+    use crate::test_util::linear_equation_solver::{Guess, LinearEquation};
+    use crate::{Checker, Expectation, Ltl};
+
+    #[test]
+    fn restrict_bounds_state_space_and_keeps_properties() {
+        // `2x + 4y` is always even, so every state within the boundary is visited.
+        let model = restrict(LinearEquation { a: 2, b: 4, c: 7 }, |&(x, y)| {
+            x < 5 && y < 5
+        });
+        let checker = model.checker().spawn_bfs().join();
+        assert_eq!(checker.unique_state_count(), 25);
+        checker.assert_no_discovery("solvable");
+
+        let model = restrict(LinearEquation { a: 2, b: 10, c: 14 }, |&(x, y)| {
+            x < 5 && y < 5
+        });
+        let checker = model.checker().spawn_bfs().join();
+        checker.assert_discovery(
+            "solvable",
+            vec![Guess::IncreaseX, Guess::IncreaseX, Guess::IncreaseY],
+        );
+
+        let model = restrict(LinearEquation { a: 2, b: 10, c: 14 }, |&(x, y)| {
+            x < 2 && y < 5
+        });
+        let checker = model.checker().spawn_dfs().join();
+        assert_eq!(checker.unique_state_count(), 10);
+        checker.assert_no_discovery("solvable");
+    }
+
+    #[test]
+    fn with_properties_adds_to_existing_properties() {
+        let model = with_properties(
+            restrict(LinearEquation { a: 2, b: 10, c: 14 }, |&(x, y)| {
+                x < 3 && y < 3
+            }),
+            vec![
+                Property::always("x at most 1", |_, s: &(u8, u8)| s.0 <= 1),
+                Property::always_step("y only increases", |_, s: &(u8, u8), _, t: &(u8, u8)| {
+                    s.1 <= t.1
+                }),
+            ],
+        );
+        let names: Vec<_> = model.properties().into_iter().map(|p| p.name).collect();
+        assert_eq!(names, vec!["solvable", "x at most 1", "y only increases"]);
+        let checker = model.checker().spawn_bfs().join();
+        checker.assert_discovery(
+            "solvable",
+            vec![Guess::IncreaseX, Guess::IncreaseX, Guess::IncreaseY],
+        );
+        checker.assert_discovery("x at most 1", vec![Guess::IncreaseX, Guess::IncreaseX]);
+        checker.assert_no_discovery("y only increases");
+    }
+
+    #[test]
+    fn map_actions_relabels_actions() {
+        let model = map_actions(
+            LinearEquation { a: 2, b: 10, c: 14 },
+            |guess| match guess {
+                Guess::IncreaseX => 'x',
+                Guess::IncreaseY => 'y',
+            },
+            |&label| match label {
+                'x' => Guess::IncreaseX,
+                _ => Guess::IncreaseY,
+            },
+        );
+        let model = with_properties(
+            restrict(model, |&(x, y)| x < 5 && y < 5),
+            vec![Property::always_step(
+                "x only after y",
+                |_, s: &(u8, u8), &a, _| a != 'x' || s.1 > 0,
+            )],
+        );
+        let checker = model.checker().spawn_bfs().join();
+        checker.assert_discovery("solvable", vec!['x', 'x', 'y']);
+        checker.assert_discovery("x only after y", vec!['x']);
+    }
+
+    #[test]
+    fn synchronous_product_steps_together() {
+        let model = synchronous_product(BinaryClock, BinaryClock);
+        assert_eq!(model.init_states(), vec![(0, 0), (0, 1), (1, 0), (1, 1)]);
+        assert_eq!(model.next_states(&(0, 1)), vec![(1, 0)],);
+        let checker = model.checker().spawn_bfs().join();
+        assert_eq!(checker.unique_state_count(), 4);
+        checker.assert_properties();
+        let names: Vec<_> = checker
+            .model()
+            .properties()
+            .into_iter()
+            .map(|p| p.name)
+            .collect();
+        assert_eq!(names, vec!["left.in [0, 1]", "right.in [0, 1]"]);
+    }
+
+    #[test]
+    fn interleaved_product_steps_independently() {
+        let model = interleaved_product(
+            restrict(LinearEquation { a: 1, b: 1, c: 3 }, |&(x, y)| {
+                x < 2 && y < 2
+            }),
+            BinaryClock,
+        );
+        assert_eq!(
+            model.next_steps(&((0, 1), 0)),
+            vec![
+                (InterleavedAction::Left(Guess::IncreaseX), ((1, 1), 0)),
+                (InterleavedAction::Left(Guess::IncreaseY), ((0, 2), 0)),
+                (
+                    InterleavedAction::Right(BinaryClockAction::GoHigh),
+                    ((0, 1), 1)
+                ),
+            ]
+        );
+        let checker = model.checker().spawn_dfs().join();
+        assert_eq!(checker.unique_state_count(), 4 * 2);
+        assert_eq!(
+            checker
+                .model()
+                .properties()
+                .into_iter()
+                .map(|p| p.expectation)
+                .collect::<Vec<_>>(),
+            vec![Expectation::Sometimes, Expectation::Always]
+        );
+        checker.assert_no_discovery("left.solvable");
+        checker.assert_no_discovery("right.in [0, 1]");
+    }
+
+    #[test]
+    fn products_lift_ltl_properties() {
+        // Counts to 2, which it must eventually reach.
+        let done = Ltl::predicate("done", |_, s: &u8| *s == 2);
+        let counter = Counter::new(2)
+            .with_property(Property::ltl("finishes", Ltl::finally(done.clone())))
+            .with_property(Property::ltl(
+                "stays done",
+                Ltl::globally(done.clone().implies(Ltl::next(done))),
+            ));
+
+        // The counter finishes when stepping together with the clock...
+        let checker = synchronous_product(counter.clone(), BinaryClock)
+            .checker()
+            .spawn_liveness()
+            .join();
+        checker.assert_no_discovery("left.finishes");
+        checker.assert_no_discovery("left.stays done");
+
+        // ...but not if the clock ticks forever instead.
+        let checker = interleaved_product(BinaryClock, counter)
+            .checker()
+            .spawn_liveness()
+            .join();
+        let path = checker.discovery("right.finishes").unwrap();
+        assert!(path.loop_start().is_some());
+        assert!(path.into_states().iter().all(|&(_, counter)| counter < 2));
+        checker.assert_no_discovery("right.stays done");
+    }
+}
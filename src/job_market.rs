@@ -8,6 +8,8 @@ use std::{collections::VecDeque, sync::Arc};
 pub(crate) struct JobBroker<Job> {
     /// Get notified when there is a new job to handle.
     has_new_jobs: Arc<Condvar>,
+    /// Get notified when the number of threads working on jobs changes during a pause.
+    pause_changed: Arc<Condvar>,
     /// The market that we share.
     market: Arc<Mutex<JobMarket<Job>>>,
 }
@@ -16,6 +18,7 @@ impl<Job> Clone for JobBroker<Job> {
     fn clone(&self) -> Self {
         Self {
             has_new_jobs: Arc::clone(&self.has_new_jobs),
+            pause_changed: Arc::clone(&self.pause_changed),
             market: Arc::clone(&self.market),
         }
     }
@@ -32,6 +35,7 @@ impl<Job> Drop for JobBroker<Job> {
         market.job_batches.clear();
         market.open_count = market.open_count.saturating_sub(1);
         self.has_new_jobs.notify_all();
+        self.pause_changed.notify_all();
     }
 }
 
@@ -44,6 +48,10 @@ struct JobMarket<Job> {
     open_count: usize,
     /// Jobs available.
     job_batches: Vec<VecDeque<Job>>,
+    /// Whether a thread is waiting for the others to pause.
+    pause_requested: bool,
+    /// Number of threads that have paused.
+    paused_count: usize,
 }
 
 impl<Job> JobBroker<Job> {
@@ -51,11 +59,14 @@ impl<Job> JobBroker<Job> {
     pub fn new(thread_count: usize) -> Self {
         Self {
             has_new_jobs: Arc::new(Condvar::new()),
+            pause_changed: Arc::new(Condvar::new()),
             market: Arc::new(Mutex::new(JobMarket {
                 open: true,
                 thread_count,
                 open_count: thread_count,
                 job_batches: Vec::new(),
+                pause_requested: false,
+                paused_count: 0,
             })),
         }
     }
@@ -69,7 +80,13 @@ impl<Job> JobBroker<Job> {
             return VecDeque::new();
         }
         loop {
-            if let Some(jobs) = market.job_batches.pop() {
+            // Jobs are left alone during a pause, as they are being gathered.
+            let jobs = if market.pause_requested {
+                None
+            } else {
+                market.job_batches.pop()
+            };
+            if let Some(jobs) = jobs {
                 log::trace!(
                     "{}: Got jobs. Working.",
                     std::thread::current().name().unwrap_or_default()
@@ -89,6 +106,9 @@ impl<Job> JobBroker<Job> {
                     market.open = false;
                     return VecDeque::new();
                 }
+                if market.pause_requested {
+                    self.pause_changed.notify_all();
+                }
                 log::trace!(
                     "{}: No jobs. Awaiting. running={}",
                     std::thread::current().name().unwrap_or_default(),
@@ -146,6 +166,46 @@ impl<Job> JobBroker<Job> {
         }
     }
 
+    /// Whether a thread is waiting in [`JobBroker::pause`] for the others to pause.
+    pub fn is_pause_requested(&self) -> bool {
+        self.market.lock().pause_requested
+    }
+
+    /// Hands the caller's jobs back to the market and waits until every thread that is working on
+    /// jobs has done likewise, at which point the last of them calls `on_paused` with all the jobs
+    /// that remain to be done. Other threads should call this method once
+    /// [`JobBroker::is_pause_requested`] indicates a pause.
+    pub fn pause(&mut self, jobs: &mut VecDeque<Job>, on_paused: impl FnOnce(&[VecDeque<Job>])) {
+        let mut market = self.market.lock();
+        if !market.open {
+            jobs.clear();
+            return;
+        }
+        if !jobs.is_empty() {
+            market.job_batches.push(std::mem::take(jobs));
+        }
+        market.pause_requested = true;
+        market.paused_count += 1;
+        log::trace!(
+            "{}: Paused. paused={} running={}",
+            std::thread::current().name().unwrap_or_default(),
+            market.paused_count,
+            market.open_count
+        );
+        let mut on_paused = Some(on_paused);
+        while market.open && market.pause_requested {
+            if market.paused_count == market.open_count {
+                (on_paused.take().unwrap())(&market.job_batches);
+                market.pause_requested = false;
+                market.paused_count = 0;
+                self.pause_changed.notify_all();
+                self.has_new_jobs.notify_all();
+                break;
+            }
+            self.pause_changed.wait(&mut market);
+        }
+    }
+
     /// See whether the market is closed.
     pub fn is_closed(&self) -> bool {
         let market = self.market.lock();
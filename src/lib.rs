@@ -138,11 +138,13 @@ mod job_market;
 pub mod report;
 use std::fmt::Debug;
 use std::hash::{Hash, Hasher};
+use std::sync::Arc;
 
 #[cfg(test)]
 mod test_util;
 
 pub mod actor;
+pub mod combinators;
 pub use checker::*;
 pub mod semantics;
 pub mod util;
@@ -227,11 +229,11 @@ pub trait Model: Sized {
     }
 
     /// Looks up a property by name. Panics if the property does not exist.
-    fn property(&self, name: &'static str) -> Property<Self> {
+    fn property(&self, name: &str) -> Property<Self> {
         if let Some(p) = self.properties().into_iter().find(|p| p.name == name) {
             p
         } else {
-            let available: Vec<_> = self.properties().iter().map(|p| p.name).collect();
+            let available: Vec<_> = self.properties().into_iter().map(|p| p.name).collect();
             panic!(
                 "Unknown property. requested={}, available={:?}",
                 name, available
@@ -244,6 +246,36 @@ pub trait Model: Sized {
         true
     }
 
+    /// Indicates whether a state without next states is an acceptable place for the system to
+    /// stop, such as when every request has been served, rather than a deadlock. Only consulted
+    /// when [`CheckerBuilder::deadlock_detection`] is enabled. Defaults to `false`.
+    fn valid_end_state(&self, _state: &Self::State) -> bool {
+        false
+    }
+
+    /// Indicates the [`Fairness`] constraint (if any) that infinite behaviors must satisfy with
+    /// respect to an action, along with an identifier for the action that remains the same across
+    /// states (such as a hash of the action). An action is considered enabled in a state if it
+    /// leads to a next state within the boundary.
+    ///
+    /// Only [`CheckerBuilder::spawn_liveness`] considers fairness, discarding unfair lasso shaped
+    /// behaviors when checking `eventually` and `ltl` properties.
+    fn fairness(&self, _action: &Self::Action) -> Option<(Fairness, u64)> {
+        None
+    }
+
+    /// Indicates whether two actions are independent in a state, meaning that neither disables
+    /// the other, that taking them in either order reaches the same state, and that the state in
+    /// between satisfies the same property conditions either way. The model checker may also ask
+    /// about an action that only becomes enabled later, so this should only return `true` if
+    /// this holds whenever both actions are enabled.
+    ///
+    /// Used for partial order reduction by [`CheckerBuilder::spawn_dpor`] and
+    /// [`CheckerBuilder::partial_order_reduction`]. Defaults to `false`, which is always safe.
+    fn independent(&self, _state: &Self::State, _a1: &Self::Action, _a2: &Self::Action) -> bool {
+        false
+    }
+
     /// Instantiates a [`CheckerBuilder`] for this model.
     fn checker(self) -> CheckerBuilder<Self>
     where
@@ -258,21 +290,60 @@ pub trait Model: Sized {
 /// checker would find an example) or "an epoch *always* has at most one leader" (for which the
 /// model checker would find a counterexample) or "a proposal is *eventually* accepted" (for
 /// which the model checker would find a counterexample path leading from the initial state
-/// through to a terminal state).
+/// through to a terminal state). More general requirements can be expressed as [`Ltl`] formulas
+/// via [`Property::ltl`].
+///
+/// Names are owned and conditions can be closures, so properties can be generated at runtime,
+/// such as one per key of a map or with a threshold read from the command line:
+///
+/// ```rust
+/// # use stateright::*;
+/// # struct M;
+/// # impl Model for M {
+/// #     type State = Vec<u8>;
+/// #     type Action = ();
+/// #     fn init_states(&self) -> Vec<Self::State> { vec![vec![0, 0]] }
+/// #     fn actions(&self, _: &Self::State, _: &mut Vec<Self::Action>) {}
+/// #     fn next_state(&self, _: &Self::State, _: Self::Action) -> Option<Self::State> { None }
+/// # }
+/// let max = 3;
+/// let properties: Vec<Property<M>> = (0..2)
+///     .map(|i| {
+///         Property::always(format!("counter {} at most {}", i, max), move |_, s: &Vec<u8>| {
+///             s[i] <= max
+///         })
+///     })
+///     .collect();
+/// assert_eq!(properties[1].name, "counter 1 at most 3");
+/// ```
 pub struct Property<M: Model> {
     pub expectation: Expectation,
-    pub name: &'static str,
-    pub condition: fn(&M, &M::State) -> bool,
+    pub name: String,
+    /// The condition on each state, which is `None` for [`Expectation::Ltl`] and
+    /// [`Expectation::AlwaysStep`] properties.
+    #[allow(clippy::type_complexity)]
+    pub condition: Option<Arc<dyn Fn(&M, &M::State) -> bool + Send + Sync>>,
+    /// The formula for an [`Expectation::Ltl`] property.
+    pub formula: Option<Ltl<M>>,
+    /// The condition on each step for an [`Expectation::AlwaysStep`] property.
+    #[allow(clippy::type_complexity)]
+    pub step_condition:
+        Option<Arc<dyn Fn(&M, &M::State, &M::Action, &M::State) -> bool + Send + Sync>>,
 }
 impl<M: Model> Property<M> {
     /// An invariant that defines a [safety
     /// property](https://en.wikipedia.org/wiki/Safety_property). The model checker will try to
     /// discover a counterexample.
-    pub fn always(name: &'static str, condition: fn(&M, &M::State) -> bool) -> Property<M> {
+    pub fn always(
+        name: impl Into<String>,
+        condition: impl Fn(&M, &M::State) -> bool + Send + Sync + 'static,
+    ) -> Property<M> {
         Property {
             expectation: Expectation::Always,
-            name,
-            condition,
+            name: name.into(),
+            condition: Some(Arc::new(condition)),
+            formula: None,
+            step_condition: None,
         }
     }
 
@@ -285,37 +356,95 @@ impl<M: Model> Property<M> {
     /// paths (those that end in either states with no successors or checking boundaries). A path
     /// ending in a cycle is not viewed as _terminating_ in that cycle, as the checker does not
     /// differentiate cycles from DAG joins, and so an `eventually` property that has not been met
-    /// by the cycle-closing edge will ignored -- a false negative.
-    pub fn eventually(name: &'static str, condition: fn(&M, &M::State) -> bool) -> Property<M> {
+    /// by the cycle-closing edge will ignored -- a false negative. Similarly a state reached via
+    /// two paths is only explored for the first path unless
+    /// [`CheckerBuilder::track_eventually_bits`] is enabled. [`CheckerBuilder::spawn_liveness`]
+    /// addresses both limitations, reporting cycles as lasso shaped [`Path`]s.
+    pub fn eventually(
+        name: impl Into<String>,
+        condition: impl Fn(&M, &M::State) -> bool + Send + Sync + 'static,
+    ) -> Property<M> {
         Property {
             expectation: Expectation::Eventually,
-            name,
-            condition,
+            name: name.into(),
+            condition: Some(Arc::new(condition)),
+            formula: None,
+            step_condition: None,
         }
     }
 
     /// Something that should be possible in the model. The model checker will try to discover an
     /// example.
-    pub fn sometimes(name: &'static str, condition: fn(&M, &M::State) -> bool) -> Property<M> {
+    pub fn sometimes(
+        name: impl Into<String>,
+        condition: impl Fn(&M, &M::State) -> bool + Send + Sync + 'static,
+    ) -> Property<M> {
         Property {
             expectation: Expectation::Sometimes,
-            name,
-            condition,
+            name: name.into(),
+            condition: Some(Arc::new(condition)),
+            formula: None,
+            step_condition: None,
+        }
+    }
+
+    /// A [linear temporal logic](https://en.wikipedia.org/wiki/Linear_temporal_logic) formula
+    /// that must hold for every behavior of the model, such as
+    /// `G (request -> F response)`. The model checker will try to discover a counterexample,
+    /// which is either a path to a terminal state (after which the behavior is viewed as remaining
+    /// in that state forever) or a lasso shaped [`Path`] that repeats a loop forever.
+    ///
+    /// `ltl` properties are only checked by [`CheckerBuilder::spawn_liveness`]. Other checkers
+    /// ignore them.
+    pub fn ltl(name: impl Into<String>, formula: Ltl<M>) -> Property<M> {
+        Property {
+            expectation: Expectation::Ltl,
+            name: name.into(),
+            condition: None,
+            formula: Some(formula),
+            step_condition: None,
+        }
+    }
+
+    /// An invariant over each step between states, such as "a committed index never decreases",
+    /// which is given the state before the step, the action taken, and the state after it. The
+    /// model checker will try to discover a counterexample path that ends with a violating step.
+    pub fn always_step(
+        name: impl Into<String>,
+        condition: impl Fn(&M, &M::State, &M::Action, &M::State) -> bool + Send + Sync + 'static,
+    ) -> Property<M> {
+        Property {
+            expectation: Expectation::AlwaysStep,
+            name: name.into(),
+            condition: None,
+            formula: None,
+            step_condition: Some(Arc::new(condition)),
         }
     }
+
+    /// Indicates whether a state satisfies the property's `condition`. Properties without one
+    /// hold in every state.
+    pub(crate) fn holds(&self, model: &M, state: &M::State) -> bool {
+        self.condition
+            .as_ref()
+            .is_none_or(|condition| condition(model, state))
+    }
 }
 impl<M: Model> Clone for Property<M> {
     fn clone(&self) -> Self {
         Property {
             expectation: self.expectation.clone(),
-            name: self.name,
-            condition: self.condition,
+            name: self.name.clone(),
+            condition: self.condition.clone(),
+            formula: self.formula.clone(),
+            step_condition: self.step_condition.clone(),
         }
     }
 }
 
 /// Indicates whether a property is always, eventually, or sometimes true.
 #[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd, serde::Deserialize, serde::Serialize)]
+#[non_exhaustive]
 pub enum Expectation {
     /// The property is true for all reachable states.
     Always,
@@ -323,6 +452,19 @@ pub enum Expectation {
     Eventually,
     /// The property is true for at least one reachable state.
     Sometimes,
+    /// The property's [`Ltl`] formula is true for all behavior paths.
+    Ltl,
+    /// The property is true for all steps between reachable states.
+    AlwaysStep,
+}
+
+/// Indicates which infinite behaviors treat an action fairly. See [`Model::fairness`].
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Fairness {
+    /// An action that is continuously enabled from some point onward is eventually taken.
+    Weak,
+    /// An action that is enabled infinitely often is taken infinitely often.
+    Strong,
 }
 
 /// A state identifier. See [`fingerprint`].
@@ -4,7 +4,7 @@ use std::hash::Hash;
 use std::io::Write;
 use std::time::Duration;
 
-use crate::{DiscoveryClassification, Model, Path};
+use crate::{DiscoveryClassification, Limit, Model, Path};
 
 /// The data sent during a report event.
 pub struct ReportData {
@@ -14,6 +14,12 @@ pub struct ReportData {
     pub unique_states: usize,
     /// Maximum depth explored.
     pub max_depth: usize,
+    /// The estimated probability that a newly reached state was omitted, if checking is
+    /// approximate. See [`Checker::omission_probability`](crate::Checker::omission_probability).
+    pub omission_probability: Option<f64>,
+    /// The limit that ended checking, if any. See
+    /// [`Checker::limit_reached`](crate::Checker::limit_reached).
+    pub limit_reached: Option<Limit>,
     /// The current duration checking has been running for.
     pub duration: Duration,
     /// Whether checking is done.
@@ -29,6 +35,9 @@ where
     pub path: Path<M::State, M::Action>,
     /// The classification of the path.
     pub classification: DiscoveryClassification,
+    /// The seed of the simulation trace or swarm search that led to the discovery, if known. See
+    /// [`Checker::discovery_seed`](crate::Checker::discovery_seed).
+    pub seed: Option<u64>,
 }
 
 /// A reporter for progress during the model checking.
@@ -37,7 +46,7 @@ pub trait Reporter<M: Model> {
     fn report_checking(&mut self, data: ReportData);
 
     /// Report the discoveries at the end of the checking run.
-    fn report_discoveries(&mut self, discoveries: BTreeMap<&'static str, ReportDiscovery<M>>)
+    fn report_discoveries(&mut self, discoveries: BTreeMap<String, ReportDiscovery<M>>)
     where
         M::Action: Debug,
         M::State: Debug + Hash;
@@ -64,7 +73,7 @@ where
 {
     fn report_checking(&mut self, data: ReportData) {
         if data.done {
-            let _ = writeln!(
+            let _ = write!(
                 self.writer,
                 "Done. states={}, unique={}, depth={}, sec={}",
                 data.total_states,
@@ -72,6 +81,17 @@ where
                 data.max_depth,
                 data.duration.as_secs(),
             );
+            if let Some(omission_probability) = data.omission_probability {
+                let _ = write!(
+                    self.writer,
+                    ", omission_probability={:e}",
+                    omission_probability
+                );
+            }
+            if let Some(limit) = data.limit_reached {
+                let _ = write!(self.writer, ", limit={}", limit);
+            }
+            let _ = writeln!(self.writer);
         } else {
             let _ = writeln!(
                 self.writer,
@@ -81,7 +101,7 @@ where
         }
     }
 
-    fn report_discoveries(&mut self, discoveries: BTreeMap<&'static str, ReportDiscovery<M>>)
+    fn report_discoveries(&mut self, discoveries: BTreeMap<String, ReportDiscovery<M>>)
     where
         M::Action: Debug,
         M::State: Debug + Hash,
@@ -93,6 +113,9 @@ where
                 name, discovery.classification, discovery.path,
             );
             let _ = writeln!(self.writer, "Fingerprint path: {}", discovery.path.encode());
+            if let Some(seed) = discovery.seed {
+                let _ = writeln!(self.writer, "Seed: {}", seed);
+            }
         }
     }
 }
@@ -46,6 +46,86 @@ pub mod binary_clock {
     }
 }
 
+/// A counter that increments up to a maximum, optionally with extra jumps between values.
+pub mod counter {
+    use crate::*;
+
+    #[derive(Clone)]
+    pub struct Counter {
+        max: u8,
+        jumps: Vec<(&'static str, u8, u8)>,
+        valid_end_states: Vec<u8>,
+        properties: Vec<Property<Counter>>,
+    }
+
+    impl Counter {
+        /// Counts from zero to `max` via `"increment"` actions.
+        pub fn new(max: u8) -> Self {
+            Counter {
+                max,
+                jumps: Vec::new(),
+                valid_end_states: Vec::new(),
+                properties: Vec::new(),
+            }
+        }
+
+        /// Adds an action named `name` that moves the counter from `src` to `dst`.
+        pub fn with_jump(mut self, name: &'static str, src: u8, dst: u8) -> Self {
+            self.jumps.push((name, src, dst));
+            self
+        }
+
+        /// Treats `state` as a valid end state for deadlock detection.
+        pub fn with_valid_end_state(mut self, state: u8) -> Self {
+            self.valid_end_states.push(state);
+            self
+        }
+
+        pub fn with_property(mut self, property: Property<Counter>) -> Self {
+            self.properties.push(property);
+            self
+        }
+    }
+
+    impl Model for Counter {
+        type State = u8;
+        type Action = &'static str;
+
+        fn init_states(&self) -> Vec<Self::State> {
+            vec![0]
+        }
+
+        fn actions(&self, state: &Self::State, actions: &mut Vec<Self::Action>) {
+            if *state < self.max {
+                actions.push("increment");
+            }
+            for &(name, src, _) in &self.jumps {
+                if src == *state {
+                    actions.push(name);
+                }
+            }
+        }
+
+        fn next_state(&self, state: &Self::State, action: Self::Action) -> Option<Self::State> {
+            if action == "increment" {
+                return Some(state + 1);
+            }
+            self.jumps
+                .iter()
+                .find(|&&(name, src, _)| name == action && src == *state)
+                .map(|&(_, _, dst)| dst)
+        }
+
+        fn properties(&self) -> Vec<Property<Self>> {
+            self.properties.clone()
+        }
+
+        fn valid_end_state(&self, state: &Self::State) -> bool {
+            self.valid_end_states.contains(state)
+        }
+    }
+}
+
 /// A directed graph, specified via paths from initial states.
 pub mod dgraph {
     use crate::Checker;
@@ -56,6 +136,7 @@ pub mod dgraph {
     pub struct DGraph {
         inits: BTreeSet<u8>,
         edges: BTreeMap<u8, BTreeSet<u8>>,
+        fairness: BTreeMap<u8, Fairness>,
         property: Property<DGraph>,
     }
 
@@ -64,6 +145,7 @@ pub mod dgraph {
             DGraph {
                 inits: Default::default(),
                 edges: Default::default(),
+                fairness: Default::default(),
                 property,
             }
         }
@@ -82,10 +164,17 @@ pub mod dgraph {
                     }
                     self.edges
                 },
+                fairness: self.fairness,
                 property: self.property,
             }
         }
 
+        /// Constrains the edges to the specified state.
+        pub fn with_fairness(mut self, dst: u8, fairness: Fairness) -> Self {
+            self.fairness.insert(dst, fairness);
+            self
+        }
+
         pub fn check(self) -> impl Checker<Self> {
             self.checker().spawn_bfs().join()
         }
@@ -112,6 +201,10 @@ pub mod dgraph {
         fn properties(&self) -> Vec<Property<Self>> {
             vec![self.property.clone()]
         }
+
+        fn fairness(&self, action: &Self::Action) -> Option<(Fairness, u64)> {
+            self.fairness.get(action).map(|&f| (f, *action as u64))
+        }
     }
 }
 
@@ -226,3 +319,91 @@ pub mod panicker {
         }
     }
 }
+
+/// Two processes that are symmetric to one another.
+pub mod symmetric_processes {
+    use crate::actor::Id;
+    use crate::*;
+
+    #[derive(Clone, Debug, Eq, Hash, PartialEq)]
+    pub struct Sys;
+
+    #[derive(Clone, Debug, Eq, Hash, PartialEq)]
+    pub struct SysState(pub Vec<ProcState>);
+
+    #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    pub enum ProcState {
+        // A process advances from `Loading` to `Running` to cycling between `Paused` and
+        // `Running`. There is no way for a process to move from `Loading` to `Paused` or any
+        // non-`Loading` state to `Loading`, but a previous implementation of symmetry
+        // reduction would mistakenly collect a path with an invalid step because it would
+        // enqueue the representative of a state rather than the original state.
+        //
+        // Here is an example of the steps that would manifest that bug:
+        //
+        // 1. System starts: `[Loading, Loading]`
+        // 2. Second process advances: `[Loading, Running]`
+        // 3. Second process advances: `[Loading, Paused]`
+        //
+        // But in the third state above, the representative function swaps the process order
+        // because `Paused < Loading`, so the collected path becomes:
+        //
+        // 1. `[Loading, Loading]`
+        // 2. `[Loading, Running]`
+        // 3. `[Paused, Loading]`
+        //
+        // Both `Loading -> Loading -> Paused` (process 0) and `Loading -> Running -> Loading`
+        // (process 1) are invalid.
+        Paused,
+        Loading,
+        Running,
+    }
+
+    impl Model for Sys {
+        type Action = Id;
+        type State = SysState;
+
+        fn init_states(&self) -> Vec<SysState> {
+            vec![SysState(vec![ProcState::Loading, ProcState::Loading])]
+        }
+
+        fn actions(&self, _: &Self::State, actions: &mut Vec<Self::Action>) {
+            // Either process can run next.
+            actions.push(Id::from(0));
+            actions.push(Id::from(1));
+        }
+
+        fn next_state(&self, state: &Self::State, action: Self::Action) -> Option<Self::State> {
+            let i = usize::from(action);
+            let mut state = state.clone();
+            match state.0[i] {
+                ProcState::Loading => state.0[i] = ProcState::Running,
+                ProcState::Running => state.0[i] = ProcState::Paused,
+                ProcState::Paused => state.0[i] = ProcState::Running,
+            }
+            Some(state)
+        }
+
+        fn properties(&self) -> Vec<Property<Self>> {
+            vec![
+                Property::<Self>::always("visit all states", |_, _| true),
+                Property::<Self>::sometimes("a process pauses", |_, s| {
+                    s.0[0] == ProcState::Paused || s.0[1] == ProcState::Paused
+                }),
+            ]
+        }
+    }
+
+    impl Representative for SysState {
+        fn representative(&self) -> Self {
+            let plan = RewritePlan::from_values_to_sort(&self.0);
+            SysState(plan.reindex(&self.0))
+        }
+    }
+
+    impl Rewrite<Id> for ProcState {
+        fn rewrite<S>(&self, _: &RewritePlan<Id, S>) -> Self {
+            self.clone()
+        }
+    }
+}
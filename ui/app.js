@@ -1,5 +1,5 @@
 /// Represents the checker status. Reloads periodically until checking completes.
-function Status({done, state_count, unique_state_count, max_depth, model, properties, recent_path}) {
+function Status({done, paused, state_count, unique_state_count, max_depth, model, properties, recent_path}) {
     let status = this;
 
     status.stateCount = state_count.toLocaleString();
@@ -7,7 +7,9 @@ function Status({done, state_count, unique_state_count, max_depth, model, proper
     status.maxDepth = max_depth.toLocaleString();
     status.model = model.replace(/[0-9A-Za-z_]+::/g, '');
     status.progress = 'Done';
-    if (!done) {
+    if (paused && !done) {
+        status.progress = 'Paused';
+    } else if (!done) {
         status.progress = (recent_path || '').length < 100
             ? recent_path
             : recent_path.substring(0, 99 - 3) + '...';
@@ -18,6 +20,7 @@ function Status({done, state_count, unique_state_count, max_depth, model, proper
 /// Placeholder status.
 Status.LOADING = new Status({
     done: 'loading...',
+    paused: false,
     state_count: 'loading...',
     unique_state_count: 'loading...',
     max_depth: 'loading...',
@@ -26,6 +29,12 @@ Status.LOADING = new Status({
     recent_path: 'loading...',
 });
 
+/// Indicates whether an encoded path is a lasso, i.e. its last state repeats an earlier state.
+function isLasso(encodedPath) {
+    let fingerprints = encodedPath.split('/');
+    return fingerprints.indexOf(fingerprints[fingerprints.length - 1]) < fingerprints.length - 1;
+}
+
 function getProperty(p, done) {
     let expectation = p[0];
     let discoveryPath = p[2];
@@ -33,21 +42,28 @@ function getProperty(p, done) {
         expectation,
         name: p[1],
         discoveryPath,
+        otherDiscoveryPaths: p[3] || [],
         summary: (() => {
             if (discoveryPath) {
                 switch (expectation) {
-                    case 'Always':     return '⚠️ Counterexample found: ';
+                    case 'Always':
+                    case 'AlwaysStep': return '⚠️ Counterexample found: ';
                     case 'Sometimes':  return '✅ Example found: ';
-                    case 'Eventually': return '⚠️ Counterexample found: ';
+                    case 'Eventually':
+                    case 'Ltl':        return isLasso(discoveryPath)
+                        ? '⚠️ Counterexample loop found: '
+                        : '⚠️ Counterexample found: ';
                     default:
                         throw new Error(`Invalid expectation ${expectation}.`);
                 }
             } else {
                 if (!done) { return '🔎 Searching: ' };
                 switch (expectation) {
-                    case 'Always':     return '✅ Safety holds: ';
+                    case 'Always':
+                    case 'AlwaysStep': return '✅ Safety holds: ';
                     case 'Sometimes':  return '⚠️ Example not found: ';
                     case 'Eventually': return '✅ Liveness holds: ';
+                    case 'Ltl':        return '✅ Formula holds: ';
                     default:
                         throw new Error(`Invalid expectation ${expectation}.`);
                 }
@@ -78,18 +94,22 @@ function getPropertyForState(p, path) {
                 return ['⬇️', ''];
             } else {
                 switch (expectation) {
-                    case 'Always': return [ '⚠️',' Counterexample found: ' ];
+                    case 'Always':
+                    case 'AlwaysStep': return [ '⚠️',' Counterexample found: ' ];
                     case 'Sometimes':  return [ '✅', ' Example found: ' ];
                     case 'Eventually': return [ '⚠️', ' Counterexample found: ' ];
+                    case 'Ltl':        return [ '⚠️', ' Counterexample found: ' ];
                     default:
                         throw new Error(`Invalid expectation ${expectation}.`);
                 }
             }
         } else {
             switch (expectation) {
-                case 'Always':     return [ '✅', ' Safety holds: ' ];
+                case 'Always':
+                case 'AlwaysStep': return [ '✅', ' Safety holds: ' ];
                 case 'Sometimes':  return [ '⚠️', ' Example not found: ' ];
                 case 'Eventually': return [ '✅', ' Liveness holds: ' ];
+                case 'Ltl':        return [ '✅', ' Formula holds: ' ];
                 default:
                     throw new Error(`Invalid expectation ${expectation}.`);
             }
@@ -107,13 +127,14 @@ function getPropertyForState(p, path) {
 
 
 /// Represents a model step. Only loads next steps on demand.
-function Step({action, outcome, state, fingerprint, properties, prevStep, svg}) {
+function Step({action, outcome, state, fingerprint, properties, prevStep, svg, deadlock}) {
     let step = this;
 
     step.action = action || `Init ${i}`;
     step.outcome = outcome;
     step.state = state;
     step.svg = svg;
+    step.deadlock = !!deadlock;
     step.fingerprint = fingerprint;
     step.prevStep = prevStep;
 
@@ -123,6 +144,15 @@ function Step({action, outcome, state, fingerprint, properties, prevStep, svg})
     step.icons = step.properties.map((p) => { return p.icon }).join(' ')
 
     step.pathSteps = () => (prevStep ? prevStep.pathSteps() : []).concat([step]);
+    /// The index within `pathSteps` of the state that this step returns to, if the path is a lasso.
+    step.loopStart = () => {
+        let index = step.pathSteps().findIndex(s => s.fingerprint == step.fingerprint);
+        return index < step.pathSteps().length - 1 ? index : null;
+    };
+    step.isLoopStep = (index) => {
+        let loopStart = step.loopStart();
+        return loopStart !== null && index > loopStart;
+    };
     step.nextSteps = ko.observableArray();
     step.computeOffsetTo = (dstStep) => {
         let offset = 0;
@@ -155,6 +185,7 @@ function Step({action, outcome, state, fingerprint, properties, prevStep, svg})
                     svg: nextStep.svg,
                     fingerprint: nextStep.fingerprint,
                     properties: nextStep.properties,
+                    deadlock: nextStep.deadlock,
                     prevStep: step,
                 }));
             })
@@ -278,5 +309,11 @@ async function runToCompletion() {
     console.log(response);
 }
 
+async function control(request) {
+    console.log(`${request} checker`);
+    let response = await fetch(`/.${request}`, {method:'POST'});
+    console.log(response);
+}
+
 window.app = new App();
 ko.applyBindings(window.app);